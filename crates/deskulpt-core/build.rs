@@ -3,16 +3,29 @@ fn main() {
         .commands(&[
             "bundle_widgets",
             "call_plugin",
+            "check_widget_updates",
+            "create_widget",
+            "get_bundle_report",
+            "install_widget",
+            "list_installed",
             "open_widget",
             "rescan_widgets",
             "set_render_ready",
+            "subscribe_metric",
+            "uninstall_widget",
+            "unsubscribe_metric",
             "update_settings",
+            "update_widget",
         ])
         .events(&[
+            "BundleProgressEvent",
+            "MetricUpdateEvent",
+            "PluginEvent",
             "RenderWidgetsEvent",
             "ShowToastEvent",
             "UpdateSettingsEvent",
-            "UpdateWidgetCatalogEvent",
+            "WidgetCatalogDiffEvent",
+            "WidgetUpdatesEvent",
         ])
         .build();
 }
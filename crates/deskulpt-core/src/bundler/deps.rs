@@ -0,0 +1,277 @@
+//! Offline resolution of widget dependencies.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use semver::{Version, VersionReq};
+use serde::Deserialize;
+
+/// The subset of an installed package's `package.json` that we care about.
+#[derive(Deserialize)]
+struct InstalledPackageJson {
+    version: String,
+}
+
+/// An npm-style version range, e.g. `^1.2.0 || >=2.1.0 <3`.
+///
+/// npm ranges are a union (`||`) of intersections (whitespace-separated) of
+/// comparators, which we translate into [`VersionReq`]s. Operators may be
+/// separated from their versions by whitespace, e.g. `>= 1.2.0`, and hyphen
+/// ranges, e.g. `1.0.0 - 2.0.0`, are inclusive on both ends. Notably, a bare
+/// version means an exact match in npm but a caret match in Cargo, so it is
+/// explicitly prefixed with `=`. Non-registry specifiers (e.g. `file:` or git
+/// URLs) are not supported.
+struct VersionRange(Vec<VersionReq>);
+
+impl VersionRange {
+    /// Parse an npm-style version range.
+    fn parse(spec: &str) -> Result<Self> {
+        let spec = spec.trim();
+        if spec.is_empty() || spec == "latest" {
+            return Ok(Self(vec![VersionReq::STAR]));
+        }
+
+        spec.split("||")
+            .map(parse_comparators)
+            .collect::<Result<Vec<_>>>()
+            .map(Self)
+            .with_context(|| format!("Unsupported version range: {spec:?}"))
+    }
+
+    /// Check whether a version satisfies the range.
+    fn matches(&self, version: &Version) -> bool {
+        self.0.iter().any(|req| req.matches(version))
+    }
+}
+
+/// Parse a whitespace-separated intersection of npm comparators.
+fn parse_comparators(set: &str) -> Result<VersionReq> {
+    let strip_v = |version: &str| version.strip_prefix('v').unwrap_or(version).to_string();
+
+    let tokens = set.split_whitespace().collect::<Vec<_>>();
+    if let [low, "-", high] = tokens[..] {
+        let req = format!(">={}, <={}", strip_v(low), strip_v(high));
+        return Ok(VersionReq::parse(&req)?);
+    }
+
+    let mut comparators = vec![];
+    let mut op = String::new();
+    for token in tokens {
+        let (prefix, version) = token.split_at(
+            token
+                .find(|ch: char| !matches!(ch, '<' | '>' | '=' | '~' | '^'))
+                .unwrap_or(token.len()),
+        );
+        op.push_str(prefix);
+        // The operator is separated from its version by whitespace
+        if version.is_empty() {
+            continue;
+        }
+        let version = strip_v(version);
+        if op.is_empty() && version.starts_with(|ch: char| ch.is_ascii_digit()) {
+            op.push('=');
+        }
+        comparators.push(format!("{op}{version}"));
+        op.clear();
+    }
+    if !op.is_empty() {
+        bail!("Operator {op:?} is not followed by a version");
+    }
+    Ok(VersionReq::parse(&comparators.join(", "))?)
+}
+
+/// Read the version of an installed package.
+///
+/// This returns `Ok(None)` if the directory does not contain a `package.json`,
+/// i.e., the package is not installed there.
+fn installed_version(dir: &Path) -> Result<Option<Version>> {
+    let path = dir.join("package.json");
+    if !path.exists() {
+        return Ok(None);
+    }
+    let file = File::open(&path)?;
+    let package_json: InstalledPackageJson = serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let version = Version::parse(&package_json.version)
+        .with_context(|| format!("Invalid version in {}", path.display()))?;
+    Ok(Some(version))
+}
+
+/// Outcome of looking up a dependency in a single location.
+enum Lookup {
+    /// A satisfying version is installed in the given directory.
+    Found(PathBuf),
+    /// Some versions are installed but none of them satisfy the range.
+    Mismatch(Vec<(Version, PathBuf)>),
+    /// The dependency is not installed at all.
+    Missing,
+}
+
+/// Look up a dependency in the `node_modules` directory of the widget.
+fn lookup_node_modules(root: &Path, name: &str, range: &VersionRange) -> Result<Lookup> {
+    let dir = root.join("node_modules").join(name);
+    Ok(match installed_version(&dir)? {
+        Some(version) if range.matches(&version) => Lookup::Found(dir),
+        Some(version) => Lookup::Mismatch(vec![(version, dir)]),
+        None => Lookup::Missing,
+    })
+}
+
+/// Look up a dependency in the shared package cache.
+///
+/// The cache stores each version of a package in its own directory, i.e.,
+/// `<packages_dir>/<name>/<version>/`. If multiple versions satisfy the range,
+/// the highest one is picked.
+fn lookup_packages_dir(packages_dir: &Path, name: &str, range: &VersionRange) -> Result<Lookup> {
+    let dir = packages_dir.join(name);
+    if !dir.is_dir() {
+        return Ok(Lookup::Missing);
+    }
+
+    let mut installed = vec![];
+    for entry in std::fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if let Some(version) = installed_version(&path)? {
+            installed.push((version, path));
+        }
+    }
+
+    let best = installed
+        .iter()
+        .filter(|(version, _)| range.matches(version))
+        .max_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(match best {
+        Some((_, path)) => Lookup::Found(path.clone()),
+        None if installed.is_empty() => Lookup::Missing,
+        None => Lookup::Mismatch(installed),
+    })
+}
+
+/// Resolve the declared dependencies of a widget to package directories.
+///
+/// Each dependency is first looked up in the `node_modules` directory of the
+/// widget and then in the shared package cache if given. Nothing is ever
+/// downloaded. All dependencies are checked before returning so that the error,
+/// if any, lists every missing or mismatched dependency at once.
+///
+/// Dependencies whose names are in `skip` are not resolved; this is meant for
+/// packages that are provided by Deskulpt at runtime.
+pub fn resolve_dependencies(
+    root: &Path,
+    packages_dir: Option<&Path>,
+    dependencies: &HashMap<String, String>,
+    skip: &[&str],
+) -> Result<Vec<(String, PathBuf)>> {
+    let mut resolved = vec![];
+    let mut errors = vec![];
+
+    let mut dependencies = dependencies
+        .iter()
+        .filter(|(name, _)| !skip.contains(&name.as_str()))
+        .collect::<Vec<_>>();
+    dependencies.sort();
+
+    for (name, spec) in dependencies {
+        let result = VersionRange::parse(spec).and_then(|range| {
+            let mut lookup = lookup_node_modules(root, name, &range)?;
+            if !matches!(lookup, Lookup::Found(_)) {
+                if let Some(packages_dir) = packages_dir {
+                    match lookup_packages_dir(packages_dir, name, &range)? {
+                        Lookup::Found(path) => lookup = Lookup::Found(path),
+                        Lookup::Mismatch(mut installed) => match &mut lookup {
+                            Lookup::Mismatch(prev) => prev.append(&mut installed),
+                            _ => lookup = Lookup::Mismatch(installed),
+                        },
+                        Lookup::Missing => {},
+                    }
+                }
+            }
+            Ok(lookup)
+        });
+
+        match result {
+            Ok(Lookup::Found(path)) => resolved.push((name.clone(), path)),
+            Ok(Lookup::Mismatch(installed)) => {
+                let found = installed
+                    .iter()
+                    .map(|(version, path)| format!("{version} at {}", path.display()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                errors.push(format!(
+                    "Dependency {name:?} has a version mismatch: declared {spec:?}, found {found}"
+                ));
+            },
+            Ok(Lookup::Missing) => errors.push(format!(
+                "Dependency {name:?} (declared {spec:?}) is missing: not found in node_modules{}",
+                packages_dir
+                    .map(|dir| format!(" or in the package cache at {}", dir.display()))
+                    .unwrap_or_default()
+            )),
+            Err(e) => errors.push(format!("Dependency {name:?}: {e:?}")),
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!(
+            "Failed to resolve widget dependencies:\n\n{}",
+            errors.join("\n")
+        );
+    }
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(spec: &str, version: &str) -> bool {
+        VersionRange::parse(spec)
+            .unwrap()
+            .matches(&Version::parse(version).unwrap())
+    }
+
+    #[test]
+    fn test_bare_version_is_exact() {
+        assert!(matches("1.2.3", "1.2.3"));
+        assert!(!matches("1.2.3", "1.2.4"));
+        assert!(matches("v1.2.3", "1.2.3"));
+    }
+
+    #[test]
+    fn test_operator_separated_by_whitespace() {
+        assert!(matches(">= 1.2.0", "1.2.0"));
+        assert!(matches(">= 1.2.0", "3.0.0"));
+        assert!(!matches(">= 1.2.0", "1.1.9"));
+        assert!(matches(">= 1.2.0 < 2", "1.9.9"));
+        assert!(!matches(">= 1.2.0 < 2", "2.0.0"));
+        assert!(matches("^ 1.2.0", "1.3.0"));
+    }
+
+    #[test]
+    fn test_hyphen_range() {
+        assert!(matches("1.0.0 - 2.0.0", "1.0.0"));
+        assert!(matches("1.0.0 - 2.0.0", "2.0.0"));
+        assert!(!matches("1.0.0 - 2.0.0", "2.0.1"));
+        assert!(!matches("1.0.0 - 2.0.0", "0.9.9"));
+        assert!(matches("1.2 - 2.3", "2.3.9"));
+        assert!(!matches("1.2 - 2.3", "2.4.0"));
+    }
+
+    #[test]
+    fn test_union() {
+        assert!(matches("^1.2.0 || >= 2.1.0 < 3", "1.5.0"));
+        assert!(matches("^1.2.0 || >= 2.1.0 < 3", "2.5.0"));
+        assert!(!matches("^1.2.0 || >= 2.1.0 < 3", "2.0.0"));
+        assert!(matches("1.0.0 - 1.1.0 || 2.0.0", "2.0.0"));
+    }
+
+    #[test]
+    fn test_unsupported_ranges() {
+        assert!(VersionRange::parse(">=").is_err());
+        assert!(VersionRange::parse("1.0.0 >=").is_err());
+        assert!(VersionRange::parse("file:../foo").is_err());
+    }
+}
@@ -1,31 +1,143 @@
 //! Bundler for Deskulpt widgets.
 
 mod alias;
+mod deps;
+mod report;
 
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use alias::AliasPlugin;
 use anyhow::{anyhow, bail, Result};
 use either::Either;
+pub use report::BundleReport;
+use report::ReportPlugin;
 use rolldown::{
     Bundler, BundlerOptions, BundlerTransformOptions, JsxOptions, OutputFormat, Platform,
-    RawMinifyOptions,
+    RawMinifyOptions, ResolveOptions,
 };
 use rolldown_common::Output;
 
+use crate::config::BuildConfig;
+
+/// Packages provided by Deskulpt at runtime.
+///
+/// Widgets may still declare these as dependencies for type checking purposes,
+/// but they are never resolved from disk and cannot be overridden.
+const RUNTIME_PACKAGES: &[&str] = &[
+    "@deskulpt-test/apis",
+    "@deskulpt-test/emotion",
+    "@deskulpt-test/raw-apis",
+    "@deskulpt-test/react",
+    "@deskulpt-test/ui",
+];
+
+/// Prefix of the placeholders that are replaced in the bundled code at runtime.
+const PLACEHOLDER_PREFIX: &str = "__DESKULPT_";
+
+/// Validate a widget-specific bundler configuration.
+///
+/// This makes sure that the configuration does not override the packages
+/// provided at runtime or the runtime placeholders, and that aliases do not
+/// point outside of the widget directory. All violations are reported at once.
+fn validate_build_config(config: &BuildConfig) -> Result<()> {
+    let mut errors = vec![];
+
+    for (key, path) in &config.alias {
+        let key = key.trim_end_matches('/');
+        if key.is_empty() {
+            errors.push("Alias keys cannot be empty".to_string());
+            continue;
+        }
+        let overrides_runtime = RUNTIME_PACKAGES.iter().any(|package| {
+            *package == key
+                || package.starts_with(&format!("{key}/"))
+                || key.starts_with(&format!("{package}/"))
+        });
+        if overrides_runtime || key.starts_with(PLACEHOLDER_PREFIX) {
+            errors.push(format!("Alias {key:?} overrides a core external"));
+        }
+        let escapes = Path::new(path).components().any(|c| {
+            matches!(
+                c,
+                Component::ParentDir | Component::RootDir | Component::Prefix(_)
+            )
+        });
+        if escapes {
+            errors.push(format!(
+                "Alias {key:?} must map to a relative path within the widget directory; got \
+                 {path:?}"
+            ));
+        }
+    }
+
+    for key in config.define.keys() {
+        if key.starts_with(PLACEHOLDER_PREFIX) {
+            errors.push(format!("Define {key:?} overrides a core external"));
+        }
+    }
+
+    if !errors.is_empty() {
+        bail!("Invalid build configuration:\n\n{}", errors.join("\n"));
+    }
+    Ok(())
+}
+
 /// Builder for the Deskulpt widget bundler.
 pub struct WidgetBundlerBuilder {
     /// Absolute path to the widget directory.
     root: PathBuf,
     /// Entry file relative to the widget directory.
     entry: String,
+    /// Dependencies declared in `package.json`, mapped to version ranges.
+    dependencies: HashMap<String, String>,
+    /// Shared package cache to resolve dependencies from.
+    packages_dir: Option<PathBuf>,
+    /// Widget-specific bundler configuration.
+    config: BuildConfig,
 }
 
 impl WidgetBundlerBuilder {
     /// Create a new widget bundler builder instance.
     pub fn new(root: PathBuf, entry: String) -> Self {
-        Self { root, entry }
+        Self {
+            root,
+            entry,
+            dependencies: Default::default(),
+            packages_dir: None,
+            config: Default::default(),
+        }
+    }
+
+    /// Set the dependencies of the widget.
+    ///
+    /// Bare imports of these dependencies are resolved offline, either from the
+    /// `node_modules` directory of the widget or from the shared package cache
+    /// set by [`packages_dir`](Self::packages_dir). Building the bundler fails
+    /// if any of them is missing or does not satisfy its version range.
+    pub fn dependencies(mut self, dependencies: HashMap<String, String>) -> Self {
+        self.dependencies = dependencies;
+        self
+    }
+
+    /// Set the shared package cache directory.
+    ///
+    /// See [`PathExt::packages_dir`](crate::path::PathExt::packages_dir) for
+    /// its layout.
+    pub fn packages_dir(mut self, packages_dir: PathBuf) -> Self {
+        self.packages_dir = Some(packages_dir);
+        self
+    }
+
+    /// Set the widget-specific bundler configuration.
+    ///
+    /// The configuration is merged over the defaults when building the bundler.
+    /// Building the bundler fails if the configuration attempts to override
+    /// the core externals.
+    pub fn config(mut self, config: BuildConfig) -> Self {
+        self.config = config;
+        self
     }
 
     /// Build the Deskulpt widget bundler.
@@ -36,22 +148,58 @@ impl WidgetBundlerBuilder {
         const UI_URL: &str = "__DESKULPT_BASE_URL__/gen/ui.js";
         const APIS_BLOB_URL: &str = "__DESKULPT_APIS_BLOB_URL__";
 
+        validate_build_config(&self.config)?;
+
+        // Custom aliases take precedence over dependencies so that widgets can
+        // still redirect a dependency to their own sources
+        let mut alias = self
+            .config
+            .alias
+            .iter()
+            .map(|(key, path)| {
+                let path = self.root.join(path.trim_end_matches('/'));
+                (
+                    key.trim_end_matches('/').to_string(),
+                    vec![Some(path.to_string_lossy().to_string())],
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Point bare imports of declared dependencies to their installed
+        // package directories
+        let resolved = deps::resolve_dependencies(
+            &self.root,
+            self.packages_dir.as_deref(),
+            &self.dependencies,
+            RUNTIME_PACKAGES,
+        )?;
+        alias.extend(
+            resolved
+                .into_iter()
+                .map(|(name, dir)| (name, vec![Some(dir.to_string_lossy().to_string())])),
+        );
+
         let bundler_options = BundlerOptions {
             input: Some(vec![self.entry.into()]),
-            cwd: Some(self.root),
+            cwd: Some(self.root.clone()),
             format: Some(OutputFormat::Esm),
             platform: Some(Platform::Browser),
-            minify: Some(RawMinifyOptions::Bool(true)),
+            minify: Some(RawMinifyOptions::Bool(self.config.minify.unwrap_or(true))),
             // Use automatic runtime for JSX transforms, which will refer to
-            // `@deskulpt-test/emotion/jsx-runtime`
+            // `@deskulpt-test/emotion/jsx-runtime` unless overridden
             transform: Some(BundlerTransformOptions {
                 jsx: Some(Either::Right(JsxOptions {
                     runtime: Some("automatic".to_string()),
-                    import_source: Some("@deskulpt-test/emotion".to_string()),
+                    import_source: Some(
+                        self.config
+                            .jsx_import_source
+                            .unwrap_or_else(|| "@deskulpt-test/emotion".to_string()),
+                    ),
                     ..Default::default()
                 })),
                 ..Default::default()
             }),
+            define: Some(self.config.define.into_iter().collect()),
             // Externalize default dependencies available at runtime
             external: Some(
                 vec![
@@ -63,40 +211,67 @@ impl WidgetBundlerBuilder {
                 ]
                 .into(),
             ),
+            resolve: Some(ResolveOptions {
+                alias: Some(alias),
+                ..Default::default()
+            }),
             ..Default::default()
         };
 
         // Alias the default dependencies to URLs resolvable at runtime
-        let alias_plugin = AliasPlugin(
-            [
-                (
-                    "@deskulpt-test/emotion/jsx-runtime".to_string(),
-                    JSX_RUNTIME_URL.to_string(),
-                ),
-                (
-                    "@deskulpt-test/raw-apis".to_string(),
-                    RAW_APIS_URL.to_string(),
-                ),
-                ("@deskulpt-test/react".to_string(), REACT_URL.to_string()),
-                ("@deskulpt-test/ui".to_string(), UI_URL.to_string()),
-                ("@deskulpt-test/apis".to_string(), APIS_BLOB_URL.to_string()),
-            ]
-            .into(),
-        );
+        let aliases: HashMap<_, _> = [
+            (
+                "@deskulpt-test/emotion/jsx-runtime".to_string(),
+                JSX_RUNTIME_URL.to_string(),
+            ),
+            (
+                "@deskulpt-test/raw-apis".to_string(),
+                RAW_APIS_URL.to_string(),
+            ),
+            ("@deskulpt-test/react".to_string(), REACT_URL.to_string()),
+            ("@deskulpt-test/ui".to_string(), UI_URL.to_string()),
+            ("@deskulpt-test/apis".to_string(), APIS_BLOB_URL.to_string()),
+        ]
+        .into();
+        let alias_plugin = AliasPlugin(aliases.clone());
+
+        // Record the import graph for the bundle report
+        let imports = Arc::new(Mutex::new(Default::default()));
+        let report_plugin = ReportPlugin {
+            root: self.root.clone(),
+            imports: Arc::clone(&imports),
+        };
 
-        let bundler = Bundler::with_plugins(bundler_options, vec![Arc::new(alias_plugin)])?;
-        Ok(WidgetBundler { bundler })
+        let bundler = Bundler::with_plugins(
+            bundler_options,
+            vec![Arc::new(alias_plugin), Arc::new(report_plugin)],
+        )?;
+        Ok(WidgetBundler {
+            bundler,
+            root: self.root,
+            aliases,
+            imports,
+        })
     }
 }
 
 /// The Deskulpt widget bundler.
 pub struct WidgetBundler {
     bundler: Bundler,
+    /// Absolute path to the widget directory.
+    root: PathBuf,
+    /// The alias map from default dependencies to URLs resolvable at runtime.
+    aliases: HashMap<String, String>,
+    /// The import graph recorded by [`ReportPlugin`] during bundling.
+    imports: Arc<Mutex<BTreeMap<String, Vec<String>>>>,
 }
 
 impl WidgetBundler {
     /// Bundle the widget into a single ESM code string.
-    pub async fn bundle(&mut self) -> Result<String> {
+    ///
+    /// This also returns a [`BundleReport`] describing the bundled code.
+    pub async fn bundle(&mut self) -> Result<(String, BundleReport)> {
+        self.imports.lock().unwrap().clear();
         let result = self.bundler.generate().await.map_err(|e| {
             anyhow!(e
                 .into_vec()
@@ -117,11 +292,23 @@ impl WidgetBundler {
             );
         }
 
+        let imports = std::mem::take(&mut *self.imports.lock().unwrap());
         let output = &result.assets[0];
-        let code = match output {
-            Output::Asset(asset) => asset.source.clone().try_into_string()?,
-            Output::Chunk(chunk) => chunk.code.clone(),
+        let (code, report) = match output {
+            Output::Asset(asset) => {
+                let code = asset.source.clone().try_into_string()?;
+                let report = BundleReport {
+                    size: code.len() as u32,
+                    imports,
+                    ..Default::default()
+                };
+                (code, report)
+            },
+            Output::Chunk(chunk) => (
+                chunk.code.clone(),
+                BundleReport::new(chunk, imports, &self.aliases, &self.root),
+            ),
         };
-        Ok(code)
+        Ok((code, report))
     }
 }
@@ -0,0 +1,116 @@
+//! Bundle reports for Deskulpt widgets.
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use rolldown::plugin::{HookNoopReturn, HookUsage, Plugin, PluginContext};
+use rolldown_common::{ModuleInfo, NormalModule, OutputChunk};
+use serde::{Deserialize, Serialize};
+
+/// Report of a widget bundle.
+///
+/// All module paths are relative to the widget directory. Modules outside the
+/// widget directory, e.g. dependencies resolved from the shared package cache,
+/// are relative paths starting with `..`. Externalized imports are kept as is.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleReport {
+    /// The size of the bundled code in bytes.
+    pub size: u32,
+    /// The size in bytes contributed by each module to the bundled code.
+    ///
+    /// Modules that are completely tree-shaken are not included.
+    pub modules: BTreeMap<String, u32>,
+    /// The import graph.
+    ///
+    /// This maps each module to the modules it imports, including externalized
+    /// imports, in the order they are imported.
+    pub imports: BTreeMap<String, Vec<String>>,
+    /// The imports externalized through the alias map.
+    ///
+    /// This maps each externalized import specifier to the URL it is aliased
+    /// to, which will be resolved at runtime.
+    pub externals: BTreeMap<String, String>,
+}
+
+impl BundleReport {
+    /// Create a bundle report from the output chunk and the import graph.
+    ///
+    /// `aliases` is the alias map used by the bundler, from which the imports
+    /// that are actually externalized are picked.
+    pub(super) fn new(
+        chunk: &OutputChunk,
+        imports: BTreeMap<String, Vec<String>>,
+        aliases: &HashMap<String, String>,
+        root: &Path,
+    ) -> Self {
+        let modules = chunk
+            .modules
+            .keys
+            .iter()
+            .zip(chunk.modules.values.iter())
+            .filter_map(|(id, module)| {
+                let size = module
+                    .code()
+                    .map(|code| code.len() as u32)
+                    .unwrap_or_default();
+                (size > 0).then(|| (id.stabilize(root), size))
+            })
+            .collect();
+
+        let externals = aliases
+            .iter()
+            .filter(|(_, url)| imports.values().flatten().any(|id| id == *url))
+            .map(|(specifier, url)| (specifier.clone(), url.clone()))
+            .collect();
+
+        Self {
+            size: chunk.code.len() as u32,
+            modules,
+            imports,
+            externals,
+        }
+    }
+}
+
+/// Deskulpt report plugin.
+///
+/// This plugin records the import graph of the modules as they are parsed. The
+/// recorded graph is shared with the bundler through the inner mutex.
+#[derive(Debug)]
+pub struct ReportPlugin {
+    /// Absolute path to the widget directory.
+    pub root: PathBuf,
+    /// The recorded import graph.
+    pub imports: Arc<Mutex<BTreeMap<String, Vec<String>>>>,
+}
+
+impl Plugin for ReportPlugin {
+    fn name(&self) -> Cow<'static, str> {
+        Cow::Borrowed("deskulpt:report")
+    }
+
+    async fn module_parsed(
+        &self,
+        _ctx: &PluginContext,
+        module_info: Arc<ModuleInfo>,
+        _normal_module: &NormalModule,
+    ) -> HookNoopReturn {
+        let imported_ids = module_info
+            .imported_ids
+            .iter()
+            .map(|id| id.stabilize(&self.root))
+            .collect();
+        self.imports
+            .lock()
+            .unwrap()
+            .insert(module_info.id.stabilize(&self.root), imported_ids);
+        Ok(())
+    }
+
+    fn register_hook_usage(&self) -> HookUsage {
+        HookUsage::ModuleParsed
+    }
+}
@@ -1,28 +1,40 @@
-use std::collections::HashMap;
+use std::time::Instant;
 
 use anyhow::Context;
 use deskulpt_common::event::Event;
 use deskulpt_common::outcome::Outcome;
-use deskulpt_common::window::DeskulptWindow;
+use futures::StreamExt;
 use tauri::{command, AppHandle, Runtime};
 
 use super::error::CmdResult;
 use crate::bundler::WidgetBundlerBuilder;
-use crate::events::RenderWidgetsEvent;
+use crate::commands::retain_plugin_processes;
+use crate::events::{BundleProgressEvent, RenderWidgetsEvent};
 use crate::path::PathExt;
-use crate::states::WidgetCatalogStateExt;
+use crate::states::{
+    BundleReportsStateExt, InitialRenderStateExt, MetricSubscriptionsStateExt, SettingsStateExt,
+    WidgetCatalogStateExt,
+};
 
 /// Bundle widgets.
 ///
 /// This command bundles the specified widgets that exist in the catalog. If
-/// `ids` is not provided, all widgets in the catalog are bundled. Failure to
-/// bundle an individual widget does not prevent other widgets from being
-/// bundled. Instead, the outcome of each bundling operation is collected and
-/// sent to the canvas window via the [`RenderWidgetsEvent`].
+/// `ids` is not provided, all widgets in the catalog are bundled. At most
+/// [`Settings::bundle_concurrency`](crate::settings::Settings::bundle_concurrency)
+/// widgets are bundled at the same time. Failure to bundle an individual widget
+/// does not prevent other widgets from being bundled.
+///
+/// The progress of each widget is reported to all windows via the
+/// [`BundleProgressEvent`]. As soon as a widget finishes bundling, its outcome
+/// is sent to the canvas window via the [`RenderWidgetsEvent`], so that one
+/// slow widget does not delay the others from being rendered. The report of
+/// each successful bundle is kept in the state, which can be retrieved with
+/// [`get_bundle_report`](crate::commands::get_bundle_report).
 ///
 /// ### Errors
 ///
-/// - Error accessing the widgets directory.
+/// - Error accessing the persistence directory.
+/// - Error emitting the [`BundleProgressEvent`].
 /// - Error emitting the [`RenderWidgetsEvent`].
 #[command]
 #[specta::specta]
@@ -30,32 +42,42 @@ pub async fn bundle_widgets<R: Runtime>(
     app_handle: AppHandle<R>,
     ids: Option<Vec<String>>,
 ) -> CmdResult<()> {
-    let widgets_dir = app_handle.widgets_dir()?;
+    let packages_dir = app_handle.packages_dir()?;
 
     let widgets: Vec<_> = {
         let catalog = app_handle.get_widget_catalog();
-        match ids {
-            Some(ids) => ids
-                .into_iter()
-                .filter_map(|id| catalog.0.get(&id).cloned().map(|config| (id, config)))
-                .collect(),
-            None => catalog
-                .0
-                .iter()
-                .map(|(id, config)| (id.clone(), config.clone()))
-                .collect(),
-        }
+        let ids = ids.unwrap_or_else(|| catalog.widgets.keys().cloned().collect());
+        ids.into_iter()
+            .filter_map(|id| {
+                let config = catalog.widgets.get(&id)?.clone();
+                let dir = catalog.dirs.get(&id)?.clone();
+                Some((id, config, dir))
+            })
+            .collect()
     };
 
     if widgets.is_empty() {
         return Ok(());
     }
 
-    let futs = widgets.into_iter().map(|(id, config)| async move {
-        match config {
-            Outcome::Ok(config) => {
-                let report =
-                    match WidgetBundlerBuilder::new(widgets_dir.join(&id), config.entry.clone())
+    let concurrency = match app_handle.get_settings().bundle_concurrency {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n as usize,
+    };
+
+    let futs = widgets.into_iter().map(|(id, config, root)| {
+        let packages_dir = packages_dir.clone();
+        let app_handle = app_handle.clone();
+        async move {
+            BundleProgressEvent::Started { id: id.clone() }.emit(&app_handle)?;
+            let start = Instant::now();
+
+            let report = match config {
+                Outcome::Ok(config) => {
+                    match WidgetBundlerBuilder::new(root, config.entry)
+                        .dependencies(config.dependencies)
+                        .packages_dir(packages_dir)
+                        .config(config.build)
                         .build()
                         .context("Failed to build widget bundler")
                     {
@@ -63,20 +85,46 @@ pub async fn bundle_widgets<R: Runtime>(
                             .bundle()
                             .await
                             .with_context(|| format!("Failed to bundle widget (id={id})"))
-                            .map_or_else(|e| Outcome::Err(format!("{e:?}")), Outcome::Ok),
+                            .map_or_else(
+                                |e| Outcome::Err(format!("{e:?}")),
+                                |(code, bundle_report)| {
+                                    app_handle.set_bundle_report(id.clone(), bundle_report);
+                                    Outcome::Ok(code)
+                                },
+                            ),
                         Err(e) => Outcome::Err(format!("{e:?}")),
-                    };
-                (id, report)
-            },
-            Outcome::Err(e) => (id, Outcome::Err(e.clone())),
+                    }
+                },
+                Outcome::Err(e) => Outcome::Err(e),
+            };
+
+            let elapsed = start.elapsed().as_millis().try_into().unwrap_or(u32::MAX);
+            let progress = match &report {
+                Outcome::Ok(_) => BundleProgressEvent::Finished {
+                    id: id.clone(),
+                    elapsed,
+                },
+                Outcome::Err(e) => BundleProgressEvent::Failed {
+                    id: id.clone(),
+                    elapsed,
+                    error: e.clone(),
+                },
+            };
+            progress.emit(&app_handle)?;
+
+            anyhow::Ok((id, report))
         }
     });
 
-    let reports = futures::future::join_all(futs)
-        .await
-        .into_iter()
-        .collect::<HashMap<_, _>>();
+    let mut reports = futures::stream::iter(futs).buffer_unordered(concurrency);
+    while let Some(result) = reports.next().await {
+        let (id, report) = result?;
+        // The widget module is re-imported on render, so its subscriptions and
+        // processes are dropped and will be recreated by the new module
+        app_handle.retain_metric_subscriptions(|widget| widget != id);
+        retain_plugin_processes(|widget| widget != id);
+        app_handle.emit_on_render_ready(RenderWidgetsEvent([(id, report)].into()))?;
+    }
 
-    RenderWidgetsEvent(reports).emit_to(&app_handle, DeskulptWindow::Canvas)?;
     Ok(())
 }
@@ -1,17 +1,50 @@
+use anyhow::{bail, Result};
+use deskulpt_common::event::Event;
+use deskulpt_common::outcome::Outcome;
+use deskulpt_common::window::DeskulptWindow;
+use deskulpt_plugin::Plugin;
 use once_cell::sync::Lazy;
 use tauri::{command, AppHandle, Runtime};
 use tokio::sync::Mutex;
 
 use super::error::{cmdbail, CmdResult};
+use crate::events::PluginEvent;
+use crate::package::SignatureStatus;
 use crate::path::PathExt;
+use crate::states::{SettingsStateExt, WidgetCatalogStateExt};
+
+/// Plugins that give widgets privileged access to the system.
+///
+/// Access to these plugins is refused for unverified widgets if
+/// [`Settings::restrict_unverified_widgets`](crate::settings::Settings::restrict_unverified_widgets)
+/// is enabled. Note that permissions declared in the configurations of
+/// unverified widgets cannot be trusted, so plugins that are restricted by
+/// such permissions are privileged as well. Other plugins are not refused, but
+/// see [`widget_permissions`] for how their permissions are restricted.
+///
+/// The restriction is advisory only, since the widget ID is supplied by the
+/// caller and is not authenticated.
+const PRIVILEGED_PLUGINS: &[&str] = &["fs", "http", "shell"];
 
 // TODO: Remove this temporary implementation
 static FS_PLUGIN: Lazy<Mutex<deskulpt_plugin_fs::FsPlugin>> =
     Lazy::new(|| Mutex::new(deskulpt_plugin_fs::FsPlugin));
 
 // TODO: Remove this temporary implementation
-static SYS_PLUGIN: Lazy<Mutex<deskulpt_plugin_sys::SysPlugin>> =
-    Lazy::new(|| Mutex::new(Default::default()));
+static HTTP_PLUGIN: Lazy<deskulpt_plugin_http::HttpPlugin> = Lazy::new(Default::default);
+
+// TODO: Remove this temporary implementation
+static SHELL_PLUGIN: Lazy<deskulpt_plugin_shell::ShellPlugin> = Lazy::new(Default::default);
+
+// TODO: Remove this temporary implementation
+static SQLITE_PLUGIN: Lazy<deskulpt_plugin_sqlite::SqlitePlugin> =
+    Lazy::new(|| deskulpt_plugin_sqlite::SqlitePlugin);
+
+// TODO: Remove this temporary implementation
+static STORAGE_PLUGIN: Lazy<deskulpt_plugin_storage::StoragePlugin> = Lazy::new(Default::default);
+
+// TODO: Remove this temporary implementation
+static SYS_PLUGIN: Lazy<deskulpt_plugin_sys::SysPlugin> = Lazy::new(Default::default);
 
 /// Call a plugin command (🚧 TODO 🚧).
 ///
@@ -25,6 +58,15 @@ static SYS_PLUGIN: Lazy<Mutex<deskulpt_plugin_sys::SysPlugin>> =
 /// Also, in order to simplify the engine API for the plugin (because it is
 /// a temporary implementation), `app_handle` is using the default runtime but
 /// it should be a generic `R: Runtime` parameter in the final implementation.
+///
+/// ### Errors
+///
+/// - The plugin is privileged, unverified widgets are restricted, and the
+///   widget is not installed from a package signed by a trusted key. Note that
+///   this trusts the widget ID sent by the caller, so it does not stop a
+///   malicious widget that impersonates a verified one.
+/// - Error calling the plugin command.
+/// - Unknown plugin.
 #[command]
 #[specta::specta]
 pub async fn call_plugin<R: Runtime>(
@@ -34,31 +76,166 @@ pub async fn call_plugin<R: Runtime>(
     id: String,
     payload: Option<serde_json::Value>,
 ) -> CmdResult<serde_json::Value> {
-    let widget_dir_fn = move |id: &str| app_handle.widget_dir(id);
+    if PRIVILEGED_PLUGINS.contains(&plugin.as_str()) && is_restricted(&app_handle, &id) {
+        cmdbail!("Unverified widget {id} cannot access privileged plugin: {plugin}");
+    }
+
+    Ok(dispatch_plugin(app_handle, &plugin, &command, id, payload).await?)
+}
+
+/// Whether a widget is unverified and unverified widgets are restricted.
+fn is_restricted<R: Runtime>(app_handle: &AppHandle<R>, id: &str) -> bool {
+    app_handle.get_settings().restrict_unverified_widgets
+        && !matches!(
+            app_handle.get_widget_catalog().signatures.get(id),
+            Some(SignatureStatus::Verified(_))
+        )
+}
+
+/// Get the permissions that a widget declares for a plugin.
+///
+/// This is the field of the `permissions` section of the widget configuration
+/// named after the plugin, or `null` if not declared. Permissions declared by
+/// unverified widgets are ignored if unverified widgets are restricted.
+pub(crate) fn widget_permissions<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    plugin: &str,
+    id: &str,
+) -> Result<serde_json::Value> {
+    let permissions = match app_handle.get_widget_catalog().widgets.get(id) {
+        Some(Outcome::Ok(config)) => serde_json::to_value(&config.permissions)?,
+        Some(Outcome::Err(_)) => bail!("Widget has invalid configuration: {id}"),
+        None => bail!("Widget not found in the catalog: {id}"),
+    };
+    if is_restricted(app_handle, id) {
+        return Ok(serde_json::Value::Null);
+    }
+    Ok(permissions.get(plugin).cloned().unwrap_or_default())
+}
 
-    match plugin.as_str() {
+/// Dispatch a call to a plugin command.
+///
+/// Unlike [`call_plugin`], this does not check whether the widget has access
+/// to the plugin.
+pub(crate) async fn dispatch_plugin<R: Runtime>(
+    app_handle: AppHandle<R>,
+    plugin: &str,
+    command: &str,
+    id: String,
+    payload: Option<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    match plugin {
         "fs" => {
             let plugin = FS_PLUGIN.lock().await;
-            let result = deskulpt_plugin::call_plugin(
-                widget_dir_fn,
-                &*plugin,
-                command.as_str(),
-                id,
-                payload,
-            )?;
-            Ok(result)
+            run_plugin(app_handle, "fs", &*plugin, command, id, payload)
         },
-        "sys" => {
-            let plugin = SYS_PLUGIN.lock().await;
-            let result = deskulpt_plugin::call_plugin(
-                widget_dir_fn,
-                &*plugin,
-                command.as_str(),
+        "http" => dispatch_blocking(app_handle, "http", &*HTTP_PLUGIN, command, id, payload).await,
+        "shell" => {
+            dispatch_blocking(app_handle, "shell", &*SHELL_PLUGIN, command, id, payload).await
+        },
+        "sqlite" => {
+            dispatch_blocking(app_handle, "sqlite", &*SQLITE_PLUGIN, command, id, payload).await
+        },
+        "storage" => {
+            dispatch_blocking(
+                app_handle,
+                "storage",
+                &*STORAGE_PLUGIN,
+                command,
                 id,
                 payload,
-            )?;
-            Ok(result)
+            )
+            .await
         },
-        _ => cmdbail!("Unknown plugin: {}", plugin),
+        "sys" => dispatch_blocking(app_handle, "sys", &*SYS_PLUGIN, command, id, payload).await,
+        _ => bail!("Unknown plugin: {}", plugin),
     }
 }
+
+/// Dispatch a call to a plugin command on a blocking thread.
+///
+/// Plugin commands may block for long, e.g., accessing files, sending requests,
+/// running processes, executing SQL statements or sampling system information,
+/// so they must not run on the async runtime. The plugin is not locked, so
+/// concurrent calls may run at the same time and the plugin must synchronize
+/// its own state.
+async fn dispatch_blocking<R: Runtime, P: Plugin + Sync>(
+    app_handle: AppHandle<R>,
+    name: &'static str,
+    plugin: &'static P,
+    command: &str,
+    id: String,
+    payload: Option<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    let command = command.to_string();
+    tauri::async_runtime::spawn_blocking(move || {
+        run_plugin(app_handle, name, plugin, &command, id, payload)
+    })
+    .await?
+}
+
+/// Run a plugin command on the current thread.
+fn run_plugin<R: Runtime, P: Plugin>(
+    app_handle: AppHandle<R>,
+    name: &str,
+    plugin: &P,
+    command: &str,
+    id: String,
+    payload: Option<serde_json::Value>,
+) -> Result<serde_json::Value> {
+    let widget_dir_fn = {
+        let app_handle = app_handle.clone();
+        move |id: &str| app_handle.widget_dir(id)
+    };
+    // Only widgets in the catalog have storage, so that storage directories
+    // are not created for arbitrary IDs
+    let widget_storage_dir_fn = {
+        let app_handle = app_handle.clone();
+        move |id: &str| {
+            app_handle.widget_dir(id)?;
+            app_handle.widget_storage_dir(id)
+        }
+    };
+    let emit_event_fn = {
+        let app_handle = app_handle.clone();
+        let name = name.to_string();
+        move |id: &str, payload: serde_json::Value| {
+            let event = PluginEvent {
+                plugin: name.clone(),
+                id: id.to_string(),
+                payload,
+            };
+            event.emit_to(&app_handle, DeskulptWindow::Canvas)
+        }
+    };
+    let widget_permissions_fn = {
+        let name = name.to_string();
+        move |id: &str| widget_permissions(&app_handle, &name, id)
+    };
+
+    deskulpt_plugin::call_plugin(
+        widget_dir_fn,
+        widget_storage_dir_fn,
+        widget_permissions_fn,
+        emit_event_fn,
+        plugin,
+        command,
+        id,
+        payload,
+    )
+}
+
+/// Stop the background processes of widgets not specified by the predicate
+/// (🚧 TODO 🚧).
+///
+/// This should be called when widgets are re-rendered or removed, since their
+/// processes can no longer deliver output to them, and for all widgets when the
+/// application exits.
+///
+/// ### 🚧 TODO 🚧
+///
+/// The Deskulpt core should notify the plugins about the lifecycle of widgets
+/// instead of reaching into a specific plugin.
+pub(crate) fn retain_plugin_processes(f: impl FnMut(&str) -> bool) {
+    SHELL_PLUGIN.retain_processes(f);
+}
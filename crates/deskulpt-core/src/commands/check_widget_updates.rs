@@ -0,0 +1,42 @@
+use std::collections::BTreeMap;
+
+use deskulpt_common::event::Event;
+use deskulpt_common::window::DeskulptWindow;
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::{cmdbail, CmdResult};
+use crate::events::WidgetUpdatesEvent;
+use crate::path::PathExt;
+use crate::registry::{Registry, WidgetUpdate};
+use crate::states::{SettingsStateExt, WidgetCatalogStateExt};
+
+/// Check the installed widgets for updates in the widget registry.
+///
+/// This command fetches the index configured in
+/// [`Settings::registry_index`](crate::settings::Settings::registry_index) and
+/// compares it with the versions of the widgets installed from packages. The
+/// available updates are reported to the manager window with the
+/// [`WidgetUpdatesEvent`] and also returned.
+///
+/// ### Errors
+///
+/// - No registry index is configured.
+/// - Error fetching or parsing the registry index.
+/// - Error accessing the widgets directory.
+/// - Error emitting the [`WidgetUpdatesEvent`].
+#[command]
+#[specta::specta]
+pub async fn check_widget_updates<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> CmdResult<BTreeMap<String, WidgetUpdate>> {
+    let Some(location) = app_handle.get_settings().registry_index.clone() else {
+        cmdbail!("No widget registry index is configured");
+    };
+    let registry = tauri::async_runtime::spawn_blocking(move || Registry::fetch(&location))
+        .await
+        .map_err(anyhow::Error::from)??;
+
+    let updates = registry.updates(&app_handle.get_widget_catalog(), app_handle.widgets_dir()?);
+    WidgetUpdatesEvent(updates.clone()).emit_to(&app_handle, DeskulptWindow::Manager)?;
+    Ok(updates)
+}
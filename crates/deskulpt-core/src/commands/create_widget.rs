@@ -0,0 +1,42 @@
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::CmdResult;
+use crate::commands::rescan_widgets;
+use crate::path::PathExt;
+use crate::scaffold::{self, WidgetTemplate};
+use crate::states::WidgetCatalogStateExt;
+
+/// Create a new widget from a built-in template.
+///
+/// This command generates a widget with the given display name from the given
+/// template in its own directory in the widgets directory. The directory is
+/// named after the widget name and made unique among the existing directories
+/// and widget IDs. It then rescans the widgets with `rescan_widgets` so that
+/// the new widget is discovered, and returns the ID of the new widget.
+///
+/// ### Errors
+///
+/// - The widget name is empty.
+/// - Error accessing the widgets directory.
+/// - Error writing the widget files.
+/// - Error rescanning the widgets.
+#[command]
+#[specta::specta]
+pub async fn create_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    name: String,
+    template: WidgetTemplate,
+) -> CmdResult<String> {
+    let ids = app_handle
+        .get_widget_catalog()
+        .widgets
+        .keys()
+        .cloned()
+        .collect::<Vec<_>>();
+    let id = scaffold::create(app_handle.widgets_dir()?, &name, template, |dir_name| {
+        ids.iter().any(|id| id == dir_name)
+    })?;
+
+    rescan_widgets(app_handle).await?;
+    Ok(id)
+}
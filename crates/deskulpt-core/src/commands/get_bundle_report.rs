@@ -0,0 +1,19 @@
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::CmdResult;
+use crate::bundler::BundleReport;
+use crate::states::BundleReportsStateExt;
+
+/// Get the report of the last successful bundle of a widget.
+///
+/// The report includes the bundle size, the size contributed by each module,
+/// the import graph, and the imports externalized through the alias map. If
+/// the widget has not been bundled successfully yet, `null` is returned.
+#[command]
+#[specta::specta]
+pub async fn get_bundle_report<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+) -> CmdResult<Option<BundleReport>> {
+    Ok(app_handle.get_bundle_report(&id))
+}
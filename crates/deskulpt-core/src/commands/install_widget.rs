@@ -0,0 +1,38 @@
+use std::path::PathBuf;
+
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::CmdResult;
+use crate::commands::rescan_widgets;
+use crate::package;
+use crate::path::PathExt;
+
+/// Install a widget from a package.
+///
+/// This command verifies the widget package at the given local path and
+/// extracts it into its own directory in the widgets directory. See
+/// [`PackageManifest`](crate::package::PackageManifest) for the package format.
+/// It then rescans the widgets with `rescan_widgets` so that the installed
+/// widget is discovered, and returns the ID of the installed widget.
+///
+/// ### Errors
+///
+/// - Error accessing the widgets directory.
+/// - The package is invalid, e.g., its files do not match the manifest or it
+///   contains entries that escape the widget directory.
+/// - A widget directory with the same ID already exists.
+/// - Error rescanning the widgets.
+#[command]
+#[specta::specta]
+pub async fn install_widget<R: Runtime>(
+    app_handle: AppHandle<R>,
+    path: PathBuf,
+) -> CmdResult<String> {
+    let widgets_dir = app_handle.widgets_dir()?.to_path_buf();
+    let manifest =
+        tauri::async_runtime::spawn_blocking(move || package::install(&path, &widgets_dir, false))
+            .await
+            .map_err(anyhow::Error::from)??;
+    rescan_widgets(app_handle).await?;
+    Ok(manifest.id)
+}
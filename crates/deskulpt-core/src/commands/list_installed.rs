@@ -0,0 +1,32 @@
+use std::collections::BTreeMap;
+
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::CmdResult;
+use crate::package::{InstalledWidget, PackageManifest};
+use crate::states::WidgetCatalogStateExt;
+
+/// List the widgets installed from packages.
+///
+/// This returns the mapping from the IDs of the widgets in the catalog that
+/// are installed from packages to their installation information. Widgets
+/// whose package manifests cannot be read are skipped.
+#[command]
+#[specta::specta]
+pub async fn list_installed<R: Runtime>(
+    app_handle: AppHandle<R>,
+) -> CmdResult<BTreeMap<String, InstalledWidget>> {
+    let catalog = app_handle.get_widget_catalog();
+    let installed = catalog
+        .dirs
+        .iter()
+        .filter_map(|(id, dir)| match PackageManifest::load(dir) {
+            Ok(manifest) => manifest.map(|manifest| (id.clone(), manifest.into())),
+            Err(e) => {
+                eprintln!("Failed to load package manifest of {id}: {e:?}");
+                None
+            },
+        })
+        .collect();
+    Ok(installed)
+}
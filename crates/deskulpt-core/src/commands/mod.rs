@@ -5,19 +5,46 @@ mod bundle_widgets;
 #[doc(hidden)]
 mod call_plugin;
 #[doc(hidden)]
+mod check_widget_updates;
+#[doc(hidden)]
+mod create_widget;
+#[doc(hidden)]
+mod get_bundle_report;
+#[doc(hidden)]
+mod install_widget;
+#[doc(hidden)]
+mod list_installed;
+#[doc(hidden)]
 mod open_widget;
 #[doc(hidden)]
 mod rescan_widgets;
 #[doc(hidden)]
 mod set_render_ready;
 #[doc(hidden)]
+mod subscribe_metric;
+#[doc(hidden)]
+mod uninstall_widget;
+#[doc(hidden)]
+mod unsubscribe_metric;
+#[doc(hidden)]
 mod update_settings;
+#[doc(hidden)]
+mod update_widget;
 
 mod error;
 
 pub use bundle_widgets::*;
 pub use call_plugin::*;
+pub use check_widget_updates::*;
+pub use create_widget::*;
+pub use get_bundle_report::*;
+pub use install_widget::*;
+pub use list_installed::*;
 pub use open_widget::*;
 pub use rescan_widgets::*;
 pub use set_render_ready::*;
+pub use subscribe_metric::*;
+pub use uninstall_widget::*;
+pub use unsubscribe_metric::*;
 pub use update_settings::*;
+pub use update_widget::*;
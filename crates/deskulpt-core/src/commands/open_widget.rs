@@ -2,6 +2,7 @@ use tauri::{command, AppHandle, Runtime};
 
 use super::error::CmdResult;
 use crate::path::PathExt;
+use crate::states::WidgetCatalogStateExt;
 
 /// Open the widgets directory or a specific widget directory.
 ///
@@ -10,7 +11,7 @@ use crate::path::PathExt;
 ///
 /// ### Errors
 ///
-/// - Widget ID is provided but does not exist in the collection.
+/// - Widget ID is provided but does not exist in the catalog.
 /// - Failed to access the widgets directory.
 /// - Error opening the directory.
 #[command]
@@ -19,13 +20,11 @@ pub async fn open_widget<R: Runtime>(
     app_handle: AppHandle<R>,
     id: Option<String>,
 ) -> CmdResult<()> {
-    let widgets_dir = app_handle.widgets_dir()?;
-
     if let Some(id) = id {
         let widget_dir = app_handle.widget_dir(&id)?;
         open::that_detached(widget_dir)?;
     } else {
-        open::that_detached(widgets_dir)?;
+        open::that_detached(app_handle.widgets_dir()?)?;
     };
 
     Ok(())
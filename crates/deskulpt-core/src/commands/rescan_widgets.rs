@@ -1,48 +1,240 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::Result;
 use deskulpt_common::event::Event;
+use deskulpt_common::outcome::Outcome;
+use deskulpt_common::window::DeskulptWindow;
 use tauri::{command, AppHandle, Runtime};
 
 use super::error::CmdResult;
-use crate::commands::bundle_widgets;
+use crate::commands::{bundle_widgets, retain_plugin_processes};
 use crate::config::WidgetCatalog;
-use crate::events::{UpdateSettingsEvent, UpdateWidgetCatalogEvent};
-use crate::path::PathExt;
-use crate::states::{SettingsStateExt, WidgetCatalogStateExt};
+use crate::events::{ShowToastEvent, UpdateSettingsEvent, WidgetCatalogDiffEvent};
+use crate::package::TrustStore;
+use crate::path::{storage_name, PathExt};
+use crate::states::{
+    BundleReportsStateExt, MetricSubscriptionsStateExt, SettingsStateExt, WidgetCatalogStateExt,
+};
+use crate::window::WindowExt;
 
 /// Rescan the widgets directory to discover widgets.
 ///
-/// This command scans the widgets directory for available widgets and updates
-/// the widget catalog and settings accordingly. It then emits events to notify
-/// the frontend of these changes. Finally, it triggers the bundling of all
-/// widgets in the updated catalog with `bundle_widgets` to ensure they are
-/// ready for use.
+/// This command scans the widgets directory as well as the extra widget roots
+/// in [`Settings::widget_roots`](crate::settings::Settings::widget_roots) for
+/// available widgets and updates the widget catalog and settings accordingly.
+/// It then emits events to notify the frontend of these changes, where only the
+/// difference from the previous catalog is sent. Finally, it triggers the
+/// bundling of the added and changed widgets with `bundle_widgets`, while the
+/// unchanged widgets are left untouched. Settings are only emitted if they
+/// have changed.
+///
+/// The signatures of the widgets installed from packages are checked against
+/// the trust store in the persistence directory. See
+/// [`TrustStore`](crate::package::TrustStore) for more information.
+///
+/// Problems with the extra widget roots, such as missing directories or ID
+/// conflicts, do not fail the rescan. They are instead shown as an error toast
+/// on the canvas.
+///
+/// Settings of widgets that are no longer discovered are removed, unless the
+/// widget directories have only been renamed or moved, in which case the
+/// settings are migrated to the new widget IDs. See
+/// [`Settings::migrate_widgets`](crate::settings::Settings::migrate_widgets)
+/// for more information. Newly discovered widgets are sized and placed in free
+/// space on the canvas according to the layout hints in their configurations.
+/// See [`LayoutConfig`](crate::config::LayoutConfig) for more information.
+///
+/// Storage directories of migrated widgets are moved along with their
+/// settings, and those of widgets that are no longer discovered are removed.
+/// Widgets in extra widget roots that could not be scanned, e.g., on a detached
+/// drive, keep their storage since they may only be unavailable for now. See
+/// [`PathExt::widget_storage_dir`] for more information. Failing to clean up
+/// the storage directories does not fail the rescan.
 ///
 /// ### Errors
 ///
 /// - Error accessing the widgets directory.
+/// - Error accessing the persistence directory.
 /// - Error loading the new widget catalog from the widgets directory.
 /// - Error emitting the [`UpdateSettingsEvent`].
-/// - Error emitting the [`UpdateWidgetCatalogEvent`].
-/// - Error bundling all discovered widgets.
+/// - Error emitting the [`WidgetCatalogDiffEvent`].
+/// - Error bundling the added and changed widgets.
 #[command]
 #[specta::specta]
 pub async fn rescan_widgets<R: Runtime>(app_handle: AppHandle<R>) -> CmdResult<()> {
-    let catalog = WidgetCatalog::load(app_handle.widgets_dir()?)?;
-    *app_handle.get_widget_catalog_mut() = catalog.clone();
+    let roots = app_handle.get_settings().widget_roots.clone();
+    let mut catalog = WidgetCatalog::load_with_roots(app_handle.widgets_dir()?, &roots)?;
+    let trust_store = TrustStore::load(&app_handle.trusted_keys_dir()?).unwrap_or_else(|e| {
+        eprintln!("Failed to load trust store: {e:?}");
+        TrustStore::default()
+    });
+    catalog.verify_signatures(&trust_store);
+    if !catalog.errors.is_empty() {
+        if let Err(e) = ShowToastEvent::Error(catalog.errors.join("\n"))
+            .emit_to(&app_handle, DeskulptWindow::Canvas)
+        {
+            eprintln!("Failed to emit ShowToastEvent to canvas: {e}");
+        }
+    }
+    let diff = {
+        let mut current = app_handle.get_widget_catalog_mut();
+        let diff = current.diff(&catalog);
+        *current = catalog.clone();
+        diff
+    };
+    app_handle.retain_bundle_reports(|id, _| catalog.widgets.contains_key(id));
+    app_handle.retain_metric_subscriptions(|id| catalog.widgets.contains_key(id));
+    retain_plugin_processes(|id| catalog.widgets.contains_key(id));
 
-    {
+    let canvas_size = app_handle
+        .canvas_size()
+        .inspect_err(|e| eprintln!("Failed to get canvas size: {e}"))
+        .ok();
+
+    let migrated = {
         let mut settings = app_handle.get_settings_mut();
-        settings.widgets.retain(|id, _| catalog.0.contains_key(id));
-        for id in catalog.0.keys() {
-            settings
-                .widgets
-                .entry(id.clone())
-                .or_insert_with(Default::default);
-        }
-        UpdateSettingsEvent(settings.clone()).emit(&app_handle)?;
+        let original = settings.clone();
+        let migrated = settings.migrate_widgets(&catalog);
+        for (id, config) in catalog.widgets.iter() {
+            if settings.widgets.contains_key(id) {
+                continue;
+            }
+            let layout = match config {
+                Outcome::Ok(config) => config.layout.clone(),
+                Outcome::Err(_) => Default::default(),
+            };
+            settings.place_widget(id.clone(), &layout, canvas_size);
+        }
+        for (id, widget) in settings.widgets.iter_mut() {
+            widget.fingerprint = catalog.fingerprints.get(id).cloned();
+        }
+        if *settings != original {
+            UpdateSettingsEvent(settings.clone()).emit(&app_handle)?;
+        }
+        migrated
+    };
+    if let Err(e) = app_handle
+        .storage_dir()
+        .and_then(|dir| clean_widget_storage(&dir, &catalog, &migrated))
+    {
+        eprintln!("Failed to clean up widget storage: {e:?}");
     }
 
-    UpdateWidgetCatalogEvent(catalog).emit(&app_handle)?;
+    let ids = diff
+        .added
+        .keys()
+        .chain(diff.changed.keys())
+        .cloned()
+        .collect();
+    WidgetCatalogDiffEvent(diff).emit(&app_handle)?;
 
-    bundle_widgets(app_handle, None).await?;
+    bundle_widgets(app_handle, Some(ids)).await?;
     Ok(())
 }
+
+/// Clean up the widget storage directories after a rescan.
+///
+/// Storage directories are first moved from the old IDs to the new IDs of the
+/// migrated widgets. Any remaining entry in the storage directory that does
+/// not belong to a widget in the catalog is then removed, unless it belongs to
+/// a widget in one of the [unavailable
+/// roots](crate::config::WidgetCatalog::unavailable_roots).
+fn clean_widget_storage(
+    dir: &Path,
+    catalog: &WidgetCatalog,
+    migrated: &BTreeMap<String, String>,
+) -> Result<()> {
+    if !dir.exists() {
+        return Ok(());
+    }
+
+    for (old_id, new_id) in migrated {
+        let old_dir = dir.join(storage_name(old_id));
+        if old_dir.exists() {
+            std::fs::rename(old_dir, dir.join(storage_name(new_id)))?;
+        }
+    }
+
+    let expected = catalog
+        .widgets
+        .keys()
+        .map(|id| storage_name(id))
+        .collect::<BTreeSet<_>>();
+    let unavailable = catalog
+        .unavailable_roots
+        .iter()
+        .map(|namespace| storage_name(&format!("{namespace}:")))
+        .collect::<Vec<_>>();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if expected.contains(&name) || unavailable.iter().any(|prefix| name.starts_with(prefix)) {
+            continue;
+        }
+        let path = entry.path();
+        if path.is_dir() {
+            std::fs::remove_dir_all(&path)?;
+        } else {
+            std::fs::remove_file(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_widget_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let widgets_dir = dir.path().join("widgets");
+        let shared_dir = dir.path().join("shared");
+        let storage_dir = dir.path().join("storage");
+        for widget_dir in [widgets_dir.join("clock"), shared_dir.join("notes")] {
+            std::fs::create_dir_all(&widget_dir).unwrap();
+            std::fs::write(
+                widget_dir.join("deskulpt.conf.json"),
+                r#"{ "name": "Widget", "entry": "index.jsx" }"#,
+            )
+            .unwrap();
+        }
+        for id in [
+            "renamed",
+            "deleted",
+            "shared:notes",
+            "shared:deleted",
+            "detached:clock",
+        ] {
+            std::fs::create_dir_all(storage_dir.join(storage_name(id))).unwrap();
+        }
+
+        // The "detached" root is missing, e.g., on a detached drive
+        let roots = BTreeMap::from([
+            ("shared".to_string(), shared_dir),
+            ("detached".to_string(), dir.path().join("missing")),
+        ]);
+        let catalog = WidgetCatalog::load_with_roots(&widgets_dir, &roots).unwrap();
+        assert_eq!(
+            catalog.unavailable_roots,
+            BTreeSet::from(["detached".to_string()])
+        );
+
+        let migrated = BTreeMap::from([("renamed".to_string(), "clock".to_string())]);
+        clean_widget_storage(&storage_dir, &catalog, &migrated).unwrap();
+        let mut remaining = std::fs::read_dir(&storage_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        remaining.sort();
+        assert_eq!(
+            remaining,
+            [
+                storage_name("clock"),
+                storage_name("detached:clock"),
+                storage_name("shared:notes"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use deskulpt_common::event::Event;
+use deskulpt_common::outcome::Outcome;
+use deskulpt_common::window::DeskulptWindow;
+use serde::{Deserialize, Serialize};
+use tauri::{command, AppHandle, Runtime};
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+
+use super::error::{cmdbail, CmdResult};
+use crate::commands::{dispatch_plugin, widget_permissions};
+use crate::events::MetricUpdateEvent;
+use crate::states::MetricSubscriptionsStateExt;
+
+/// The plugin that provides the metrics.
+const METRIC_PLUGIN: &str = "sys";
+
+/// The minimum sampling interval of a metric in milliseconds.
+const MIN_METRIC_INTERVAL: u32 = 250;
+
+/// A subscription to a metric topic.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricSubscription {
+    /// The handle of the subscription.
+    ///
+    /// This is used to unsubscribe with
+    /// [`unsubscribe_metric`](crate::commands::unsubscribe_metric).
+    pub handle: u32,
+    /// The key of the metric topic.
+    ///
+    /// Samples of the metric are sent with [`MetricUpdateEvent`] carrying
+    /// this key.
+    pub topic: String,
+    /// The latest sample of the metric.
+    pub latest: Outcome<serde_json::Value>,
+}
+
+/// Subscribe a widget to a metric.
+///
+/// A metric is a command of the system information plugin with its payload,
+/// sampled every `interval` milliseconds. Each distinct metric is sampled by a
+/// single task no matter how many widgets subscribe to it, and each sample is
+/// sent to the canvas window via the [`MetricUpdateEvent`] with the key of the
+/// metric topic. The latest sample is returned immediately on subscription so
+/// that the widget does not have to wait for the next one.
+///
+/// Subscriptions are removed with
+/// [`unsubscribe_metric`](crate::commands::unsubscribe_metric), or when the
+/// widget is re-rendered or removed. The metric is no longer sampled once it
+/// has no subscriptions left.
+///
+/// ### Errors
+///
+/// - The interval is shorter than the minimum of 250 milliseconds.
+/// - The widget is not found or has invalid configuration.
+/// - Error sampling the metric for the first time, e.g., unknown command or
+///   invalid payload.
+#[command]
+#[specta::specta]
+pub async fn subscribe_metric<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    command: String,
+    payload: Option<serde_json::Value>,
+    interval: u32,
+) -> CmdResult<MetricSubscription> {
+    if interval < MIN_METRIC_INTERVAL {
+        cmdbail!("Metric interval must be at least {MIN_METRIC_INTERVAL}ms, got {interval}ms");
+    }
+
+    // Samples may depend on the permissions of the widget, so only widgets
+    // with the same permissions share a topic
+    let payload = payload.unwrap_or_default();
+    let permissions = widget_permissions(&app_handle, METRIC_PLUGIN, &id)?;
+    let topic = format!("{command}@{interval}:{payload}:{permissions}");
+    if let Some((handle, latest)) = app_handle.subscribe_metric_topic(&topic, id.clone()) {
+        return Ok(MetricSubscription {
+            handle,
+            topic,
+            latest,
+        });
+    }
+
+    // The first sample is taken before the topic is added, so that invalid
+    // metrics are rejected and never sampled periodically
+    let latest = dispatch_plugin(
+        app_handle.clone(),
+        METRIC_PLUGIN,
+        &command,
+        id.clone(),
+        Some(payload.clone()),
+    )
+    .await?;
+
+    let task = {
+        let app_handle = app_handle.clone();
+        let topic = topic.clone();
+        tauri::async_runtime::spawn(async move {
+            let period = Duration::from_millis(interval.into());
+            let mut ticker = interval_at(Instant::now() + period, period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                // All subscribers have the same permissions, so samples are
+                // taken on behalf of any of them
+                let Some(id) = app_handle.metric_topic_subscriber(&topic) else {
+                    break;
+                };
+                // Sampling may sleep, e.g., to measure CPU usage, which does not
+                // stall this task since the sys plugin is dispatched on a
+                // blocking thread
+                let sample = dispatch_plugin(
+                    app_handle.clone(),
+                    METRIC_PLUGIN,
+                    &command,
+                    id,
+                    Some(payload.clone()),
+                )
+                .await
+                .map_or_else(|e| Outcome::Err(format!("{e:?}")), Outcome::Ok);
+                if !app_handle.set_metric_topic_latest(&topic, sample.clone()) {
+                    break;
+                }
+                let event = MetricUpdateEvent {
+                    topic: topic.clone(),
+                    sample,
+                };
+                if let Err(e) = event.emit_to(&app_handle, DeskulptWindow::Canvas) {
+                    eprintln!("Failed to emit MetricUpdateEvent to canvas: {e}");
+                }
+            }
+        })
+    };
+
+    // The widget may be removed while the first sample is taken, in which case
+    // its subscriptions have already been dropped
+    let Some((handle, latest)) =
+        app_handle.add_metric_topic(topic.clone(), id.clone(), Outcome::Ok(latest), task)
+    else {
+        cmdbail!("Widget not found in the catalog: {id}");
+    };
+    Ok(MetricSubscription {
+        handle,
+        topic,
+        latest,
+    })
+}
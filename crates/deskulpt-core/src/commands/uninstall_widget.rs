@@ -0,0 +1,47 @@
+use deskulpt_common::event::Event;
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::CmdResult;
+use crate::commands::rescan_widgets;
+use crate::events::UpdateSettingsEvent;
+use crate::package;
+use crate::path::PathExt;
+use crate::states::{SettingsStateExt, WidgetCatalogStateExt};
+
+/// Uninstall a widget installed from a package.
+///
+/// This command removes the directory of the widget and cleans up its
+/// settings and storage. It then rescans the widgets with `rescan_widgets` so
+/// that the widget is removed from the catalog. Widgets that are not installed
+/// from packages cannot be uninstalled. Failing to remove the storage does not
+/// fail the uninstallation.
+///
+/// ### Errors
+///
+/// - The widget does not exist in the catalog.
+/// - The widget is not installed from a package.
+/// - Error removing the widget directory.
+/// - Error emitting the [`UpdateSettingsEvent`].
+/// - Error rescanning the widgets.
+#[command]
+#[specta::specta]
+pub async fn uninstall_widget<R: Runtime>(app_handle: AppHandle<R>, id: String) -> CmdResult<()> {
+    package::uninstall(&app_handle.widget_dir(&id)?)?;
+
+    let storage_dir = app_handle.widget_storage_dir(&id)?;
+    if storage_dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&storage_dir) {
+            eprintln!("Failed to remove {}: {e}", storage_dir.display());
+        }
+    }
+
+    {
+        let mut settings = app_handle.get_settings_mut();
+        if settings.widgets.remove(&id).is_some() {
+            UpdateSettingsEvent(settings.clone()).emit(&app_handle)?;
+        }
+    }
+
+    rescan_widgets(app_handle).await?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::CmdResult;
+use crate::states::MetricSubscriptionsStateExt;
+
+/// Wrapper of
+/// [`remove_metric_subscription`](MetricSubscriptionsStateExt::remove_metric_subscription).
+///
+/// The handle is obtained from
+/// [`subscribe_metric`](crate::commands::subscribe_metric). Unknown handles,
+/// e.g., of subscriptions already removed because the widget is re-rendered,
+/// and handles of subscriptions of other widgets are ignored.
+#[command]
+#[specta::specta]
+pub async fn unsubscribe_metric<R: Runtime>(
+    app_handle: AppHandle<R>,
+    id: String,
+    handle: u32,
+) -> CmdResult<()> {
+    app_handle.remove_metric_subscription(&id, handle);
+    Ok(())
+}
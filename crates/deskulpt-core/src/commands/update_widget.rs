@@ -0,0 +1,45 @@
+use tauri::{command, AppHandle, Runtime};
+
+use super::error::{cmdbail, CmdResult};
+use crate::commands::{check_widget_updates, rescan_widgets};
+use crate::path::PathExt;
+use crate::registry::Registry;
+use crate::states::SettingsStateExt;
+
+/// Update a widget to its latest version in the widget registry.
+///
+/// This command downloads the package of the widget from the registry index
+/// configured in
+/// [`Settings::registry_index`](crate::settings::Settings::registry_index),
+/// verifies it against the checksum in the index, and installs it in place of
+/// the existing installation. The existing installation is restored if the
+/// update fails. It then rescans the widgets with `rescan_widgets` and checks
+/// for updates again with `check_widget_updates`.
+///
+/// ### Errors
+///
+/// - No registry index is configured.
+/// - Error fetching or parsing the registry index.
+/// - The widget ID is invalid or not in the registry.
+/// - Error downloading the package or checksum mismatch.
+/// - Error installing the package, e.g., the package is invalid or the widget
+///   is not installed from a package.
+/// - Error rescanning the widgets.
+/// - Error checking for updates.
+#[command]
+#[specta::specta]
+pub async fn update_widget<R: Runtime>(app_handle: AppHandle<R>, id: String) -> CmdResult<()> {
+    let Some(location) = app_handle.get_settings().registry_index.clone() else {
+        cmdbail!("No widget registry index is configured");
+    };
+    let widgets_dir = app_handle.widgets_dir()?.to_path_buf();
+    tauri::async_runtime::spawn_blocking(move || {
+        Registry::fetch(&location)?.install(&id, &widgets_dir)
+    })
+    .await
+    .map_err(anyhow::Error::from)??;
+
+    rescan_widgets(app_handle.clone()).await?;
+    check_widget_updates(app_handle).await?;
+    Ok(())
+}
@@ -1,19 +1,40 @@
 //! Configuration of Deskulpt widgets.
 
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use deskulpt_common::outcome::Outcome;
+use schemars::JsonSchema;
+use semver::Version;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::package::{verify_installed, SignatureStatus, TrustStore};
 
 /// Deserialized `deskulpt.conf.json`.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, JsonSchema)]
 #[serde(rename_all = "camelCase")]
+#[schemars(title = "Deskulpt widget configuration")]
 pub struct DeskulptConf {
+    /// The JSON schema URL.
+    ///
+    /// This is only for editor support and is otherwise ignored. The schema is
+    /// published at <https://deskulpt-apps.github.io/deskulpt-conf-schema.json>.
+    #[serde(rename = "$schema", default)]
+    #[allow(dead_code)]
+    schema: Option<String>,
+    /// The stable identifier of the widget.
+    ///
+    /// If specified, this is used as the widget ID instead of the path of the
+    /// widget directory, so that the widget keeps its settings when its
+    /// directory is renamed or moved. It may only contain ASCII letters,
+    /// digits, `-`, `_` and `.`, and must be unique within its widget root.
+    #[serde(default)]
+    pub id: Option<String>,
     /// The name of the widget.
     ///
     /// This is purely used for display purposes. It does not need to be related
@@ -30,6 +51,300 @@ pub struct DeskulptConf {
     /// This is useful for temporarily disabling a widget without removing it.
     #[serde(default, skip_serializing)]
     ignore: bool,
+    /// Descriptive metadata of the widget.
+    #[serde(flatten)]
+    pub metadata: WidgetMetadata,
+    /// Default geometry and placement hints of the widget.
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    /// Widget-specific bundler configuration.
+    #[serde(default)]
+    pub build: BuildConfig,
+    /// Permissions requested by the widget.
+    #[serde(default)]
+    pub permissions: PermissionsConfig,
+}
+
+/// Descriptive metadata of a widget.
+///
+/// These fields are all optional in `deskulpt.conf.json`, specified at the top
+/// level next to `name` and `entry`. They are mostly for display purposes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WidgetMetadata {
+    /// A short description of the widget.
+    pub description: Option<String>,
+    /// The version of the widget, following semantic versioning.
+    pub version: Option<String>,
+    /// The authors of the widget.
+    pub authors: Vec<String>,
+    /// The URL to the homepage of the widget.
+    pub homepage: Option<String>,
+    /// The license of the widget, preferably as an SPDX identifier.
+    pub license: Option<String>,
+    /// The icon of the widget.
+    ///
+    /// This is the path to an image file relative to the widget directory.
+    pub icon: Option<String>,
+    /// Tags for categorizing the widget.
+    pub tags: Vec<String>,
+    /// The minimum version of Deskulpt required by the widget.
+    ///
+    /// A widget that requires a newer version of Deskulpt than the running one
+    /// fails to load.
+    pub min_deskulpt_version: Option<String>,
+}
+
+impl WidgetMetadata {
+    /// Validate the metadata against the widget directory.
+    fn validate(&self, dir: &Path) -> Result<()> {
+        if let Some(version) = &self.version {
+            Version::parse(version)
+                .with_context(|| format!("Invalid widget version: {version:?}"))?;
+        }
+
+        if let Some(icon) = &self.icon {
+            let escapes = Path::new(icon).components().any(|c| {
+                matches!(
+                    c,
+                    Component::ParentDir | Component::RootDir | Component::Prefix(_)
+                )
+            });
+            if escapes {
+                bail!("Icon must be a relative path within the widget directory; got {icon:?}");
+            }
+            if !dir.join(icon).is_file() {
+                bail!("Icon not found: {icon:?}");
+            }
+        }
+
+        if let Some(required) = &self.min_deskulpt_version {
+            let required = Version::parse(required)
+                .with_context(|| format!("Invalid minDeskulptVersion: {required:?}"))?;
+            let current = Version::parse(env!("CARGO_PKG_VERSION"))?;
+            if required > current {
+                bail!(
+                    "Widget requires Deskulpt {required} or newer, but the current version is \
+                     {current}"
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Preferred anchor of a widget on the canvas.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, specta::Type,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+/// Default geometry and placement hints of a widget.
+///
+/// This is the `layout` section of `deskulpt.conf.json`. The default size and
+/// anchor only take effect when the widget is discovered for the first time,
+/// while the size constraints are enforced whenever the widget is resized.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LayoutConfig {
+    /// The default width in pixels.
+    pub width: Option<u32>,
+    /// The default height in pixels.
+    pub height: Option<u32>,
+    /// The minimum width in pixels.
+    pub min_width: Option<u32>,
+    /// The minimum height in pixels.
+    pub min_height: Option<u32>,
+    /// The maximum width in pixels.
+    pub max_width: Option<u32>,
+    /// The maximum height in pixels.
+    pub max_height: Option<u32>,
+    /// The aspect ratio (width divided by height) to maintain.
+    pub aspect_ratio: Option<f64>,
+    /// The preferred anchor on the canvas.
+    ///
+    /// New widgets are placed in the free space closest to this anchor.
+    pub anchor: Anchor,
+}
+
+impl LayoutConfig {
+    /// Validate the layout configuration.
+    fn validate(&self) -> Result<()> {
+        let mut errors = vec![];
+
+        if let (Some(min), Some(max)) = (self.min_width, self.max_width) {
+            if min > max {
+                errors.push(format!("minWidth ({min}) is greater than maxWidth ({max})"));
+            }
+        }
+        if let (Some(min), Some(max)) = (self.min_height, self.max_height) {
+            if min > max {
+                errors.push(format!(
+                    "minHeight ({min}) is greater than maxHeight ({max})"
+                ));
+            }
+        }
+        if let Some(ratio) = self.aspect_ratio {
+            if !ratio.is_finite() || ratio <= 0.0 {
+                errors.push(format!(
+                    "aspectRatio must be a positive number; got {ratio}"
+                ));
+            }
+        }
+
+        if !errors.is_empty() {
+            bail!("{}", errors.join("\n"));
+        }
+        Ok(())
+    }
+
+    /// Constrain a size to the declared limits.
+    ///
+    /// If an aspect ratio is declared, the height is derived from the width,
+    /// unless that violates the height limits, in which case the width is
+    /// derived from the clamped height instead.
+    pub fn constrain(&self, width: u32, height: u32) -> (u32, u32) {
+        let clamp_width = |width: u32| {
+            width
+                .max(self.min_width.unwrap_or(0))
+                .min(self.max_width.unwrap_or(u32::MAX))
+        };
+        let clamp_height = |height: u32| {
+            height
+                .max(self.min_height.unwrap_or(0))
+                .min(self.max_height.unwrap_or(u32::MAX))
+        };
+
+        let width = clamp_width(width);
+        let Some(ratio) = self.aspect_ratio else {
+            return (width, clamp_height(height));
+        };
+
+        let derived = (width as f64 / ratio).round() as u32;
+        let height = clamp_height(derived);
+        if height == derived {
+            return (width, height);
+        }
+        (clamp_width((height as f64 * ratio).round() as u32), height)
+    }
+
+    /// Resize from the current size with a partial new size.
+    ///
+    /// If only the height is given and an aspect ratio is declared, the width
+    /// is derived from the new height. The result is then constrained with
+    /// [`constrain`](Self::constrain).
+    pub fn resize(
+        &self,
+        current: (u32, u32),
+        width: Option<u32>,
+        height: Option<u32>,
+    ) -> (u32, u32) {
+        let width = match (width, height, self.aspect_ratio) {
+            (None, Some(height), Some(ratio)) => (height as f64 * ratio).round() as u32,
+            (width, _, _) => width.unwrap_or(current.0),
+        };
+        self.constrain(width, height.unwrap_or(current.1))
+    }
+}
+
+/// Widget-specific bundler configuration.
+///
+/// This is the `build` section of `deskulpt.conf.json`. Each field is optional
+/// and, if specified, is merged over the defaults of the widget bundler.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BuildConfig {
+    /// Import aliases.
+    ///
+    /// This maps import specifiers (or specifier prefixes, e.g. `@/`) to paths
+    /// relative to the widget directory (e.g. `src/`). Aliases must stay within
+    /// the widget directory and cannot override the packages provided by
+    /// Deskulpt at runtime.
+    pub alias: BTreeMap<String, String>,
+    /// Global constants to replace at build time.
+    ///
+    /// This maps identifiers or property accesses to JavaScript expressions,
+    /// e.g. `"__DEBUG__": "true"` or `"process.env.API": "\"/api\""`.
+    pub define: BTreeMap<String, String>,
+    /// The import source for the automatic JSX runtime.
+    ///
+    /// Defaults to `@deskulpt-test/emotion` if not specified.
+    pub jsx_import_source: Option<String>,
+    /// Whether to minify the bundled code.
+    ///
+    /// Defaults to `true` if not specified. Turning this off can be useful for
+    /// debugging.
+    pub minify: Option<bool>,
+}
+
+/// Permissions requested by a widget.
+///
+/// This is the `permissions` section of `deskulpt.conf.json`. Each field
+/// declares what the widget may access through the plugin of the same name,
+/// and is passed to that plugin as is.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PermissionsConfig {
+    /// The hosts that the widget may send HTTP requests to.
+    ///
+    /// Each host may be followed by a port to only allow that port, e.g.
+    /// `localhost:8080`, and may start with `*.` to allow all of its
+    /// subdomains, e.g. `*.example.com`.
+    pub http: Vec<String>,
+    /// The shell commands that the widget may run.
+    pub shell: ShellPermissionsConfig,
+    /// The sensitive system information that the widget may read.
+    pub sys: SysPermissionsConfig,
+}
+
+/// Shell commands that a widget may run.
+///
+/// This is the `permissions.shell` section of `deskulpt.conf.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShellPermissionsConfig {
+    /// The commands that the widget may run, with any arguments.
+    ///
+    /// Each command is either the name of a program looked up in `PATH`, e.g.
+    /// `git`, or a path relative to the widget directory, e.g.
+    /// `./scripts/status.sh`. Note that allowing an interpreter such as `sh`
+    /// or `python` effectively allows any command.
+    pub commands: Vec<String>,
+    /// Extra directories that the commands may run in.
+    ///
+    /// Commands run in the widget directory by default, and may run in its
+    /// subdirectories or in these directories and their subdirectories.
+    /// Relative paths are relative to the widget directory.
+    pub dirs: Vec<String>,
+    /// Environment variables that the widget may set for the commands.
+    ///
+    /// Commands inherit the environment of Deskulpt, and only these variables
+    /// may be added or overridden. Note that many variables make programs run
+    /// other code, e.g. `LD_PRELOAD`, `BASH_ENV`, `NODE_OPTIONS` or
+    /// `GIT_SSH_COMMAND`, so allowing them is like allowing an interpreter.
+    pub env: Vec<String>,
+}
+
+/// Sensitive system information that a widget may read.
+///
+/// This is the `permissions.sys` section of `deskulpt.conf.json`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema, specta::Type)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SysPermissionsConfig {
+    /// Whether the widget may read the command lines of processes.
+    ///
+    /// Command lines may contain secrets such as tokens passed as arguments,
+    /// so they are omitted from process lists unless this is enabled.
+    pub process_command_lines: bool,
 }
 
 /// Deserialized `package.json`.
@@ -56,7 +371,13 @@ trait LoadFromFile: Sized + DeserializeOwned {
         }
         let file = File::open(path)?;
         let reader = BufReader::new(file);
-        let config = serde_json::from_reader(reader)?;
+        let mut deserializer = serde_json::Deserializer::from_reader(reader);
+        let config = serde_path_to_error::deserialize(&mut deserializer).map_err(|e| {
+            match e.path().to_string().as_str() {
+                "." => anyhow!("{}", e.inner()),
+                path => anyhow!("Invalid value at {path:?}: {}", e.inner()),
+            }
+        })?;
         Ok(Some(config))
     }
 }
@@ -70,15 +391,25 @@ impl LoadFromFile for PackageJson {
 }
 
 /// Full configuration of a Deskulpt widget.
-#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub struct WidgetConfig {
+    /// The stable identifier of the widget, if specified.
+    pub id: Option<String>,
     /// The name of the widget.
     pub name: String,
     /// The entry point of the widget.
     pub entry: String,
     /// The dependencies of the widget.
     pub dependencies: HashMap<String, String>,
+    /// Descriptive metadata of the widget.
+    pub metadata: WidgetMetadata,
+    /// Default geometry and placement hints of the widget.
+    pub layout: LayoutConfig,
+    /// Widget-specific bundler configuration.
+    pub build: BuildConfig,
+    /// Permissions requested by the widget.
+    pub permissions: PermissionsConfig,
 }
 
 impl WidgetConfig {
@@ -94,34 +425,362 @@ impl WidgetConfig {
                 _ => return Ok(None),
             };
 
+        if let Some(id) = &deskulpt_conf.id {
+            let valid = !id.is_empty()
+                && id
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+            if !valid {
+                bail!(
+                    "Invalid widget ID {id:?} in deskulpt.conf.json: only ASCII letters, digits, \
+                     '-', '_' and '.' are allowed"
+                );
+            }
+        }
+
+        deskulpt_conf
+            .metadata
+            .validate(dir)
+            .context("Invalid widget metadata in deskulpt.conf.json")?;
+        deskulpt_conf
+            .layout
+            .validate()
+            .context("Invalid layout in deskulpt.conf.json")?;
+
         let package_json = PackageJson::load(dir)
             .context("Failed to load package.json")?
             .unwrap_or_default();
 
         Ok(Some(WidgetConfig {
+            id: deskulpt_conf.id,
             name: deskulpt_conf.name,
             entry: deskulpt_conf.entry,
             dependencies: package_json.dependencies,
+            metadata: deskulpt_conf.metadata,
+            layout: deskulpt_conf.layout,
+            build: deskulpt_conf.build,
+            permissions: deskulpt_conf.permissions,
         }))
     }
 }
 
+/// The maximum depth of widget discovery.
+///
+/// Top-level subdirectories of the widgets directory are at depth 1.
+const MAX_DEPTH: usize = 4;
+
 /// The widget catalog.
 ///
 /// This is a collection of all widgets discovered locally, mapped from their
 /// widget IDs to their configurations.
 #[derive(Debug, Default, Clone, Serialize, Deserialize, specta::Type)]
-pub struct WidgetCatalog(pub BTreeMap<String, Outcome<WidgetConfig>>);
+pub struct WidgetCatalog {
+    /// The mapping from widget IDs to their configurations.
+    ///
+    /// Widget IDs are the stable identifiers declared in the widget
+    /// configurations if any, and otherwise the paths of the widget directories
+    /// relative to their widget root, using `/` as the separator, e.g.
+    /// `work/jira-board`. Widgets in extra widget roots are prefixed with the
+    /// namespace of the root, e.g. `shared:work/jira-board`. A widget whose ID
+    /// is already used by another widget is reported as an error under a
+    /// unique key, e.g. `work/jira-board#2`.
+    pub widgets: BTreeMap<String, Outcome<WidgetConfig>>,
+    /// The mapping from groups to the IDs of the widgets directly in them.
+    ///
+    /// Groups are the paths of non-widget directories that contain widgets,
+    /// e.g. `work` or `shared:work`. Top-level widgets of each widget root do
+    /// not belong to any group.
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// Errors that are not specific to a single widget.
+    ///
+    /// These include invalid or unreadable extra widget roots.
+    pub errors: Vec<String>,
+    /// The namespaces of the extra widget roots that could not be scanned.
+    ///
+    /// Widgets in these roots are missing from the catalog, but they may only
+    /// be unavailable for now, e.g., on a detached drive.
+    #[serde(skip)]
+    pub unavailable_roots: BTreeSet<String>,
+    /// The mapping from widget IDs to their signature statuses.
+    ///
+    /// This is not filled when loading the catalog, but needs to be checked
+    /// against a trust store with
+    /// [`verify_signatures`](Self::verify_signatures).
+    pub signatures: BTreeMap<String, SignatureStatus>,
+    /// The mapping from widget IDs to their directories.
+    #[serde(skip)]
+    pub dirs: BTreeMap<String, PathBuf>,
+    /// The mapping from widget IDs to the fingerprints of their contents.
+    ///
+    /// See [`fingerprint`] for more details.
+    #[serde(skip)]
+    pub fingerprints: BTreeMap<String, String>,
+}
+
+/// The difference between two widget catalogs.
+#[derive(Debug, Default, Clone, Serialize, Deserialize, specta::Type)]
+pub struct WidgetCatalogDiff {
+    /// Newly discovered widgets, mapped from their IDs to their configurations.
+    pub added: BTreeMap<String, Outcome<WidgetConfig>>,
+    /// The IDs of widgets that are no longer discovered.
+    pub removed: Vec<String>,
+    /// Widgets that have changed, mapped from their IDs to their new
+    /// configurations.
+    ///
+    /// A widget is considered changed if its configuration, its directory, or
+    /// the fingerprint of its contents has changed.
+    pub changed: BTreeMap<String, Outcome<WidgetConfig>>,
+    /// The IDs of widgets that have not changed.
+    pub unchanged: Vec<String>,
+    /// The full mapping from groups to the IDs of the widgets directly in them.
+    ///
+    /// See [`WidgetCatalog::groups`] for more details.
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// The full mapping from widget IDs to their signature statuses.
+    ///
+    /// See [`WidgetCatalog::signatures`] for more details.
+    pub signatures: BTreeMap<String, SignatureStatus>,
+}
 
 impl WidgetCatalog {
     /// Load the widget catalog from the given directory.
     ///
-    /// This scans all top-level subdirectories and attempts to load them as
-    /// widgets. Non-widget directories are simply ignored. See
-    /// [`WidgetConfig::load`] for more details.
+    /// This recursively scans the subdirectories up to [`MAX_DEPTH`] and
+    /// attempts to load them as widgets. Directories that are not widgets are
+    /// treated as groups and scanned further, while widget directories are not.
+    /// `node_modules` and directories whose names start with a dot are skipped.
+    /// See [`WidgetConfig::load`] for more details.
     pub fn load(dir: &Path) -> Result<Self> {
+        Self::load_with_roots(dir, &BTreeMap::new())
+    }
+
+    /// Load the widget catalog from the given directory and extra widget roots.
+    ///
+    /// The extra widget roots are mapped from their namespaces to directories.
+    /// Each of them is scanned in the same way as the main directory in
+    /// [`load`](Self::load), with the discovered widget IDs prefixed by the
+    /// namespace. Problems with an extra root do not fail the whole catalog but
+    /// are recorded in [`errors`](Self::errors) and
+    /// [`unavailable_roots`](Self::unavailable_roots). If the same widget
+    /// directory is discovered more than once, e.g. because the roots
+    /// overlap, only the first occurrence is loaded and the others are
+    /// reported as conflicts.
+    pub fn load_with_roots(dir: &Path, roots: &BTreeMap<String, PathBuf>) -> Result<Self> {
         let mut catalog = Self::default();
+        let mut seen = HashMap::new();
+        catalog.load_root(dir, "", &mut seen)?;
+
+        for (namespace, root) in roots {
+            let valid = !namespace.is_empty()
+                && namespace
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid {
+                catalog.errors.push(format!(
+                    "Invalid namespace {namespace:?} for widget root {}: only ASCII letters, \
+                     digits, '-' and '_' are allowed",
+                    root.display()
+                ));
+                catalog.unavailable_roots.insert(namespace.clone());
+                continue;
+            }
+            if !root.is_dir() {
+                catalog.errors.push(format!(
+                    "Widget root {namespace:?} is not a directory: {}",
+                    root.display()
+                ));
+                catalog.unavailable_roots.insert(namespace.clone());
+                continue;
+            }
+            if let Err(e) = catalog.load_root(root, &format!("{namespace}:"), &mut seen) {
+                catalog.errors.push(format!(
+                    "Failed to scan widget root {namespace:?} at {}: {e:?}",
+                    root.display()
+                ));
+                catalog.unavailable_roots.insert(namespace.clone());
+            }
+        }
+
+        for ids in catalog.groups.values_mut() {
+            ids.sort();
+        }
+        Ok(catalog)
+    }
+
+    /// Compute the difference from this catalog to a new catalog.
+    pub fn diff(&self, new: &WidgetCatalog) -> WidgetCatalogDiff {
+        let mut diff = WidgetCatalogDiff {
+            groups: new.groups.clone(),
+            signatures: new.signatures.clone(),
+            ..Default::default()
+        };
+
+        for (id, config) in &new.widgets {
+            match self.widgets.get(id) {
+                None => {
+                    diff.added.insert(id.clone(), config.clone());
+                },
+                Some(old) => {
+                    if old != config
+                        || self.dirs.get(id) != new.dirs.get(id)
+                        || self.fingerprints.get(id) != new.fingerprints.get(id)
+                    {
+                        diff.changed.insert(id.clone(), config.clone());
+                    } else {
+                        diff.unchanged.push(id.clone());
+                    }
+                },
+            }
+        }
+        diff.removed = self
+            .widgets
+            .keys()
+            .filter(|id| !new.widgets.contains_key(*id))
+            .cloned()
+            .collect();
+
+        diff
+    }
+
+    /// Check the signature statuses of all widgets against a trust store.
+    ///
+    /// See [`verify_installed`](crate::package::verify_installed) for more
+    /// details.
+    pub fn verify_signatures(&mut self, trust_store: &TrustStore) {
+        self.signatures = self
+            .dirs
+            .iter()
+            .map(|(id, dir)| (id.clone(), verify_installed(dir, trust_store)))
+            .collect();
+    }
+
+    /// Load the widgets in a widget root into the catalog.
+    ///
+    /// `prefix` is prepended to all discovered widget IDs and groups. `seen`
+    /// maps the canonical paths of the widget directories that have been
+    /// loaded to their IDs.
+    ///
+    /// Stable identifiers are resolved before path-based IDs, so a widget
+    /// declaring a stable identifier always keeps it regardless of the scan
+    /// order, unless another widget declares the same one. A widget whose ID
+    /// is already taken is loaded as an error under a unique key derived from
+    /// its path-based ID, e.g. `work/jira-board#2`.
+    fn load_root(
+        &mut self,
+        dir: &Path,
+        prefix: &str,
+        seen: &mut HashMap<PathBuf, String>,
+    ) -> Result<()> {
+        let mut widgets = vec![];
+        Self::scan(dir, None, 1, &mut widgets)?;
+        widgets.sort_by(|a, b| a.rel.cmp(&b.rel));
+
+        let mut ids = vec![None; widgets.len()];
+        let mut taken: HashMap<String, PathBuf> = HashMap::new();
+        for (widget, id) in widgets.iter_mut().zip(ids.iter_mut()) {
+            let Outcome::Ok(WidgetConfig {
+                id: Some(stable_id),
+                ..
+            }) = &widget.config
+            else {
+                continue;
+            };
+            let stable_id = format!("{prefix}{stable_id}");
+            match taken.get(&stable_id) {
+                Some(other) => {
+                    widget.config = Outcome::Err(format!(
+                        "Widget ID {stable_id:?} is already used by the widget at {}",
+                        other.display()
+                    ));
+                },
+                None => {
+                    taken.insert(stable_id.clone(), widget.path.clone());
+                    *id = Some(stable_id);
+                },
+            }
+        }
+
+        let path_ids = widgets
+            .iter()
+            .map(|widget| format!("{prefix}{}", widget.rel))
+            .collect::<HashSet<_>>();
+        for (widget, id) in widgets.iter_mut().zip(ids.iter_mut()) {
+            if id.is_some() {
+                continue;
+            }
+            let path_id = format!("{prefix}{}", widget.rel);
+            let resolved = match taken.get(&path_id) {
+                Some(other) => {
+                    if let Outcome::Ok(_) = widget.config {
+                        widget.config = Outcome::Err(format!(
+                            "Widget ID {path_id:?} is already used by the widget at {}",
+                            other.display()
+                        ));
+                    }
+                    (2..)
+                        .map(|n| format!("{path_id}#{n}"))
+                        .find(|key| !taken.contains_key(key) && !path_ids.contains(key))
+                        .unwrap()
+                },
+                None => path_id,
+            };
+            taken.insert(resolved.clone(), widget.path.clone());
+            *id = Some(resolved);
+        }
+
+        for (widget, id) in widgets.into_iter().zip(ids) {
+            let ScannedWidget {
+                path,
+                group,
+                config,
+                ..
+            } = widget;
+            let id = id.unwrap();
+
+            let canonical = dunce::canonicalize(&path).unwrap_or_else(|_| path.clone());
+            let config = match seen.get(&canonical) {
+                Some(other) => Outcome::Err(format!(
+                    "Widget directory {} conflicts with widget {other:?}, which is loaded from \
+                     the same directory",
+                    path.display()
+                )),
+                None => {
+                    seen.insert(canonical, id.clone());
+                    config
+                },
+            };
+
+            match fingerprint(&path, &config) {
+                Ok(fingerprint) => {
+                    self.fingerprints.insert(id.clone(), fingerprint);
+                },
+                Err(e) => eprintln!("Failed to fingerprint {}: {e:?}", path.display()),
+            }
 
+            if let Some(group) = group {
+                self.groups
+                    .entry(format!("{prefix}{group}"))
+                    .or_default()
+                    .push(id.clone());
+            }
+            self.dirs.insert(id.clone(), path);
+            self.widgets.insert(id, config);
+        }
+
+        Ok(())
+    }
+
+    /// Scan a directory for widgets.
+    ///
+    /// `group` is the path of the directory relative to its widget root, or
+    /// `None` for the widget root itself. Discovered widgets are appended to
+    /// `widgets` without resolving their IDs.
+    fn scan(
+        dir: &Path,
+        group: Option<&str>,
+        depth: usize,
+        widgets: &mut Vec<ScannedWidget>,
+    ) -> Result<()> {
         let entries = std::fs::read_dir(dir)?;
         for entry in entries {
             let entry = entry?;
@@ -131,18 +790,126 @@ impl WidgetCatalog {
                 continue; // Non-directory entries are not widgets, skip
             }
 
-            if let Some(config) = WidgetConfig::load(&path)
-                .map(|opt| opt.map(Outcome::Ok))
-                .unwrap_or_else(|e| Some(Outcome::Err(format!("{e:?}"))))
-            {
-                // Since each widget must be at the top level of the widgets
-                // directory, the directory names must be unique and we can use
-                // them as widget IDs
-                let id = entry.file_name().to_string_lossy().to_string();
-                catalog.0.insert(id, config);
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') || name == "node_modules" {
+                continue;
             }
+            let rel = match group {
+                Some(group) => format!("{group}/{name}"),
+                None => name,
+            };
+
+            let config = match WidgetConfig::load(&path) {
+                Ok(Some(config)) => Outcome::Ok(config),
+                Ok(None) => {
+                    // Directories without a configuration file are groups,
+                    // while those with one are ignored widgets
+                    if depth < MAX_DEPTH && !path.join(DeskulptConf::FILE_NAME).exists() {
+                        if let Err(e) = Self::scan(&path, Some(&rel), depth + 1, widgets) {
+                            eprintln!("Failed to scan {}: {e:?}", path.display());
+                        }
+                    }
+                    continue;
+                },
+                Err(e) => Outcome::Err(format!("{e:?}")),
+            };
+
+            widgets.push(ScannedWidget {
+                path,
+                rel,
+                group: group.map(String::from),
+                config,
+            });
         }
 
-        Ok(catalog)
+        Ok(())
+    }
+}
+
+/// A widget discovered by [`WidgetCatalog::scan`] whose ID is not resolved.
+struct ScannedWidget {
+    /// The widget directory.
+    path: PathBuf,
+    /// The path of the widget directory relative to its widget root.
+    rel: String,
+    /// The group of the widget, if any.
+    group: Option<String>,
+    /// The widget configuration.
+    config: Outcome<WidgetConfig>,
+}
+
+/// Compute the fingerprint of the contents of a widget directory.
+///
+/// The fingerprint is the SHA-256 digest of the widget configuration file, the
+/// `package.json` file and the entry file if they exist. It does not depend on
+/// the location of the widget directory, so it can be used to recognize a
+/// widget after its directory is renamed or moved.
+pub fn fingerprint(dir: &Path, config: &Outcome<WidgetConfig>) -> Result<String> {
+    let mut hasher = Sha256::new();
+    hasher.update(std::fs::read(dir.join(DeskulptConf::FILE_NAME))?);
+    let package_json = dir.join(PackageJson::FILE_NAME);
+    if package_json.exists() {
+        hasher.update(std::fs::read(package_json)?);
+    }
+    if let Outcome::Ok(config) = config {
+        let entry = dir.join(&config.entry);
+        if entry.exists() {
+            hasher.update(std::fs::read(entry)?);
+        }
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Write the configuration of a widget at a path relative to the root.
+    fn widget(root: &TempDir, rel: &str, conf: &str) {
+        let dir = root.path().join(rel);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(DeskulptConf::FILE_NAME), conf).unwrap();
+    }
+
+    #[test]
+    fn test_stable_id_takes_precedence_over_path_id() {
+        let root = TempDir::new().unwrap();
+        // "a" is scanned before "b" in path order, but its path-based ID is
+        // the stable identifier declared by "b"
+        widget(&root, "a", r#"{ "name": "A", "entry": "index.jsx" }"#);
+        widget(
+            &root,
+            "b",
+            r#"{ "id": "a", "name": "B", "entry": "index.jsx" }"#,
+        );
+
+        let catalog = WidgetCatalog::load(root.path()).unwrap();
+        assert_eq!(catalog.widgets.len(), 2);
+        assert!(matches!(&catalog.widgets["a"], Outcome::Ok(config) if config.name == "B"));
+        assert_eq!(catalog.dirs["a"], root.path().join("b"));
+        assert!(matches!(&catalog.widgets["a#2"], Outcome::Err(e) if e.contains("\"a\"")));
+        assert_eq!(catalog.dirs["a#2"], root.path().join("a"));
+    }
+
+    #[test]
+    fn test_duplicate_stable_ids() {
+        let root = TempDir::new().unwrap();
+        widget(
+            &root,
+            "x",
+            r#"{ "id": "w", "name": "X", "entry": "index.jsx" }"#,
+        );
+        widget(
+            &root,
+            "y",
+            r#"{ "id": "w", "name": "Y", "entry": "index.jsx" }"#,
+        );
+
+        let catalog = WidgetCatalog::load(root.path()).unwrap();
+        assert_eq!(catalog.widgets.len(), 2);
+        assert!(matches!(&catalog.widgets["w"], Outcome::Ok(config) if config.name == "X"));
+        assert!(matches!(&catalog.widgets["y"], Outcome::Err(e) if e.contains("\"w\"")));
     }
 }
@@ -1,14 +1,74 @@
 //! Deskulpt core events.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use deskulpt_common::event::Event;
 use deskulpt_common::outcome::Outcome;
 use serde::{Deserialize, Serialize};
 
-use crate::config::WidgetCatalog;
+use crate::config::WidgetCatalogDiff;
+use crate::registry::WidgetUpdate;
 use crate::settings::Settings;
 
+/// Event for reporting the progress of bundling widgets.
+///
+/// This event is emitted from the backend to all frontend windows when each
+/// widget starts and finishes bundling.
+#[derive(Clone, Serialize, Deserialize, specta::Type, Event)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum BundleProgressEvent {
+    /// A widget has started bundling.
+    Started {
+        /// The ID of the widget.
+        id: String,
+    },
+    /// A widget has been bundled successfully.
+    Finished {
+        /// The ID of the widget.
+        id: String,
+        /// The time taken to bundle the widget in milliseconds.
+        elapsed: u32,
+    },
+    /// A widget has failed to bundle.
+    Failed {
+        /// The ID of the widget.
+        id: String,
+        /// The time taken before the failure in milliseconds.
+        elapsed: u32,
+        /// The error message.
+        error: String,
+    },
+}
+
+/// Event for sending a sample of a metric.
+///
+/// This event is emitted from the backend to the canvas window whenever a
+/// metric topic with subscriptions is sampled. Widgets subscribed to the topic
+/// are responsible for picking up the samples with the matching topic key.
+#[derive(Clone, Serialize, Deserialize, specta::Type, Event)]
+pub struct MetricUpdateEvent {
+    /// The key of the metric topic.
+    pub topic: String,
+    /// The sample of the metric.
+    pub sample: Outcome<serde_json::Value>,
+}
+
+/// Event for sending data from a plugin to a widget.
+///
+/// This event is emitted from the backend to the canvas window whenever a
+/// plugin emits an event to a widget, e.g., output of a process spawned by the
+/// shell plugin. Widgets are responsible for picking up the events with their
+/// own IDs and the name of the plugin.
+#[derive(Clone, Serialize, Deserialize, specta::Type, Event)]
+pub struct PluginEvent {
+    /// The name of the plugin that emitted the event.
+    pub plugin: String,
+    /// The ID of the widget that the event is for.
+    pub id: String,
+    /// The payload of the event, interpreted by the plugin and the widget.
+    pub payload: serde_json::Value,
+}
+
 /// Event for rendering widgets.
 ///
 /// This event is emitted from the backend to the canvas window to instruct it
@@ -38,9 +98,18 @@ pub enum ShowToastEvent {
 #[derive(Clone, Serialize, Deserialize, specta::Type, Event)]
 pub struct UpdateSettingsEvent(pub Settings);
 
-/// Event for updating the widget catalog.
+/// Event for notifying changes to the widget catalog.
 ///
-/// This event is emitted from the backend to all frontend windows whenever
-/// there is a change in the widget catalog.
+/// This event is emitted from the backend to all frontend windows whenever the
+/// widget catalog is rescanned. Only the difference from the previous catalog
+/// is included, so that windows can leave unchanged widgets untouched.
+#[derive(Clone, Serialize, Deserialize, specta::Type, Event)]
+pub struct WidgetCatalogDiffEvent(pub WidgetCatalogDiff);
+
+/// Event for reporting available widget updates.
+///
+/// This event is emitted from the backend to the manager window whenever the
+/// widget registry is checked for updates. The event carries a mapping from
+/// the IDs of the widgets with available updates to the update information.
 #[derive(Clone, Serialize, Deserialize, specta::Type, Event)]
-pub struct UpdateWidgetCatalogEvent(pub WidgetCatalog);
+pub struct WidgetUpdatesEvent(pub BTreeMap<String, WidgetUpdate>);
@@ -5,13 +5,16 @@
 )]
 
 use tauri::plugin::TauriPlugin;
-use tauri::Runtime;
+use tauri::{RunEvent, Runtime};
 
 mod bundler;
 mod commands;
 mod config;
 pub mod events;
+mod package;
 pub mod path;
+mod registry;
+mod scaffold;
 mod settings;
 pub mod states;
 pub mod tray;
@@ -21,10 +24,26 @@ deskulpt_common::bindings::configure_bindings_builder!();
 
 /// Initialize the plugin.
 pub fn init<R: Runtime>() -> TauriPlugin<R> {
-    deskulpt_common::init::init_builder!().build()
+    deskulpt_common::init::init_builder!()
+        .on_event(|_, event| {
+            // Background processes of widgets would otherwise outlive Deskulpt
+            if let RunEvent::Exit = event {
+                commands::retain_plugin_processes(|_| false);
+            }
+        })
+        .build()
 }
 
 #[doc(hidden)]
 pub mod schema {
+    pub use crate::config::DeskulptConf;
     pub use crate::settings::Settings;
 }
+
+#[doc(hidden)]
+pub mod headless {
+    pub use crate::bundler::{BundleReport, WidgetBundlerBuilder};
+    pub use crate::config::{BuildConfig, WidgetCatalog, WidgetConfig};
+    pub use crate::package::{pack, PackageManifest};
+    pub use crate::scaffold::{create as create_widget, WidgetTemplate};
+}
@@ -0,0 +1,667 @@
+//! Widget packages.
+//!
+//! A widget package is a single-file zip archive of a widget directory. It
+//! contains `deskulpt.conf.json`, the widget sources or a prebuilt bundle, and
+//! a [`PackageManifest`] at the root of the archive. Installing a package
+//! extracts it into its own directory in the widgets directory, keeping the
+//! manifest there so that the widget can be recognized as installed.
+//!
+//! Packages can optionally be signed with an ed25519 key. The signature is
+//! stored in the manifest and covers the widget ID, the version, and the
+//! checksum of the package. Public keys of trusted signers are kept in a
+//! [`TrustStore`].
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::config::WidgetConfig;
+
+/// The name of the manifest file in a widget package.
+pub const MANIFEST_FILE: &str = "deskulpt.manifest.json";
+
+/// The name of the widget configuration file that every package must contain.
+const CONF_FILE: &str = "deskulpt.conf.json";
+
+/// The maximum total size in bytes of the extracted files in a package.
+pub const MAX_PACKAGE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Validate the ID of a widget package.
+///
+/// The ID is used as the name of the directory that the package is installed
+/// into, so it may only contain ASCII letters, digits, `-`, `_` and `.`, and
+/// cannot be `.` or `..`.
+pub(crate) fn validate_id(id: &str) -> Result<()> {
+    let valid = !id.is_empty()
+        && id != "."
+        && id != ".."
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !valid {
+        bail!("Invalid widget ID {id:?}: only ASCII letters, digits, '-', '_' and '.' are allowed");
+    }
+    Ok(())
+}
+
+/// The manifest of a widget package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageManifest {
+    /// The ID of the widget.
+    ///
+    /// This is also the name of the widget directory once installed. It may
+    /// only contain ASCII letters, digits, `-`, `_` and `.`.
+    pub id: String,
+    /// The version of the widget, copied from the widget configuration.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The mapping from file paths to the SHA-256 digests of their contents.
+    ///
+    /// File paths are relative to the root of the archive, using `/` as the
+    /// separator. All files in the archive except the manifest itself must be
+    /// listed here.
+    pub files: BTreeMap<String, String>,
+    /// The SHA-256 checksum of the package.
+    ///
+    /// This is computed over the sorted file paths and their digests, so it
+    /// covers the contents of the whole package. See
+    /// [`compute_checksum`](Self::compute_checksum).
+    pub checksum: String,
+    /// The signature of the package, if signed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<PackageSignature>,
+}
+
+/// The signature of a widget package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageSignature {
+    /// The hex-encoded ed25519 public key of the signer.
+    pub public_key: String,
+    /// The hex-encoded ed25519 signature of the
+    /// [signed payload](PackageManifest::signed_payload).
+    pub signature: String,
+}
+
+impl PackageSignature {
+    /// Verify the signature of a payload.
+    ///
+    /// This returns the public key of the signer if the signature is valid. It
+    /// does not check whether the signer is trusted.
+    fn verify(&self, payload: &[u8]) -> Result<VerifyingKey> {
+        let public_key = decode_hex::<32>(&self.public_key).context("Invalid public key")?;
+        let public_key = VerifyingKey::from_bytes(&public_key).context("Invalid public key")?;
+        let signature = decode_hex::<64>(&self.signature).context("Invalid signature")?;
+        public_key
+            .verify_strict(payload, &Signature::from_bytes(&signature))
+            .context("Signature verification failed")?;
+        Ok(public_key)
+    }
+}
+
+/// Decode a hex string into a fixed-size byte array.
+fn decode_hex<const N: usize>(s: &str) -> Result<[u8; N]> {
+    let mut bytes = [0; N];
+    hex::decode_to_slice(s.trim(), &mut bytes)?;
+    Ok(bytes)
+}
+
+/// The signature status of a widget.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(tag = "type", content = "content", rename_all = "camelCase")]
+pub enum SignatureStatus {
+    /// The widget is installed from a package signed by a trusted key.
+    ///
+    /// The content is the name of the trusted key in the trust store.
+    Verified(String),
+    /// The widget is not installed from a signed package, or the package is
+    /// signed by a key that is not in the trust store.
+    Unverified,
+    /// The signature of the package is invalid, or the installed files have
+    /// been modified since installation.
+    ///
+    /// The content is the reason why the signature is invalid.
+    Invalid(String),
+}
+
+/// The trust store of public keys of trusted package signers.
+///
+/// The trust store is a directory where each trusted key is stored in a file
+/// named `<name>.pub`, containing the hex-encoded ed25519 public key.
+#[derive(Debug, Default)]
+pub struct TrustStore(BTreeMap<String, VerifyingKey>);
+
+impl TrustStore {
+    /// Load the trust store from a directory.
+    ///
+    /// An empty trust store is returned if the directory does not exist. Keys
+    /// that cannot be loaded are skipped.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut keys = BTreeMap::new();
+        if !dir.exists() {
+            return Ok(Self(keys));
+        }
+
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("pub") {
+                continue;
+            }
+            let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let key = std::fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|s| decode_hex::<32>(&s))
+                .and_then(|bytes| Ok(VerifyingKey::from_bytes(&bytes)?));
+            match key {
+                Ok(key) => {
+                    keys.insert(name, key);
+                },
+                Err(e) => eprintln!("Failed to load trusted key {}: {e:?}", path.display()),
+            }
+        }
+        Ok(Self(keys))
+    }
+
+    /// Find the name of a trusted key.
+    fn find(&self, key: &VerifyingKey) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(_, trusted)| *trusted == key)
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+impl PackageManifest {
+    /// Compute the checksum over the file paths and their digests.
+    pub fn compute_checksum(files: &BTreeMap<String, String>) -> String {
+        let mut hasher = Sha256::new();
+        for (path, digest) in files {
+            hasher.update(path.as_bytes());
+            hasher.update([0]);
+            hasher.update(digest.as_bytes());
+            hasher.update([b'\n']);
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// The payload that is signed by the package signature.
+    ///
+    /// This covers the widget ID, the version, and the checksum, where the
+    /// checksum in turn covers the contents of the whole package.
+    pub fn signed_payload(&self) -> Vec<u8> {
+        format!(
+            "deskulpt-package\n{}\n{}\n{}",
+            self.id,
+            self.version.as_deref().unwrap_or_default(),
+            self.checksum
+        )
+        .into_bytes()
+    }
+
+    /// Sign the manifest with the given key.
+    pub fn sign(&mut self, key: &SigningKey) {
+        let signature = key.sign(&self.signed_payload());
+        self.signature = Some(PackageSignature {
+            public_key: hex::encode(key.verifying_key().as_bytes()),
+            signature: hex::encode(signature.to_bytes()),
+        });
+    }
+
+    /// Check the signature of the manifest against the trust store.
+    pub fn verify(&self, trust_store: &TrustStore) -> SignatureStatus {
+        let Some(signature) = &self.signature else {
+            return SignatureStatus::Unverified;
+        };
+        match signature.verify(&self.signed_payload()) {
+            Ok(key) => match trust_store.find(&key) {
+                Some(name) => SignatureStatus::Verified(name.to_string()),
+                None => SignatureStatus::Unverified,
+            },
+            Err(e) => SignatureStatus::Invalid(format!("{e:?}")),
+        }
+    }
+
+    /// Validate the manifest on its own.
+    ///
+    /// This checks that the ID is valid, that the widget configuration file is
+    /// included, and that the checksum matches the listed files. It does not
+    /// check the listed files against the actual contents of the archive.
+    fn validate(&self) -> Result<()> {
+        validate_id(&self.id)?;
+        if !self.files.contains_key(CONF_FILE) {
+            bail!("Package does not contain {CONF_FILE}");
+        }
+        if Self::compute_checksum(&self.files) != self.checksum {
+            bail!("Package checksum mismatch");
+        }
+        if let Some(signature) = &self.signature {
+            signature
+                .verify(&self.signed_payload())
+                .context("Invalid package signature")?;
+        }
+        Ok(())
+    }
+
+    /// Read the manifest of an installed widget.
+    ///
+    /// This returns `Ok(None)` if the widget directory does not contain a
+    /// manifest, i.e., the widget is not installed from a package.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file = File::open(path)?;
+        let manifest = serde_json::from_reader(file).context("Invalid package manifest")?;
+        Ok(Some(manifest))
+    }
+}
+
+/// A widget installed from a package.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledWidget {
+    /// The version of the installed package, if any.
+    pub version: Option<String>,
+    /// The checksum of the installed package.
+    pub checksum: String,
+}
+
+impl From<PackageManifest> for InstalledWidget {
+    fn from(manifest: PackageManifest) -> Self {
+        Self {
+            version: manifest.version,
+            checksum: manifest.checksum,
+        }
+    }
+}
+
+/// Compute the SHA-256 digest of some data as a hex string.
+fn sha256(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Collect the files to be packed from a widget directory.
+///
+/// `node_modules`, entries whose names start with a dot, and the manifest of a
+/// previously installed package are skipped. Paths are relative to `root`.
+fn collect_files(root: &Path, dir: &Path, files: &mut BTreeMap<String, Vec<u8>>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || name == "node_modules" {
+            continue;
+        }
+
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file() {
+            let rel = path
+                .strip_prefix(root)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            if rel == MANIFEST_FILE {
+                continue;
+            }
+            files.insert(rel, std::fs::read(&path)?);
+        }
+    }
+    Ok(())
+}
+
+/// Pack a widget directory into a widget package written to `out`.
+///
+/// The widget directory must contain a valid widget configuration. The ID of
+/// the package is the stable identifier in the configuration if declared, and
+/// otherwise the name of the directory. If a signing key is given, the package
+/// is signed with it.
+pub fn pack<W: Write + Seek>(
+    dir: &Path,
+    out: W,
+    signing_key: Option<&SigningKey>,
+) -> Result<PackageManifest> {
+    let config = WidgetConfig::load(dir)?
+        .ok_or_else(|| anyhow!("Not a widget directory: {}", dir.display()))?;
+    let id = match config.id {
+        Some(id) => id,
+        None => dir
+            .file_name()
+            .ok_or_else(|| anyhow!("Cannot infer widget ID from {}", dir.display()))?
+            .to_string_lossy()
+            .to_string(),
+    };
+
+    let mut contents = BTreeMap::new();
+    collect_files(dir, dir, &mut contents)?;
+    let files = contents
+        .iter()
+        .map(|(path, data)| (path.clone(), sha256(data)))
+        .collect::<BTreeMap<_, _>>();
+    let mut manifest = PackageManifest {
+        id,
+        version: config.metadata.version,
+        checksum: PackageManifest::compute_checksum(&files),
+        files,
+        signature: None,
+    };
+    if let Some(signing_key) = signing_key {
+        manifest.sign(signing_key);
+    }
+    manifest.validate()?;
+
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+    let mut writer = ZipWriter::new(out);
+    writer.start_file(MANIFEST_FILE, options)?;
+    writer.write_all(&serde_json::to_vec_pretty(&manifest)?)?;
+    for (path, data) in contents {
+        writer.start_file(path, options)?;
+        writer.write_all(&data)?;
+    }
+    writer.finish()?;
+
+    Ok(manifest)
+}
+
+/// Read and verify a widget package.
+///
+/// This rejects entries that are symlinks or that would be extracted outside
+/// of the widget directory, and verifies that the files in the archive match
+/// the manifest exactly. The returned files map relative paths to contents and
+/// include the manifest itself.
+fn read_package(archive: &Path) -> Result<(PackageManifest, BTreeMap<String, Vec<u8>>)> {
+    let file = File::open(archive)
+        .with_context(|| format!("Failed to open package {}", archive.display()))?;
+    let mut archive = ZipArchive::new(file).context("Invalid widget package")?;
+
+    let mut files = BTreeMap::new();
+    let mut total_size = 0u64;
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        let name = entry.name().to_string();
+        if entry.is_symlink() {
+            bail!("Package entry {name:?} is a symlink");
+        }
+        let Some(path) = entry.enclosed_name() else {
+            bail!("Package entry {name:?} escapes the widget directory");
+        };
+        if entry.is_dir() {
+            continue;
+        }
+
+        if total_size.saturating_add(entry.size()) > MAX_PACKAGE_SIZE {
+            bail!("Package exceeds the maximum size of {MAX_PACKAGE_SIZE} bytes");
+        }
+        let rel = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        // The declared size cannot be trusted, so the contents are read only up
+        // to the remaining budget
+        let mut data = vec![];
+        entry
+            .by_ref()
+            .take(MAX_PACKAGE_SIZE - total_size + 1)
+            .read_to_end(&mut data)?;
+        total_size += data.len() as u64;
+        if total_size > MAX_PACKAGE_SIZE {
+            bail!("Package exceeds the maximum size of {MAX_PACKAGE_SIZE} bytes");
+        }
+        if files.insert(rel, data).is_some() {
+            bail!("Duplicate package entry {name:?}");
+        }
+    }
+
+    let manifest: PackageManifest = serde_json::from_slice(
+        files
+            .get(MANIFEST_FILE)
+            .ok_or_else(|| anyhow!("Package does not contain {MANIFEST_FILE}"))?,
+    )
+    .context("Invalid package manifest")?;
+    manifest.validate()?;
+
+    for (path, data) in &files {
+        if path == MANIFEST_FILE {
+            continue;
+        }
+        match manifest.files.get(path) {
+            Some(digest) if *digest == sha256(data) => {},
+            Some(_) => bail!("Digest mismatch for {path:?}"),
+            None => bail!("File {path:?} is not listed in the manifest"),
+        }
+    }
+    if let Some(path) = manifest
+        .files
+        .keys()
+        .find(|path| !files.contains_key(*path))
+    {
+        bail!("File {path:?} listed in the manifest is missing");
+    }
+
+    Ok((manifest, files))
+}
+
+/// Read and verify the manifest of a widget package without installing it.
+pub fn read_manifest(archive: &Path) -> Result<PackageManifest> {
+    let (manifest, _) = read_package(archive)?;
+    Ok(manifest)
+}
+
+/// Write files into a directory.
+fn write_files(dir: &Path, files: &BTreeMap<String, Vec<u8>>) -> Result<()> {
+    for (path, data) in files {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, data)?;
+    }
+    Ok(())
+}
+
+/// Install a widget package into the widgets directory.
+///
+/// The package is verified and extracted into a staging directory first, so
+/// that a failed installation leaves the widgets directory untouched. If the
+/// widget directory already exists, this fails unless `replace` is set, in
+/// which case the existing directory must also be an installed package and is
+/// replaced as a whole. The existing directory is restored if the replacement
+/// fails.
+pub fn install(archive: &Path, widgets_dir: &Path, replace: bool) -> Result<PackageManifest> {
+    let (manifest, files) = read_package(archive)?;
+    let id = &manifest.id;
+
+    let target = widgets_dir.join(id);
+    if target.exists() {
+        if !replace {
+            bail!("Widget directory already exists: {}", target.display());
+        }
+        if PackageManifest::load(&target)?.is_none() {
+            bail!(
+                "Widget directory is not installed from a package: {}",
+                target.display()
+            );
+        }
+    }
+
+    // Staging and backup directories start with a dot so that they are never
+    // discovered as widgets
+    let staging = widgets_dir.join(format!(".install-{id}"));
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging)?;
+    }
+    let result = write_files(&staging, &files).and_then(|_| {
+        let config = WidgetConfig::load(&staging)?
+            .ok_or_else(|| anyhow!("Widget is marked as ignored in {CONF_FILE}"))?;
+        match config.id {
+            Some(conf_id) if conf_id != *id => {
+                bail!("Widget ID {conf_id:?} in {CONF_FILE} does not match {id:?} in the manifest")
+            },
+            _ => Ok(()),
+        }
+    });
+    if let Err(e) = result {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e.context("Failed to extract widget package"));
+    }
+
+    if !target.exists() {
+        std::fs::rename(&staging, &target)?;
+        return Ok(manifest);
+    }
+
+    let backup = widgets_dir.join(format!(".backup-{id}"));
+    if backup.exists() {
+        std::fs::remove_dir_all(&backup)?;
+    }
+    std::fs::rename(&target, &backup)?;
+    if let Err(e) = std::fs::rename(&staging, &target) {
+        std::fs::rename(&backup, &target)?;
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(e).context("Failed to replace the widget directory");
+    }
+    if let Err(e) = std::fs::remove_dir_all(&backup) {
+        eprintln!("Failed to remove {}: {e}", backup.display());
+    }
+    Ok(manifest)
+}
+
+/// Uninstall a widget installed from a package.
+///
+/// This removes the whole widget directory. Directories that are not installed
+/// from packages are refused, so that local widgets are never removed.
+pub fn uninstall(dir: &Path) -> Result<PackageManifest> {
+    let manifest = PackageManifest::load(dir)?.ok_or_else(|| {
+        anyhow!(
+            "Widget directory is not installed from a package: {}",
+            dir.display()
+        )
+    })?;
+    std::fs::remove_dir_all(dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    Ok(manifest)
+}
+
+/// Check the signature status of an installed widget.
+///
+/// Widgets that are not installed from packages are unverified. For installed
+/// widgets, the files listed in the manifest must be unmodified for the
+/// signature to be considered valid. Files added after installation, such as
+/// installed dependencies, are not checked.
+pub fn verify_installed(dir: &Path, trust_store: &TrustStore) -> SignatureStatus {
+    let result = PackageManifest::load(dir).and_then(|manifest| {
+        let Some(manifest) = manifest else {
+            return Ok(SignatureStatus::Unverified);
+        };
+        manifest.validate()?;
+        for (path, digest) in &manifest.files {
+            let data = std::fs::read(dir.join(path))
+                .with_context(|| format!("File {path:?} is missing"))?;
+            if sha256(&data) != *digest {
+                bail!("File {path:?} has been modified since installation");
+            }
+        }
+        Ok(manifest.verify(trust_store))
+    });
+    result.unwrap_or_else(|e| SignatureStatus::Invalid(format!("{e:?}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    /// Build an archive with a single entry written by `f`.
+    fn archive(f: impl FnOnce(&mut ZipWriter<Cursor<Vec<u8>>>, SimpleFileOptions)) -> Vec<u8> {
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        f(&mut writer, options);
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Build an archive with a single file.
+    fn single_file(name: &str, data: &[u8]) -> Vec<u8> {
+        archive(|writer, options| {
+            writer.start_file(name, options).unwrap();
+            writer.write_all(data).unwrap();
+        })
+    }
+
+    fn read_error(data: &[u8]) -> String {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("package.zip");
+        std::fs::write(&path, data).unwrap();
+        format!("{:?}", read_package(&path).unwrap_err())
+    }
+
+    #[test]
+    fn test_reject_parent_dir_entry() {
+        let error = read_error(&single_file("../evil.js", b"evil"));
+        assert!(error.contains("escapes the widget directory"), "{error}");
+    }
+
+    #[test]
+    fn test_reject_absolute_entry() {
+        let error = read_error(&single_file("/tmp/evil.js", b"evil"));
+        assert!(error.contains("escapes the widget directory"), "{error}");
+    }
+
+    #[test]
+    fn test_reject_symlink_entry() {
+        let data = archive(|writer, options| {
+            writer.add_symlink("link", "/etc", options).unwrap();
+        });
+        let error = read_error(&data);
+        assert!(error.contains("is a symlink"), "{error}");
+    }
+
+    #[test]
+    fn test_reject_oversize_entry() {
+        let data = archive(|writer, options| {
+            let options = options.large_file(true);
+            writer.start_file("big.bin", options).unwrap();
+            let chunk = vec![0; 1024 * 1024];
+            for _ in 0..=MAX_PACKAGE_SIZE / chunk.len() as u64 {
+                writer.write_all(&chunk).unwrap();
+            }
+        });
+        let error = read_error(&data);
+        assert!(error.contains("exceeds the maximum size"), "{error}");
+    }
+
+    #[test]
+    fn test_reject_oversize_entry_with_forged_size() {
+        let mut data = single_file("bomb.bin", &vec![0; MAX_PACKAGE_SIZE as usize + 1]);
+
+        // Declare the entry as 1 byte in both the local file header and the
+        // central directory
+        let forge = |data: &mut Vec<u8>, signature: &[u8], offset: usize| {
+            let pos = data
+                .windows(4)
+                .position(|window| window == signature)
+                .unwrap();
+            data[pos + offset..pos + offset + 4].copy_from_slice(&1u32.to_le_bytes());
+        };
+        forge(&mut data, b"PK\x03\x04", 22);
+        forge(&mut data, b"PK\x01\x02", 24);
+
+        let error = read_error(&data);
+        assert!(error.contains("exceeds the maximum size"), "{error}");
+    }
+}
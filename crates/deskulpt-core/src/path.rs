@@ -55,11 +55,6 @@ pub trait PathExt<R: Runtime>: Manager<R> {
         Ok(widgets_dir)
     }
 
-    /// Get a reference to a widget directory.
-    fn widget_dir(&self, id: &str) -> Result<PathBuf> {
-        Ok(self.widgets_dir()?.join(id))
-    }
-
     /// Initialize the persistence directory.
     ///
     /// This will create the persistence directory if it does not exist. It must
@@ -94,7 +89,68 @@ pub trait PathExt<R: Runtime>: Manager<R> {
         }
         Ok(persist_dir)
     }
+
+    /// Get the shared package cache directory.
+    ///
+    /// This is the `packages` subdirectory of the persistence directory. Widget
+    /// dependencies can be installed there once and shared across widgets, with
+    /// each version of a package in its own `<name>/<version>/` directory. This
+    /// directory is not created automatically since Deskulpt only reads from
+    /// it.
+    fn packages_dir(&self) -> Result<PathBuf> {
+        Ok(self.persist_dir()?.join("packages"))
+    }
+
+    /// Get the trust store directory.
+    ///
+    /// This is the `trusted-keys` subdirectory of the persistence directory,
+    /// which contains the public keys of trusted widget package signers. See
+    /// [`TrustStore`](crate::package::TrustStore) for the format. This
+    /// directory is not created automatically since Deskulpt only reads from
+    /// it.
+    fn trusted_keys_dir(&self) -> Result<PathBuf> {
+        Ok(self.persist_dir()?.join("trusted-keys"))
+    }
+
+    /// Get the storage directory.
+    ///
+    /// This is the `storage` subdirectory of the persistence directory, which
+    /// contains the storage directories of the widgets. See
+    /// [`widget_storage_dir`](PathExt::widget_storage_dir). This directory is
+    /// not created automatically since it is only needed once a widget stores
+    /// data.
+    fn storage_dir(&self) -> Result<PathBuf> {
+        Ok(self.persist_dir()?.join("storage"))
+    }
+
+    /// Get the storage directory of a widget.
+    ///
+    /// This is a subdirectory of the storage directory named after the widget
+    /// ID, where plugins persist data of the widget. See [`storage_name`] for
+    /// how the name is derived from the ID. This directory is not created
+    /// automatically.
+    fn widget_storage_dir(&self, id: &str) -> Result<PathBuf> {
+        Ok(self.storage_dir()?.join(storage_name(id)))
+    }
 }
 
 impl<R: Runtime> PathExt<R> for App<R> {}
 impl<R: Runtime> PathExt<R> for AppHandle<R> {}
+
+/// Get the name of the storage directory of a widget.
+///
+/// Since widget IDs may contain characters that are not allowed in file names,
+/// e.g. `/` and `:`, characters other than ASCII letters, digits, `-` and `_`
+/// are percent-encoded. The encoding is done byte by byte, so the name of an ID
+/// prefix is a prefix of the name of the ID.
+pub(crate) fn storage_name(id: &str) -> String {
+    let mut name = String::with_capacity(id.len());
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_') {
+            name.push(byte as char);
+        } else {
+            name.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    name
+}
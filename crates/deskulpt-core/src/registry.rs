@@ -0,0 +1,478 @@
+//! Widget registry.
+//!
+//! A widget registry is a static JSON index that lists the widget packages
+//! available for installation. The index can be hosted as a local file or
+//! served over plain HTTP, e.g., on an internal network. Package locations in
+//! the index are resolved relative to the location of the index itself.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use deskulpt_common::outcome::Outcome;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::config::WidgetCatalog;
+use crate::package::{self, PackageManifest, MAX_PACKAGE_SIZE};
+
+/// The timeout for fetching resources from a registry over HTTP.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An entry in the registry index.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    /// The display name of the widget.
+    pub name: String,
+    /// The latest version of the widget, following semantic versioning.
+    pub version: String,
+    /// The location of the widget package.
+    ///
+    /// This can be an absolute URL or a path relative to the index.
+    pub package: String,
+    /// The SHA-256 checksum of the widget package file as a hex string.
+    pub checksum: String,
+}
+
+/// The registry index.
+///
+/// ```json
+/// {
+///   "widgets": {
+///     "clock": {
+///       "name": "Clock",
+///       "version": "1.2.0",
+///       "package": "packages/clock-1.2.0.zip",
+///       "checksum": "<sha256>"
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryIndex {
+    /// The mapping from widget IDs to their registry entries.
+    pub widgets: BTreeMap<String, RegistryEntry>,
+}
+
+/// An update available for an installed widget.
+#[derive(Debug, Clone, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WidgetUpdate {
+    /// The display name of the widget in the registry.
+    pub name: String,
+    /// The installed version of the widget.
+    pub current: String,
+    /// The latest version of the widget in the registry.
+    pub latest: String,
+}
+
+/// A widget registry fetched from its index.
+#[derive(Debug, Clone)]
+pub struct Registry {
+    /// The resolved location of the index.
+    url: Url,
+    /// The registry index.
+    index: RegistryIndex,
+}
+
+/// Parse a registry location into a URL.
+///
+/// Locations that are not `file://` or `http://` URLs are treated as local
+/// file paths.
+fn parse_location(location: &str) -> Result<Url> {
+    match Url::parse(location) {
+        // Single-letter schemes are Windows drive letters
+        Ok(url) if url.scheme().len() > 1 => Ok(url),
+        _ => Url::from_file_path(location).map_err(|_| {
+            anyhow!("Registry location must be a URL or an absolute path: {location}")
+        }),
+    }
+}
+
+/// Fetch a resource from a `file://` or `http://` URL.
+///
+/// Resources larger than [`MAX_PACKAGE_SIZE`] are refused.
+fn fetch(url: &Url) -> Result<Vec<u8>> {
+    let reader: Box<dyn Read> = match url.scheme() {
+        "file" => {
+            let path = url
+                .to_file_path()
+                .map_err(|_| anyhow!("Invalid file URL: {url}"))?;
+            Box::new(
+                std::fs::File::open(&path)
+                    .with_context(|| format!("Failed to open {}", path.display()))?,
+            )
+        },
+        "http" => {
+            let response = ureq::get(url.as_str())
+                .timeout(FETCH_TIMEOUT)
+                .call()
+                .with_context(|| format!("Failed to fetch {url}"))?;
+            response.into_reader()
+        },
+        scheme => bail!("Unsupported registry URL scheme: {scheme}"),
+    };
+
+    let mut data = vec![];
+    reader
+        .take(MAX_PACKAGE_SIZE + 1)
+        .read_to_end(&mut data)
+        .with_context(|| format!("Failed to read {url}"))?;
+    if data.len() as u64 > MAX_PACKAGE_SIZE {
+        bail!("Resource exceeds {MAX_PACKAGE_SIZE} bytes: {url}");
+    }
+    Ok(data)
+}
+
+impl Registry {
+    /// Fetch the registry index from a location.
+    ///
+    /// The location can be a `file://` URL, an `http://` URL, or an absolute
+    /// path to a local file. This blocks on file system or network access.
+    pub fn fetch(location: &str) -> Result<Self> {
+        let url = parse_location(location)?;
+        let data = fetch(&url)?;
+        let index = serde_json::from_slice(&data)
+            .with_context(|| format!("Invalid registry index: {url}"))?;
+        Ok(Self { url, index })
+    }
+
+    /// Get the available updates for the installed widgets in the catalog.
+    ///
+    /// A widget is considered for updates only if it is installed from a
+    /// package into the widgets directory under its own ID, and both its
+    /// configured version and the version in the registry are valid semantic
+    /// versions. An update is available if the registry version is newer.
+    pub fn updates(
+        &self,
+        catalog: &WidgetCatalog,
+        widgets_dir: &Path,
+    ) -> BTreeMap<String, WidgetUpdate> {
+        self.index
+            .widgets
+            .iter()
+            .filter_map(|(id, entry)| {
+                let Outcome::Ok(config) = catalog.widgets.get(id)? else {
+                    return None;
+                };
+                let current = config.metadata.version.as_ref()?;
+                if catalog.dirs.get(id)? != &widgets_dir.join(id)
+                    || !matches!(PackageManifest::load(&widgets_dir.join(id)), Ok(Some(_)))
+                {
+                    return None;
+                }
+
+                let latest = Version::parse(&entry.version).ok()?;
+                if latest <= Version::parse(current).ok()? {
+                    return None;
+                }
+                let update = WidgetUpdate {
+                    name: entry.name.clone(),
+                    current: current.clone(),
+                    latest: entry.version.clone(),
+                };
+                Some((id.clone(), update))
+            })
+            .collect()
+    }
+
+    /// Install the latest version of a widget from the registry.
+    ///
+    /// The package is downloaded into the widgets directory and verified
+    /// against the checksum in the index before installation. The existing
+    /// installation of the widget, if any, is replaced, and is restored if the
+    /// installation fails. See [`package::install`] for details.
+    ///
+    /// This blocks on file system and network access.
+    pub fn install(&self, id: &str, widgets_dir: &Path) -> Result<PackageManifest> {
+        // The ID names the download and the installation directory, so it must
+        // not escape the widgets directory
+        package::validate_id(id)?;
+        let entry = self
+            .index
+            .widgets
+            .get(id)
+            .ok_or_else(|| anyhow!("Widget not found in the registry: {id}"))?;
+        let url = self
+            .url
+            .join(&entry.package)
+            .with_context(|| format!("Invalid package location: {}", entry.package))?;
+
+        let data = fetch(&url)?;
+        let checksum = format!("{:x}", Sha256::digest(&data));
+        if !checksum.eq_ignore_ascii_case(&entry.checksum) {
+            bail!(
+                "Checksum mismatch for {url}: expected {}, got {checksum}",
+                entry.checksum
+            );
+        }
+
+        // The download starts with a dot so that it is never discovered as a
+        // widget
+        let archive = widgets_dir.join(format!(".download-{id}.zip"));
+        std::fs::write(&archive, data)?;
+        let result = package::read_manifest(&archive).and_then(|manifest| {
+            if manifest.id != id {
+                bail!(
+                    "Package ID {:?} does not match {id:?} in the registry",
+                    manifest.id
+                );
+            }
+            package::install(&archive, widgets_dir, true)
+        });
+        if let Err(e) = std::fs::remove_file(&archive) {
+            eprintln!("Failed to remove {}: {e}", archive.display());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use serde_json::{json, Value};
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Pack a version of a widget into the registry directory and return its
+    /// registry entry.
+    fn publish(registry_dir: &Path, id: &str, version: &str) -> Value {
+        let src = registry_dir.join("src").join(id);
+        std::fs::create_dir_all(&src).unwrap();
+        let conf = json!({ "id": id, "name": id, "version": version, "entry": "index.jsx" });
+        std::fs::write(src.join("deskulpt.conf.json"), conf.to_string()).unwrap();
+        std::fs::write(src.join("index.jsx"), format!("// {version}")).unwrap();
+
+        let package = format!("packages/{id}-{version}.zip");
+        std::fs::create_dir_all(registry_dir.join("packages")).unwrap();
+        let out = File::create(registry_dir.join(&package)).unwrap();
+        package::pack(&src, out, None).unwrap();
+        let data = std::fs::read(registry_dir.join(&package)).unwrap();
+        json!({
+            "name": id,
+            "version": version,
+            "package": package,
+            "checksum": format!("{:x}", Sha256::digest(&data)),
+        })
+    }
+
+    /// Write the registry index and fetch it through a `file://` URL.
+    fn write_index(registry_dir: &Path, widgets: Value) -> Registry {
+        let path = registry_dir.join("index.json");
+        std::fs::write(&path, json!({ "widgets": widgets }).to_string()).unwrap();
+        Registry::fetch(Url::from_file_path(&path).unwrap().as_str()).unwrap()
+    }
+
+    /// Get the installed version of a widget.
+    fn installed_version(widgets_dir: &Path, id: &str) -> Option<String> {
+        PackageManifest::load(&widgets_dir.join(id))
+            .unwrap()
+            .unwrap()
+            .version
+    }
+
+    /// List the names of the entries in a directory.
+    fn entries(dir: &Path) -> Vec<String> {
+        let mut entries = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect::<Vec<_>>();
+        entries.sort();
+        entries
+    }
+
+    #[test]
+    fn test_parse_location() {
+        let url = parse_location("file:///srv/registry/index.json").unwrap();
+        assert_eq!(url.scheme(), "file");
+        let url = parse_location("http://registry.local/index.json").unwrap();
+        assert_eq!(url.as_str(), "http://registry.local/index.json");
+
+        // Other locations are absolute paths to local files
+        let path = std::env::temp_dir().join("index.json");
+        let url = parse_location(path.to_str().unwrap()).unwrap();
+        assert_eq!(url, Url::from_file_path(&path).unwrap());
+        assert!(parse_location("registry/index.json").is_err());
+
+        let error = format!(
+            "{:?}",
+            Registry::fetch("https://registry.local").unwrap_err()
+        );
+        assert!(error.contains("Unsupported registry URL scheme"), "{error}");
+    }
+
+    #[test]
+    fn test_fetch_path() {
+        let registry_dir = TempDir::new().unwrap();
+        let path = registry_dir.path().join("index.json");
+        std::fs::write(&path, r#"{ "widgets": {} }"#).unwrap();
+
+        let registry = Registry::fetch(path.to_str().unwrap()).unwrap();
+        assert_eq!(registry.url, Url::from_file_path(&path).unwrap());
+        assert!(registry.index.widgets.is_empty());
+    }
+
+    #[test]
+    fn test_updates() {
+        let registry_dir = TempDir::new().unwrap();
+        let widgets_dir = TempDir::new().unwrap();
+        let (registry_dir, widgets_dir) = (registry_dir.path(), widgets_dir.path());
+
+        let installed = json!({
+            "newer": publish(registry_dir, "newer", "1.0.0"),
+            "same": publish(registry_dir, "same", "1.0.0"),
+            "invalid": publish(registry_dir, "invalid", "1.0.0"),
+        });
+        let registry = write_index(registry_dir, installed);
+        for id in ["newer", "same", "invalid"] {
+            registry.install(id, widgets_dir).unwrap();
+        }
+
+        // Local widgets are not installed from packages and never updated
+        let local = widgets_dir.join("local");
+        std::fs::create_dir(&local).unwrap();
+        let conf = json!({ "name": "Local", "version": "1.0.0", "entry": "index.jsx" });
+        std::fs::write(local.join("deskulpt.conf.json"), conf.to_string()).unwrap();
+
+        let entry =
+            |version| json!({ "name": "New", "version": version, "package": "", "checksum": "" });
+        let registry = write_index(
+            registry_dir,
+            json!({
+                "newer": entry("1.0.1"),
+                "same": entry("1.0.0"),
+                "invalid": entry("latest"),
+                "local": entry("2.0.0"),
+                "missing": entry("2.0.0"),
+            }),
+        );
+        let catalog = WidgetCatalog::load(widgets_dir).unwrap();
+        let updates = registry.updates(&catalog, widgets_dir);
+        assert_eq!(updates.keys().collect::<Vec<_>>(), ["newer"]);
+        assert_eq!(updates["newer"].name, "New");
+        assert_eq!(updates["newer"].current, "1.0.0");
+        assert_eq!(updates["newer"].latest, "1.0.1");
+    }
+
+    #[test]
+    fn test_install() {
+        let registry_dir = TempDir::new().unwrap();
+        let widgets_dir = TempDir::new().unwrap();
+        let (registry_dir, widgets_dir) = (registry_dir.path(), widgets_dir.path());
+
+        let registry = write_index(
+            registry_dir,
+            json!({ "clock": publish(registry_dir, "clock", "1.0.0") }),
+        );
+        registry.install("clock", widgets_dir).unwrap();
+        assert_eq!(
+            installed_version(widgets_dir, "clock").as_deref(),
+            Some("1.0.0")
+        );
+
+        // The existing installation is replaced without leaving the download,
+        // staging or backup behind
+        let registry = write_index(
+            registry_dir,
+            json!({ "clock": publish(registry_dir, "clock", "1.1.0") }),
+        );
+        registry.install("clock", widgets_dir).unwrap();
+        assert_eq!(
+            installed_version(widgets_dir, "clock").as_deref(),
+            Some("1.1.0")
+        );
+        assert_eq!(entries(widgets_dir), ["clock"]);
+
+        for id in ["missing", "../clock", ".download-clock"] {
+            assert!(registry.install(id, widgets_dir).is_err(), "{id}");
+        }
+    }
+
+    #[test]
+    fn test_install_checksum_mismatch() {
+        let registry_dir = TempDir::new().unwrap();
+        let widgets_dir = TempDir::new().unwrap();
+        let (registry_dir, widgets_dir) = (registry_dir.path(), widgets_dir.path());
+
+        let registry = write_index(
+            registry_dir,
+            json!({ "clock": publish(registry_dir, "clock", "1.0.0") }),
+        );
+        registry.install("clock", widgets_dir).unwrap();
+
+        let mut entry = publish(registry_dir, "clock", "1.1.0");
+        entry["checksum"] = json!("0".repeat(64));
+        let registry = write_index(registry_dir, json!({ "clock": entry }));
+        let error = format!("{:?}", registry.install("clock", widgets_dir).unwrap_err());
+        assert!(error.contains("Checksum mismatch"), "{error}");
+
+        // The existing installation is kept
+        assert_eq!(
+            installed_version(widgets_dir, "clock").as_deref(),
+            Some("1.0.0")
+        );
+        assert_eq!(entries(widgets_dir), ["clock"]);
+    }
+
+    #[test]
+    fn test_install_id_mismatch() {
+        let registry_dir = TempDir::new().unwrap();
+        let widgets_dir = TempDir::new().unwrap();
+        let (registry_dir, widgets_dir) = (registry_dir.path(), widgets_dir.path());
+
+        let registry = write_index(
+            registry_dir,
+            json!({ "clock": publish(registry_dir, "clock", "1.0.0") }),
+        );
+        registry.install("clock", widgets_dir).unwrap();
+
+        // The package of another widget is listed under the ID, which must not
+        // replace the existing installation nor install the other widget
+        let registry = write_index(
+            registry_dir,
+            json!({ "clock": publish(registry_dir, "other", "1.1.0") }),
+        );
+        let error = format!("{:?}", registry.install("clock", widgets_dir).unwrap_err());
+        assert!(error.contains("does not match"), "{error}");
+
+        assert_eq!(
+            installed_version(widgets_dir, "clock").as_deref(),
+            Some("1.0.0")
+        );
+        assert_eq!(entries(widgets_dir), ["clock"]);
+    }
+
+    #[test]
+    fn test_install_over_local_widget() {
+        let registry_dir = TempDir::new().unwrap();
+        let widgets_dir = TempDir::new().unwrap();
+        let (registry_dir, widgets_dir) = (registry_dir.path(), widgets_dir.path());
+
+        let local = widgets_dir.join("clock");
+        std::fs::create_dir(&local).unwrap();
+        std::fs::write(local.join("index.jsx"), "// local").unwrap();
+
+        // Local widgets are never replaced, and the failed installation is
+        // rolled back without touching them
+        let registry = write_index(
+            registry_dir,
+            json!({ "clock": publish(registry_dir, "clock", "1.0.0") }),
+        );
+        let error = format!("{:?}", registry.install("clock", widgets_dir).unwrap_err());
+        assert!(error.contains("not installed from a package"), "{error}");
+        assert_eq!(entries(widgets_dir), ["clock"]);
+        assert_eq!(entries(&local), ["index.jsx"]);
+        assert_eq!(
+            std::fs::read_to_string(local.join("index.jsx")).unwrap(),
+            "// local"
+        );
+    }
+}
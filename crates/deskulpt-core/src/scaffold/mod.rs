@@ -0,0 +1,156 @@
+//! Widget scaffolding.
+//!
+//! New widgets are generated from built-in templates, each being a set of files
+//! with a placeholder for the widget name. The generated widgets follow the
+//! conventions of Deskulpt, e.g., importing React from `@deskulpt-test/react`
+//! and plugin APIs from `@deskulpt-test/apis`.
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// The placeholder for the widget name in the template files.
+///
+/// The placeholder is quoted, and is replaced with the name as a JSON string,
+/// which is also a valid JavaScript string literal.
+const NAME_PLACEHOLDER: &str = "\"__WIDGET_NAME__\"";
+
+/// Built-in templates for new widgets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, specta::Type)]
+#[serde(rename_all = "camelCase")]
+pub enum WidgetTemplate {
+    /// A single JSX entry file.
+    #[default]
+    Minimal,
+    /// A TSX entry file with typed props and options.
+    ///
+    /// This also includes `package.json` and `tsconfig.json` for type checking
+    /// in the editor.
+    Tsx,
+    /// A JSX entry file that calls the system plugin.
+    Plugin,
+}
+
+impl FromStr for WidgetTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "minimal" => Ok(Self::Minimal),
+            "tsx" => Ok(Self::Tsx),
+            "plugin" => Ok(Self::Plugin),
+            _ => Err(format!(
+                "Unknown template: {s:?}; expected one of minimal, tsx, plugin"
+            )),
+        }
+    }
+}
+
+impl WidgetTemplate {
+    /// The files of the template, as pairs of relative paths and contents.
+    fn files(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Self::Minimal => &[
+                (
+                    "deskulpt.conf.json",
+                    include_str!("templates/minimal/deskulpt.conf.json"),
+                ),
+                ("index.jsx", include_str!("templates/minimal/index.jsx")),
+            ],
+            Self::Tsx => &[
+                (
+                    "deskulpt.conf.json",
+                    include_str!("templates/tsx/deskulpt.conf.json"),
+                ),
+                ("index.tsx", include_str!("templates/tsx/index.tsx")),
+                ("package.json", include_str!("templates/tsx/package.json")),
+                ("tsconfig.json", include_str!("templates/tsx/tsconfig.json")),
+            ],
+            Self::Plugin => &[
+                (
+                    "deskulpt.conf.json",
+                    include_str!("templates/plugin/deskulpt.conf.json"),
+                ),
+                ("index.jsx", include_str!("templates/plugin/index.jsx")),
+            ],
+        }
+    }
+}
+
+/// Derive a directory name from a widget name.
+///
+/// ASCII letters and digits are kept in lowercase, and everything else is
+/// collapsed into single `-` separators.
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "widget".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Create a new widget from a template in the widgets directory.
+///
+/// The widget directory is named after the widget name, with a numeric suffix
+/// if the name is already taken by an existing directory or by a widget ID for
+/// which `is_taken` returns true. This returns the name of the created
+/// directory, which is also the ID of the new widget.
+pub fn create(
+    widgets_dir: &Path,
+    name: &str,
+    template: WidgetTemplate,
+    is_taken: impl Fn(&str) -> bool,
+) -> Result<String> {
+    if name.trim().is_empty() {
+        bail!("Widget name cannot be empty");
+    }
+
+    let slug = slugify(name);
+    let mut suffix = 1;
+    let (dir_name, dir) = loop {
+        let dir_name = match suffix {
+            1 => slug.clone(),
+            n => format!("{slug}-{n}"),
+        };
+        suffix += 1;
+        if is_taken(&dir_name) {
+            continue;
+        }
+
+        let dir = widgets_dir.join(&dir_name);
+        match std::fs::create_dir(&dir) {
+            Ok(()) => break (dir_name, dir),
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e).with_context(|| format!("Failed to create {}", dir.display())),
+        }
+    };
+
+    if let Err(e) = write_template(&dir, name, template) {
+        let _ = std::fs::remove_dir_all(&dir);
+        return Err(e);
+    }
+    Ok(dir_name)
+}
+
+/// Write the files of a template into a widget directory.
+fn write_template(dir: &Path, name: &str, template: WidgetTemplate) -> Result<()> {
+    let name = serde_json::to_string(name)?;
+    for (path, content) in template.files() {
+        let path = dir.join(path);
+        std::fs::write(&path, content.replace(NAME_PLACEHOLDER, &name))
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
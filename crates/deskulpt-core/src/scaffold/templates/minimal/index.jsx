@@ -0,0 +1,3 @@
+export default function Widget() {
+  return <div>Hello from {"__WIDGET_NAME__"}!</div>;
+}
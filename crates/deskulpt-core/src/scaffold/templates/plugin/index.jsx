@@ -0,0 +1,29 @@
+import { useEffect, useState } from "@deskulpt-test/react";
+import apis from "@deskulpt-test/apis";
+
+export default function Widget() {
+  const [memory, setMemory] = useState(null);
+
+  useEffect(() => {
+    const unsubscribe = apis.sys.subscribe(
+      "memory",
+      ({ ram }) => setMemory(ram),
+      { interval: 2000, payload: { fields: ["ram"] } },
+    );
+    return () => {
+      unsubscribe.then((f) => f()).catch(console.error);
+    };
+  }, []);
+
+  if (memory === null) {
+    return <div>Loading...</div>;
+  }
+
+  const usage = (memory.used / memory.total) * 100;
+  return (
+    <div>
+      <div>{"__WIDGET_NAME__"}</div>
+      <div>Memory: {usage.toFixed(1)}%</div>
+    </div>
+  );
+}
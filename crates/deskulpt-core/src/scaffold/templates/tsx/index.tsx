@@ -0,0 +1,42 @@
+import { useEffect, useState } from "@deskulpt-test/react";
+
+/** Props passed to every widget by Deskulpt. */
+interface WidgetProps {
+  id: string;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  opacity: number;
+}
+
+/** Options of this widget; edit them to customize the widget. */
+const options = {
+  title: "__WIDGET_NAME__",
+  showSize: true,
+  refreshInterval: 1000,
+};
+
+export default function Widget({ width, height }: WidgetProps) {
+  const [now, setNow] = useState(() => new Date());
+
+  useEffect(() => {
+    const timer = setInterval(
+      () => setNow(new Date()),
+      options.refreshInterval,
+    );
+    return () => clearInterval(timer);
+  }, []);
+
+  return (
+    <div css={{ padding: 8 }}>
+      <div css={{ fontWeight: "bold" }}>{options.title}</div>
+      <div>{now.toLocaleTimeString()}</div>
+      {options.showSize && (
+        <div css={{ opacity: 0.6 }}>
+          {width} x {height}
+        </div>
+      )}
+    </div>
+  );
+}
@@ -0,0 +1,57 @@
+//! Migration of widget settings across widget directory renames.
+
+use std::collections::BTreeMap;
+
+use super::{Settings, WidgetSettings};
+use crate::config::WidgetCatalog;
+
+impl Settings {
+    /// Reconcile the widget settings with a newly loaded widget catalog.
+    ///
+    /// Settings of widgets that are no longer in the catalog are removed,
+    /// unless they can be migrated to a newly discovered widget. This happens
+    /// when the fingerprint recorded in the settings matches the fingerprint
+    /// of exactly one newly discovered widget and vice versa, which usually
+    /// means that the widget directory has been renamed or moved. Ambiguous
+    /// matches are not migrated.
+    ///
+    /// Widgets with a stable identifier in their configurations keep their IDs
+    /// across renames and thus never need to be migrated.
+    ///
+    /// This returns the mapping from the old IDs to the new IDs of the migrated
+    /// widgets, so that other data of the widgets can be migrated as well.
+    pub fn migrate_widgets(&mut self, catalog: &WidgetCatalog) -> BTreeMap<String, String> {
+        let (kept, stale): (BTreeMap<_, _>, BTreeMap<_, _>) = std::mem::take(&mut self.widgets)
+            .into_iter()
+            .partition(|(id, _)| catalog.widgets.contains_key(id));
+        self.widgets = kept;
+
+        let added = catalog
+            .fingerprints
+            .iter()
+            .filter(|(id, _)| !self.widgets.contains_key(*id))
+            .collect::<Vec<_>>();
+
+        let mut migrated = BTreeMap::new();
+        for &(id, fingerprint) in &added {
+            let matches = |(_, settings): &(&String, &WidgetSettings)| {
+                settings.fingerprint.as_ref() == Some(fingerprint)
+            };
+            let mut candidates = stale.iter().filter(matches);
+            let (Some((old_id, settings)), None) = (candidates.next(), candidates.next()) else {
+                continue;
+            };
+            if added
+                .iter()
+                .filter(|(_, other)| *other == fingerprint)
+                .count()
+                > 1
+            {
+                continue;
+            }
+            self.widgets.insert(id.clone(), settings.clone());
+            migrated.insert(old_id.clone(), id.clone());
+        }
+        migrated
+    }
+}
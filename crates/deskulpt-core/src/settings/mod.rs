@@ -1,16 +1,19 @@
 //! Application and widget settings.
 
 use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::{serde_as, DefaultOnError, MapSkipError};
 
+mod migration;
 mod persistence;
+mod placement;
 mod shortcuts;
 
 /// Light/dark theme of the application.
-#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, specta::Type)]
 #[serde(rename_all = "camelCase")]
 pub enum Theme {
     #[default]
@@ -35,7 +38,7 @@ pub enum ShortcutKey {
 /// Different from widget configurations, these are independent of the widget
 /// configuration files and are managed internally by the application.
 #[serde_as]
-#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, specta::Type)]
 #[serde(rename_all = "camelCase", default)]
 pub struct WidgetSettings {
     /// The leftmost x-coordinate in pixels.
@@ -54,6 +57,13 @@ pub struct WidgetSettings {
     #[serde(deserialize_with = "WidgetSettings::deserialize_opacity")]
     #[schemars(range(min = 1, max = 100))]
     pub opacity: u8,
+    /// The fingerprint of the widget contents when it was last discovered.
+    ///
+    /// This is used to recognize the widget and migrate its settings if its
+    /// directory is renamed or moved. It is managed by the application and
+    /// should not be modified manually.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub fingerprint: Option<String>,
 }
 
 impl Default for WidgetSettings {
@@ -64,6 +74,7 @@ impl Default for WidgetSettings {
             width: 300,
             height: 200,
             opacity: 100,
+            fingerprint: None,
         }
     }
 }
@@ -107,7 +118,7 @@ pub struct WidgetSettingsPatch {
 
 /// Full settings of the Deskulpt application.
 #[serde_as]
-#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, specta::Type)]
+#[derive(Debug, Clone, Default, PartialEq, Deserialize, Serialize, JsonSchema, specta::Type)]
 #[serde(rename_all = "camelCase", default)]
 pub struct Settings {
     /// The application theme.
@@ -119,6 +130,37 @@ pub struct Settings {
     /// The mapping from widget IDs to their respective settings.
     #[serde_as(deserialize_as = "MapSkipError<_, _>")]
     pub widgets: BTreeMap<String, WidgetSettings>,
+    /// The maximum number of widgets to bundle at the same time.
+    ///
+    /// If set to 0, the available parallelism of the system is used.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub bundle_concurrency: u32,
+    /// Extra widget directories, mapped from their namespaces.
+    ///
+    /// Widgets are discovered in these directories in addition to the default
+    /// widgets directory, with their IDs prefixed by the namespace, e.g.
+    /// `shared:clock`. Namespaces may only contain ASCII letters, digits, `-`
+    /// and `_`.
+    #[serde_as(deserialize_as = "MapSkipError<_, _>")]
+    pub widget_roots: BTreeMap<String, PathBuf>,
+    /// Whether to refuse privileged plugin access to unverified widgets.
+    ///
+    /// If enabled, only widgets installed from packages signed by a trusted
+    /// key can call privileged plugins, such as the file system plugin, and
+    /// permissions declared by other widgets are ignored.
+    ///
+    /// This is advisory only and not a security boundary. All widgets run in
+    /// the same canvas and the widget ID of a plugin call is supplied by the
+    /// caller, so a malicious widget can call plugins on behalf of a verified
+    /// widget.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub restrict_unverified_widgets: bool,
+    /// The location of the widget registry index, if any.
+    ///
+    /// This can be a `file://` URL, an `http://` URL, or an absolute path to a
+    /// local file. Installed widgets are checked for updates against it.
+    #[serde_as(deserialize_as = "DefaultOnError")]
+    pub registry_index: Option<String>,
 }
 
 /// A patch for partial updates to [`Settings`].
@@ -144,4 +186,24 @@ pub struct SettingsPatch {
     /// values, and then the patch will be applied to it.
     #[specta(optional, type = BTreeMap<String, Option<WidgetSettingsPatch>>)]
     pub widgets: Option<BTreeMap<String, Option<WidgetSettingsPatch>>>,
+    /// If not `None`, update [`Settings::bundle_concurrency`].
+    #[specta(optional, type = u32)]
+    pub bundle_concurrency: Option<u32>,
+    /// If not `None`, update [`Settings::widget_roots`].
+    ///
+    /// Non-specified widget roots will remain unchanged. If a widget root value
+    /// is `None`, it means removing that widget root. Otherwise, it means
+    /// updating or adding that widget root. Changes take effect on the next
+    /// rescan.
+    #[specta(optional, type = BTreeMap<String, Option<PathBuf>>)]
+    pub widget_roots: Option<BTreeMap<String, Option<PathBuf>>>,
+    /// If not `None`, update [`Settings::restrict_unverified_widgets`].
+    #[specta(optional, type = bool)]
+    pub restrict_unverified_widgets: Option<bool>,
+    /// If not `None`, update [`Settings::registry_index`].
+    ///
+    /// If the value is `None`, it means removing the registry index.
+    #[serde(with = "::serde_with::rust::double_option")]
+    #[specta(optional, type = Option<String>)]
+    pub registry_index: Option<Option<String>>,
 }
@@ -0,0 +1,132 @@
+//! Placement of newly discovered widgets.
+
+use super::{Settings, WidgetSettings};
+use crate::config::{Anchor, LayoutConfig};
+
+/// The gap in pixels kept between placed widgets and canvas edges.
+const GAP: i32 = 16;
+
+/// A rectangle on the canvas.
+#[derive(Clone, Copy)]
+struct Rect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+impl Rect {
+    /// Whether the rectangle overlaps with another one.
+    ///
+    /// Rectangles that only touch each other on the edges do not overlap.
+    fn overlaps(&self, other: &Rect) -> bool {
+        self.x < other.x + other.width
+            && other.x < self.x + self.width
+            && self.y < other.y + other.height
+            && other.y < self.y + self.height
+    }
+}
+
+impl From<&WidgetSettings> for Rect {
+    fn from(settings: &WidgetSettings) -> Self {
+        Self {
+            x: settings.x,
+            y: settings.y,
+            width: settings.width as i32,
+            height: settings.height as i32,
+        }
+    }
+}
+
+impl Settings {
+    /// Add settings for a newly discovered widget.
+    ///
+    /// The widget gets the default size declared in its layout configuration.
+    /// If the canvas size is known, the widget is placed in the free spot on
+    /// the canvas that is closest to its preferred anchor. If there is no free
+    /// spot, it is placed at the anchor, overlapping with other widgets. If the
+    /// canvas size is unknown, the widget is placed at the origin.
+    pub fn place_widget(&mut self, id: String, layout: &LayoutConfig, canvas: Option<(u32, u32)>) {
+        let default = WidgetSettings::default();
+        let (width, height) = layout.constrain(
+            layout.width.unwrap_or(default.width),
+            layout.height.unwrap_or(default.height),
+        );
+
+        let (x, y) = match canvas {
+            Some((canvas_width, canvas_height)) => {
+                let occupied = self.widgets.values().map(Rect::from).collect::<Vec<_>>();
+                find_free_spot(
+                    &occupied,
+                    (width as i32, height as i32),
+                    (canvas_width as i32, canvas_height as i32),
+                    layout.anchor,
+                )
+            },
+            None => (0, 0),
+        };
+
+        self.widgets.insert(
+            id,
+            WidgetSettings {
+                x,
+                y,
+                width,
+                height,
+                ..default
+            },
+        );
+    }
+}
+
+/// Find the free spot closest to the anchor for a rectangle of the given size.
+///
+/// Candidate spots are aligned with the canvas edges and the edges of the
+/// occupied rectangles, which is enough to find a free spot if there is one.
+fn find_free_spot(
+    occupied: &[Rect],
+    (width, height): (i32, i32),
+    (canvas_width, canvas_height): (i32, i32),
+    anchor: Anchor,
+) -> (i32, i32) {
+    let right = canvas_width - width - GAP;
+    let bottom = canvas_height - height - GAP;
+    let target = match anchor {
+        Anchor::TopLeft => (GAP, GAP),
+        Anchor::TopRight => (right, GAP),
+        Anchor::BottomLeft => (GAP, bottom),
+        Anchor::BottomRight => (right, bottom),
+        Anchor::Center => ((canvas_width - width) / 2, (canvas_height - height) / 2),
+    };
+
+    let mut xs = vec![GAP, right, target.0];
+    let mut ys = vec![GAP, bottom, target.1];
+    for rect in occupied {
+        xs.extend([rect.x + rect.width + GAP, rect.x - width - GAP]);
+        ys.extend([rect.y + rect.height + GAP, rect.y - height - GAP]);
+    }
+
+    let distance = |(x, y): (i32, i32)| {
+        let (dx, dy) = ((x - target.0) as i64, (y - target.1) as i64);
+        dx * dx + dy * dy
+    };
+
+    xs.iter()
+        .filter(|x| (0..=canvas_width - width).contains(*x))
+        .flat_map(|&x| {
+            ys.iter()
+                .filter(|y| (0..=canvas_height - height).contains(*y))
+                .map(move |&y| (x, y))
+        })
+        .filter(|&(x, y)| {
+            let rect = Rect {
+                x,
+                y,
+                width,
+                height,
+            };
+            occupied.iter().all(|other| !rect.overlaps(other))
+        })
+        .min_by_key(|&(x, y)| (distance((x, y)), y, x))
+        .unwrap_or((target.0.max(0), target.1.max(0)))
+}
@@ -0,0 +1,43 @@
+//! State management for the bundle reports.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use tauri::{App, AppHandle, Manager, Runtime};
+
+use crate::bundler::BundleReport;
+
+/// Managed state for the bundle reports.
+///
+/// This maps widget IDs to the reports of their last successful bundles.
+#[derive(Default)]
+struct BundleReportsState(RwLock<HashMap<String, BundleReport>>);
+
+/// Extension trait for operations on bundle reports state.
+pub trait BundleReportsStateExt<R: Runtime>: Manager<R> {
+    /// Initialize state management for the bundle reports.
+    fn manage_bundle_reports(&self) {
+        self.manage(BundleReportsState::default());
+    }
+
+    /// Get the report of the last successful bundle of a widget.
+    fn get_bundle_report(&self, id: &str) -> Option<BundleReport> {
+        let state = self.state::<BundleReportsState>().inner();
+        state.0.read().unwrap().get(id).cloned()
+    }
+
+    /// Set the report of the last successful bundle of a widget.
+    fn set_bundle_report(&self, id: String, report: BundleReport) {
+        let state = self.state::<BundleReportsState>().inner();
+        state.0.write().unwrap().insert(id, report);
+    }
+
+    /// Retain only the bundle reports specified by the predicate.
+    fn retain_bundle_reports(&self, f: impl FnMut(&String, &mut BundleReport) -> bool) {
+        let state = self.state::<BundleReportsState>().inner();
+        state.0.write().unwrap().retain(f);
+    }
+}
+
+impl<R: Runtime> BundleReportsStateExt<R> for App<R> {}
+impl<R: Runtime> BundleReportsStateExt<R> for AppHandle<R> {}
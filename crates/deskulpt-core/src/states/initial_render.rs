@@ -62,8 +62,8 @@ pub trait InitialRenderStateExt<R: Runtime>: Manager<R> + Emitter<R> {
     /// Emit a [`RenderWidgetsEvent`] to the canvas when it is ready.
     ///
     /// If the canvas is already ready to render widgets, emit the given payload
-    /// to the canvas immediately. Otherwise, store the payload as pending so
-    /// that it can be emitted later when the canvas is ready.
+    /// to the canvas immediately. Otherwise, merge the payload into the pending
+    /// one so that it can be emitted later when the canvas is ready.
     fn emit_on_render_ready(&self, event: RenderWidgetsEvent) -> Result<()>
     where
         Self: Sized,
@@ -72,7 +72,10 @@ pub trait InitialRenderStateExt<R: Runtime>: Manager<R> + Emitter<R> {
         let mut initial_render = state.0.lock().unwrap();
 
         if !initial_render.ready {
-            initial_render.pending = Some(event);
+            match &mut initial_render.pending {
+                Some(pending) => pending.0.extend(event.0),
+                None => initial_render.pending = Some(event),
+            }
             return Ok(());
         }
         event.emit_to(self, DeskulptWindow::Canvas)?;
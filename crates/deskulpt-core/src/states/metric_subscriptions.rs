@@ -0,0 +1,178 @@
+//! State management for the metric subscriptions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use deskulpt_common::outcome::Outcome;
+use tauri::async_runtime::JoinHandle;
+use tauri::{App, AppHandle, Manager, Runtime};
+
+use crate::states::WidgetCatalogStateExt;
+
+/// A metric topic that is sampled periodically.
+struct MetricTopic {
+    /// The subscriptions to the topic, mapping handles to widget IDs.
+    subscriptions: BTreeMap<u32, String>,
+    /// The latest sample of the metric.
+    latest: Outcome<serde_json::Value>,
+    /// The task that samples the metric periodically.
+    task: JoinHandle<()>,
+}
+
+/// Inner structure for [`MetricSubscriptionsState`].
+#[derive(Default)]
+struct MetricSubscriptionsStateInner {
+    /// The handle to assign to the next subscription.
+    next_handle: u32,
+    /// The mapping from topic keys to topics.
+    topics: HashMap<String, MetricTopic>,
+}
+
+impl MetricSubscriptionsStateInner {
+    /// Subscribe a widget to an existing topic.
+    fn subscribe(&mut self, topic: &str, id: String) -> Option<(u32, Outcome<serde_json::Value>)> {
+        let handle = self.next_handle;
+        let topic = self.topics.get_mut(topic)?;
+        topic.subscriptions.insert(handle, id);
+        self.next_handle = self.next_handle.wrapping_add(1);
+        Some((handle, topic.latest.clone()))
+    }
+
+    /// Remove topics without subscriptions and stop their sampling tasks.
+    fn remove_idle_topics(&mut self) {
+        self.topics.retain(|_, topic| {
+            if topic.subscriptions.is_empty() {
+                topic.task.abort();
+                return false;
+            }
+            true
+        });
+    }
+}
+
+/// Managed state for the metric subscriptions.
+///
+/// Widgets subscribe to metric topics, each being a plugin command with its
+/// payload and sampling interval. Each topic is sampled by a single task no
+/// matter how many widgets subscribe to it, and the task is stopped once the
+/// topic has no subscriptions left.
+#[derive(Default)]
+struct MetricSubscriptionsState(Mutex<MetricSubscriptionsStateInner>);
+
+/// Extension trait for operations on metric subscriptions state.
+pub trait MetricSubscriptionsStateExt<R: Runtime>: Manager<R> + WidgetCatalogStateExt<R> {
+    /// Initialize state management for the metric subscriptions.
+    fn manage_metric_subscriptions(&self) {
+        self.manage(MetricSubscriptionsState::default());
+    }
+
+    /// Subscribe a widget to an existing metric topic.
+    ///
+    /// This returns the handle of the subscription and the latest sample of
+    /// the topic, or `None` if the topic does not exist.
+    fn subscribe_metric_topic(
+        &self,
+        topic: &str,
+        id: String,
+    ) -> Option<(u32, Outcome<serde_json::Value>)> {
+        let state = self.state::<MetricSubscriptionsState>().inner();
+        state.0.lock().unwrap().subscribe(topic, id)
+    }
+
+    /// Add a new metric topic with a widget as its first subscriber.
+    ///
+    /// The topic is sampled by the given task, starting with the given sample.
+    /// If the topic has been added in the meantime, the given task is aborted
+    /// and the widget subscribes to the existing topic instead. This returns
+    /// the handle of the subscription and the latest sample of the topic.
+    ///
+    /// If the widget has been removed from the catalog in the meantime, the
+    /// given task is aborted and this returns `None`. The catalog is checked
+    /// while holding the lock of the subscriptions, so that the subscription
+    /// cannot be added after the subscriptions of removed widgets are dropped.
+    fn add_metric_topic(
+        &self,
+        topic: String,
+        id: String,
+        latest: Outcome<serde_json::Value>,
+        task: JoinHandle<()>,
+    ) -> Option<(u32, Outcome<serde_json::Value>)> {
+        let state = self.state::<MetricSubscriptionsState>().inner();
+        let mut inner = state.0.lock().unwrap();
+        if !self.get_widget_catalog().widgets.contains_key(&id) {
+            task.abort();
+            return None;
+        }
+        if inner.topics.contains_key(&topic) {
+            task.abort();
+        } else {
+            let metric_topic = MetricTopic {
+                subscriptions: BTreeMap::new(),
+                latest,
+                task,
+            };
+            inner.topics.insert(topic.clone(), metric_topic);
+        }
+        inner.subscribe(&topic, id)
+    }
+
+    /// Get the ID of any widget subscribed to a metric topic.
+    ///
+    /// This returns `None` if the topic no longer exists.
+    fn metric_topic_subscriber(&self, topic: &str) -> Option<String> {
+        let state = self.state::<MetricSubscriptionsState>().inner();
+        let inner = state.0.lock().unwrap();
+        let topic = inner.topics.get(topic)?;
+        topic.subscriptions.values().next().cloned()
+    }
+
+    /// Set the latest sample of a metric topic.
+    ///
+    /// This returns whether the topic still exists.
+    fn set_metric_topic_latest(&self, topic: &str, latest: Outcome<serde_json::Value>) -> bool {
+        let state = self.state::<MetricSubscriptionsState>().inner();
+        match state.0.lock().unwrap().topics.get_mut(topic) {
+            Some(topic) => {
+                topic.latest = latest;
+                true
+            },
+            None => false,
+        }
+    }
+
+    /// Remove a metric subscription of a widget by its handle.
+    ///
+    /// The topic is removed and no longer sampled if this is its last
+    /// subscription. Unknown handles and handles of subscriptions of other
+    /// widgets are ignored.
+    fn remove_metric_subscription(&self, id: &str, handle: u32) {
+        let state = self.state::<MetricSubscriptionsState>().inner();
+        let mut inner = state.0.lock().unwrap();
+        for topic in inner.topics.values_mut() {
+            if topic
+                .subscriptions
+                .get(&handle)
+                .is_some_and(|owner| owner == id)
+            {
+                topic.subscriptions.remove(&handle);
+            }
+        }
+        inner.remove_idle_topics();
+    }
+
+    /// Retain only the metric subscriptions of widgets specified by the
+    /// predicate.
+    ///
+    /// Topics without subscriptions left are removed and no longer sampled.
+    fn retain_metric_subscriptions(&self, mut f: impl FnMut(&str) -> bool) {
+        let state = self.state::<MetricSubscriptionsState>().inner();
+        let mut inner = state.0.lock().unwrap();
+        for topic in inner.topics.values_mut() {
+            topic.subscriptions.retain(|_, id| f(id));
+        }
+        inner.remove_idle_topics();
+    }
+}
+
+impl<R: Runtime> MetricSubscriptionsStateExt<R> for App<R> {}
+impl<R: Runtime> MetricSubscriptionsStateExt<R> for AppHandle<R> {}
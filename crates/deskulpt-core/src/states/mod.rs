@@ -1,15 +1,21 @@
 //! Deskulpt runtime state management.
 
+mod bundle_reports;
 mod canvas_imode;
 mod initial_render;
+mod metric_subscriptions;
 mod settings;
 mod widget_catalog;
 
+#[doc(hidden)]
+pub use bundle_reports::BundleReportsStateExt;
 #[doc(hidden)]
 pub use canvas_imode::CanvasImodeStateExt;
 #[doc(hidden)]
 pub use initial_render::InitialRenderStateExt;
 #[doc(hidden)]
+pub use metric_subscriptions::MetricSubscriptionsStateExt;
+#[doc(hidden)]
 pub use settings::SettingsStateExt;
 #[doc(hidden)]
 pub use widget_catalog::WidgetCatalogStateExt;
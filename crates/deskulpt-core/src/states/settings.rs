@@ -3,17 +3,21 @@
 use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 use anyhow::{bail, Result};
+use deskulpt_common::outcome::Outcome;
 use tauri::{App, AppHandle, Manager, Runtime};
 use tauri_plugin_global_shortcut::GlobalShortcutExt;
 
 use crate::path::PathExt;
 use crate::settings::{Settings, SettingsPatch};
+use crate::states::WidgetCatalogStateExt;
 
 /// Managed state for the settings.
 struct SettingsState(RwLock<Settings>);
 
 /// Extension trait for operations on the settings state.
-pub trait SettingsStateExt<R: Runtime>: Manager<R> + PathExt<R> + GlobalShortcutExt<R> {
+pub trait SettingsStateExt<R: Runtime>:
+    Manager<R> + PathExt<R> + GlobalShortcutExt<R> + WidgetCatalogStateExt<R>
+{
     /// Initialize state management for the settings.
     ///
     /// This will load the settings from the persistence directory and
@@ -56,8 +60,14 @@ pub trait SettingsStateExt<R: Runtime>: Manager<R> + PathExt<R> + GlobalShortcut
     /// to be applied will be skipped, and the rest will be applied as normal.
     /// Errors will be accumulated and returned as a single error at the end if
     /// any occurred.
+    ///
+    /// Resizing a widget respects the size constraints declared in its layout
+    /// configuration, if any. See
+    /// [`LayoutConfig::resize`](crate::config::LayoutConfig::resize) for
+    /// details.
     fn apply_settings_patch(&self, patch: SettingsPatch) -> Result<()> {
         let mut errors = vec![];
+        let catalog = self.get_widget_catalog();
         let mut settings = self.get_settings_mut();
 
         if let Some(theme) = patch.theme {
@@ -80,6 +90,10 @@ pub trait SettingsStateExt<R: Runtime>: Manager<R> + PathExt<R> + GlobalShortcut
                     continue;
                 }
                 let patch = patch.unwrap();
+                let layout = match catalog.widgets.get(&id) {
+                    Some(Outcome::Ok(config)) => config.layout.clone(),
+                    _ => Default::default(),
+                };
                 let widget = settings.widgets.entry(id).or_default();
 
                 if let Some(x) = patch.x {
@@ -88,11 +102,9 @@ pub trait SettingsStateExt<R: Runtime>: Manager<R> + PathExt<R> + GlobalShortcut
                 if let Some(y) = patch.y {
                     widget.y = y;
                 }
-                if let Some(width) = patch.width {
-                    widget.width = width;
-                }
-                if let Some(height) = patch.height {
-                    widget.height = height;
+                if patch.width.is_some() || patch.height.is_some() {
+                    (widget.width, widget.height) =
+                        layout.resize((widget.width, widget.height), patch.width, patch.height);
                 }
                 if let Some(opacity) = patch.opacity {
                     widget.opacity = opacity;
@@ -100,6 +112,27 @@ pub trait SettingsStateExt<R: Runtime>: Manager<R> + PathExt<R> + GlobalShortcut
             }
         }
 
+        if let Some(bundle_concurrency) = patch.bundle_concurrency {
+            settings.bundle_concurrency = bundle_concurrency;
+        }
+
+        if let Some(widget_roots) = patch.widget_roots {
+            for (namespace, root) in widget_roots {
+                match root {
+                    Some(root) => settings.widget_roots.insert(namespace, root),
+                    None => settings.widget_roots.remove(&namespace),
+                };
+            }
+        }
+
+        if let Some(restrict_unverified_widgets) = patch.restrict_unverified_widgets {
+            settings.restrict_unverified_widgets = restrict_unverified_widgets;
+        }
+
+        if let Some(registry_index) = patch.registry_index {
+            settings.registry_index = registry_index;
+        }
+
         if errors.is_empty() {
             return Ok(());
         }
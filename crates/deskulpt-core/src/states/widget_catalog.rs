@@ -1,7 +1,9 @@
 //! State management for the widget catalog.
 
+use std::path::PathBuf;
 use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 
+use anyhow::{anyhow, Result};
 use tauri::{App, AppHandle, Manager, Runtime};
 
 use crate::config::WidgetCatalog;
@@ -35,6 +37,19 @@ pub trait WidgetCatalogStateExt<R: Runtime>: Manager<R> + PathExt<R> {
         let state = self.state::<WidgetCatalogState>().inner();
         state.0.write().unwrap()
     }
+
+    /// Get the directory of a widget in the catalog.
+    ///
+    /// This resolves the widget ID against the widget root it is discovered
+    /// in, which can be either the widgets directory or one of the extra widget
+    /// roots in the settings.
+    fn widget_dir(&self, id: &str) -> Result<PathBuf> {
+        self.get_widget_catalog()
+            .dirs
+            .get(id)
+            .cloned()
+            .ok_or_else(|| anyhow!("Widget not found in the catalog: {id}"))
+    }
 }
 
 impl<R: Runtime> WidgetCatalogStateExt<R> for App<R> {}
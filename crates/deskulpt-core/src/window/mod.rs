@@ -95,6 +95,15 @@ pub trait WindowExt<R: Runtime>: Manager<R> + SettingsStateExt<R> {
         Ok(())
     }
 
+    /// Get the logical size of the canvas window.
+    fn canvas_size(&self) -> Result<(u32, u32)> {
+        let canvas = DeskulptWindow::Canvas.webview_window(self)?;
+        let size = canvas
+            .inner_size()?
+            .to_logical::<u32>(canvas.scale_factor()?);
+        Ok((size.width, size.height))
+    }
+
     /// Open the manager window.
     fn open_manager(&self) -> Result<()> {
         let manager = DeskulptWindow::Manager.webview_window(self)?;
@@ -0,0 +1,130 @@
+//! Allowlist of the hosts that widgets may send requests to.
+
+use anyhow::{anyhow, bail, Context, Result};
+use deskulpt_plugin::{serde_json, EngineInterface};
+use url::{Host, Url};
+
+/// A pattern of hosts in the allowlist.
+struct HostPattern {
+    /// The host, or the parent domain if [`Self::subdomains`] is set.
+    host: Host,
+    /// Whether the pattern matches all subdomains of the host instead.
+    subdomains: bool,
+    /// The port to match, or `None` to match any port.
+    port: Option<u16>,
+}
+
+impl HostPattern {
+    /// Parse a host pattern, e.g., `example.com`, `localhost:8080`,
+    /// `*.example.com` or `[::1]:8080`.
+    fn parse(pattern: &str) -> Result<Self> {
+        let (host, port) = match pattern.rsplit_once(':') {
+            Some((host, port)) if !port.is_empty() && port.bytes().all(|b| b.is_ascii_digit()) => {
+                (host, Some(port.parse()?))
+            },
+            _ => (pattern, None),
+        };
+        let (host, subdomains) = match host.strip_prefix("*.") {
+            Some(host) => (host, true),
+            None => (host, false),
+        };
+        let host = Host::parse(host)?;
+        if subdomains && !matches!(host, Host::Domain(_)) {
+            bail!("Only domains can have subdomains");
+        }
+        Ok(Self {
+            host,
+            subdomains,
+            port,
+        })
+    }
+
+    /// Check if the pattern matches the host and port of a URL.
+    fn matches(&self, url: &Url) -> bool {
+        if self.port.is_some() && self.port != url.port_or_known_default() {
+            return false;
+        }
+        match (&self.host, url.host()) {
+            (Host::Domain(parent), Some(Host::Domain(domain))) if self.subdomains => domain
+                .strip_suffix(parent.as_str())
+                .is_some_and(|prefix| prefix.len() > 1 && prefix.ends_with('.')),
+            (host, Some(other)) => *host == other.to_owned(),
+            (_, None) => false,
+        }
+    }
+}
+
+/// Check that a widget is allowed to send requests to a URL.
+///
+/// ### Errors
+///
+/// - The URL is not an HTTP or HTTPS URL.
+/// - Invalid host patterns in the widget configuration.
+/// - The host of the URL is not allowed.
+pub fn check(engine: &EngineInterface, id: &str, url: &Url) -> Result<()> {
+    if !matches!(url.scheme(), "http" | "https") {
+        bail!("Unsupported URL scheme: {}", url.scheme());
+    }
+
+    let patterns = serde_json::from_value::<Option<Vec<String>>>(engine.widget_permissions(id)?)
+        .context("Invalid permissions.http in deskulpt.conf.json")?
+        .unwrap_or_default();
+    for pattern in &patterns {
+        let pattern = HostPattern::parse(pattern)
+            .with_context(|| format!("Invalid host pattern in deskulpt.conf.json: {pattern:?}"))?;
+        if pattern.matches(url) {
+            return Ok(());
+        }
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| anyhow!("URL has no host: {url}"))?;
+    bail!("Host not allowed: {host}; add it to permissions.http in deskulpt.conf.json");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, url: &str) -> bool {
+        HostPattern::parse(pattern)
+            .unwrap()
+            .matches(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_match_host() {
+        assert!(matches("example.com", "https://example.com/path"));
+        assert!(matches("example.com", "http://example.com:8080"));
+        assert!(!matches("example.com", "https://api.example.com"));
+        assert!(!matches("example.com", "https://example.org"));
+        assert!(matches("127.0.0.1", "http://127.0.0.1:3000"));
+        assert!(matches("[::1]", "http://[::1]:3000"));
+    }
+
+    #[test]
+    fn test_match_subdomains() {
+        assert!(matches("*.example.com", "https://api.example.com"));
+        assert!(matches("*.example.com", "https://a.b.example.com"));
+        assert!(!matches("*.example.com", "https://example.com"));
+        assert!(!matches("*.example.com", "https://badexample.com"));
+        assert!(!matches(
+            "*.example.com",
+            "https://api.example.com.evil.org"
+        ));
+        assert!(HostPattern::parse("*.127.0.0.1").is_err());
+    }
+
+    #[test]
+    fn test_match_port() {
+        assert!(matches("localhost:8080", "http://localhost:8080"));
+        assert!(!matches("localhost:8080", "http://localhost:8081"));
+        assert!(!matches("localhost:8080", "http://localhost"));
+        assert!(matches("example.com:443", "https://example.com"));
+        assert!(!matches("example.com:443", "http://example.com"));
+        assert!(matches("*.example.com:443", "https://api.example.com"));
+        assert!(matches("[::1]:8080", "http://[::1]:8080"));
+        assert!(!matches("[::1]:8080", "http://[::1]:8081"));
+    }
+}
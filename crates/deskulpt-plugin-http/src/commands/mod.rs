@@ -0,0 +1,6 @@
+//! HTTP client plugin commands.
+
+mod request;
+
+#[doc(hidden)]
+pub use request::Request;
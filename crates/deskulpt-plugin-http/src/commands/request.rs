@@ -0,0 +1,299 @@
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{allowlist, HttpPlugin};
+
+/// The default timeout of a request in milliseconds.
+const DEFAULT_TIMEOUT: u64 = 30_000;
+
+/// The maximum timeout of a request in milliseconds.
+const MAX_TIMEOUT: u64 = 300_000;
+
+/// The default maximum size of a response body in bytes.
+const DEFAULT_MAX_RESPONSE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// The maximum size of a response body in bytes that can be requested.
+const MAX_RESPONSE_SIZE: u64 = 100 * 1024 * 1024;
+
+pub struct Request;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestInputPayload {
+    url: String,
+    #[serde(default = "default_method")]
+    method: String,
+    /// The `Host` header cannot be set, since it must match the allowed URL.
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    body: Option<String>,
+    /// Timeout of the whole request in milliseconds, including reading the
+    /// response body.
+    timeout: Option<u64>,
+    /// Maximum size of the response body in bytes.
+    max_response_size: Option<u64>,
+}
+
+fn default_method() -> String {
+    "GET".to_string()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestOutputPayload {
+    status: u16,
+    status_text: String,
+    /// Header names are lowercase, and multiple values of the same header are
+    /// joined with `, `.
+    headers: BTreeMap<String, String>,
+    /// The response body, decoded as UTF-8 with invalid sequences replaced.
+    body: String,
+}
+
+/// Send a request and read the response.
+fn send(
+    plugin: &HttpPlugin,
+    url: &Url,
+    input: RequestInputPayload,
+) -> Result<RequestOutputPayload> {
+    let timeout = input.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    if timeout > MAX_TIMEOUT {
+        bail!("Timeout must be at most {MAX_TIMEOUT}ms, got {timeout}ms");
+    }
+    let max_response_size = input.max_response_size.unwrap_or(DEFAULT_MAX_RESPONSE_SIZE);
+    if max_response_size > MAX_RESPONSE_SIZE {
+        bail!("Maximum response size must be at most {MAX_RESPONSE_SIZE} bytes");
+    }
+    // Otherwise a request to an allowed address could reach a virtual host
+    // that is not allowed
+    if input
+        .headers
+        .keys()
+        .any(|name| name.eq_ignore_ascii_case("host"))
+    {
+        bail!("The Host header cannot be set");
+    }
+
+    let mut request = plugin
+        .agent
+        .request_url(&input.method.to_uppercase(), url)
+        .timeout(Duration::from_millis(timeout));
+    for (name, value) in &input.headers {
+        request = request.set(name, value);
+    }
+    let result = match &input.body {
+        Some(body) => request.send_string(body),
+        None => request.call(),
+    };
+
+    // Error statuses are returned as responses since the widget may need to
+    // inspect them, e.g., the error message in the body
+    let response = match result {
+        Ok(response) | Err(ureq::Error::Status(_, response)) => response,
+        Err(e) => return Err(e).context("Failed to send request"),
+    };
+
+    let mut headers = BTreeMap::new();
+    for name in response.headers_names() {
+        let value = response.all(&name).join(", ");
+        headers.insert(name, value);
+    }
+    let status = response.status();
+    let status_text = response.status_text().to_string();
+
+    let mut body = vec![];
+    response
+        .into_reader()
+        .take(max_response_size + 1)
+        .read_to_end(&mut body)
+        .context("Failed to read response body")?;
+    if body.len() as u64 > max_response_size {
+        bail!("Response body exceeds {max_response_size} bytes");
+    }
+
+    Ok(RequestOutputPayload {
+        status,
+        status_text,
+        headers,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+impl PluginCommand for Request {
+    type Plugin = HttpPlugin;
+
+    fn name(&self) -> &str {
+        "request"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: RequestInputPayload,
+    ) -> Result<RequestOutputPayload> {
+        let url = Url::parse(&input.url).with_context(|| format!("Invalid URL: {}", input.url))?;
+        allowlist::check(engine, &id, &url)?;
+        send(plugin, &url, input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    use deskulpt_plugin::serde_json::{json, Value};
+
+    use super::*;
+
+    /// A mock server on a local port that answers every request with the same
+    /// response after a delay.
+    struct MockServer {
+        port: u16,
+        /// Number of requests received so far.
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl MockServer {
+        fn start(response: String, delay: Duration) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let requests = Arc::new(AtomicUsize::new(0));
+            let counter = requests.clone();
+            thread::spawn(move || {
+                for stream in listener.incoming() {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).unwrap() > 2 {
+                        line.clear();
+                    }
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(delay);
+                    let _ = stream.write_all(response.as_bytes());
+                }
+            });
+            Self { port, requests }
+        }
+
+        fn respond(status: &str, headers: &str, body: &str) -> Self {
+            let response = format!(
+                "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: \
+                 close\r\n\r\n{body}",
+                body.len()
+            );
+            Self::start(response, Duration::ZERO)
+        }
+
+        fn url(&self) -> String {
+            format!("http://127.0.0.1:{}/path", self.port)
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(Ordering::SeqCst)
+        }
+    }
+
+    /// Send a request as a widget with the given host patterns.
+    fn request(patterns: Value, payload: Value) -> Result<Value> {
+        deskulpt_plugin::call_plugin(
+            |_| unreachable!(),
+            |_| unreachable!(),
+            move |_| Ok(patterns.clone()),
+            |_, _| Ok(()),
+            &HttpPlugin::default(),
+            "request",
+            "widget".to_string(),
+            Some(payload),
+        )
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let server = MockServer::respond("200 OK", "", "ok");
+        let url = server.url();
+        let other_port = server.port.wrapping_add(1);
+
+        for patterns in [
+            json!(["127.0.0.1"]),
+            json!([format!("127.0.0.1:{}", server.port)]),
+        ] {
+            let output = request(patterns, json!({ "url": url })).unwrap();
+            assert_eq!(output["body"], "ok");
+        }
+        assert_eq!(server.requests(), 2);
+
+        for patterns in [
+            Value::Null,
+            json!([]),
+            json!(["localhost", "127.0.0.2", "*.127.0.0.1"]),
+            json!([format!("127.0.0.1:{other_port}")]),
+        ] {
+            assert!(request(patterns, json!({ "url": url })).is_err());
+        }
+        assert_eq!(server.requests(), 2);
+    }
+
+    #[test]
+    fn test_host_header_rejected() {
+        let server = MockServer::respond("200 OK", "", "ok");
+        let payload = json!({ "url": server.url(), "headers": { "HOST": "example.com" } });
+        assert!(request(json!(["127.0.0.1"]), payload).is_err());
+        assert_eq!(server.requests(), 0);
+    }
+
+    #[test]
+    fn test_redirect_not_followed() {
+        let server = MockServer::respond("302 Found", "Location: /other\r\n", "");
+        let output = request(json!(["127.0.0.1"]), json!({ "url": server.url() })).unwrap();
+        assert_eq!(output["status"], 302);
+        assert_eq!(output["headers"]["location"], "/other");
+        assert_eq!(server.requests(), 1);
+    }
+
+    #[test]
+    fn test_error_status_returned() {
+        let server = MockServer::respond("404 Not Found", "", "missing");
+        let output = request(json!(["127.0.0.1"]), json!({ "url": server.url() })).unwrap();
+        assert_eq!(output["status"], 404);
+        assert_eq!(output["statusText"], "Not Found");
+        assert_eq!(output["body"], "missing");
+    }
+
+    #[test]
+    fn test_timeout() {
+        let server = MockServer::start(
+            "HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n".to_string(),
+            Duration::from_secs(2),
+        );
+        let payload = json!({ "url": server.url(), "timeout": 200 });
+        assert!(request(json!(["127.0.0.1"]), payload).is_err());
+
+        let payload = json!({ "url": server.url(), "timeout": MAX_TIMEOUT + 1 });
+        assert!(request(json!(["127.0.0.1"]), payload).is_err());
+    }
+
+    #[test]
+    fn test_max_response_size() {
+        let server = MockServer::respond("200 OK", "", &"x".repeat(100));
+        let payload = json!({ "url": server.url(), "maxResponseSize": 100 });
+        let output = request(json!(["127.0.0.1"]), payload).unwrap();
+        assert_eq!(output["body"].as_str().unwrap().len(), 100);
+
+        let payload = json!({ "url": server.url(), "maxResponseSize": 99 });
+        let error = request(json!(["127.0.0.1"]), payload).unwrap_err();
+        assert!(error.to_string().contains("exceeds 99 bytes"));
+    }
+}
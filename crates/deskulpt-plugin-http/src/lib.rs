@@ -0,0 +1,36 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg",
+    html_favicon_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg"
+)]
+
+mod allowlist;
+mod commands;
+
+use deskulpt_plugin::{register_commands, Plugin};
+use ureq::{Agent, AgentBuilder};
+
+/// The HTTP client plugin.
+///
+/// Requests are sent from the backend instead of the canvas webview, so they
+/// are not subject to CORS. In exchange, a widget may only send requests to
+/// the hosts declared in the `permissions.http` section of its configuration.
+/// Redirects are not followed, because the new location may not be allowed.
+/// Instead, the redirect response is returned as is so that the widget can
+/// follow it with another request.
+pub struct HttpPlugin {
+    /// The agent for sending requests, sharing connections across requests.
+    agent: Agent,
+}
+
+impl Default for HttpPlugin {
+    fn default() -> Self {
+        Self {
+            agent: AgentBuilder::new().redirects(0).build(),
+        }
+    }
+}
+
+impl Plugin for HttpPlugin {
+    register_commands![commands::Request];
+}
@@ -0,0 +1,34 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Deserialize;
+
+use crate::ShellPlugin;
+
+pub struct Kill;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KillInputPayload {
+    handle: u32,
+}
+
+impl PluginCommand for Kill {
+    type Plugin = ShellPlugin;
+
+    fn name(&self) -> &str {
+        "kill"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: KillInputPayload,
+    ) -> Result<bool> {
+        // Processes that have exited are no longer known, so this returns
+        // false for them as well as for processes of other widgets
+        Ok(plugin.processes.lock().unwrap().kill(&id, input.handle))
+    }
+}
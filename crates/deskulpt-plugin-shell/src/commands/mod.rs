@@ -0,0 +1,12 @@
+//! Shell command execution plugin commands.
+
+mod kill;
+mod run;
+mod spawn;
+
+#[doc(hidden)]
+pub use kill::Kill;
+#[doc(hidden)]
+pub use run::Run;
+#[doc(hidden)]
+pub use spawn::Spawn;
@@ -0,0 +1,148 @@
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+
+use crate::process::{read_output, wait_output, wait_with_timeout};
+use crate::sandbox::{self, CommandOptions};
+use crate::ShellPlugin;
+
+/// The default timeout of a command in milliseconds.
+const DEFAULT_TIMEOUT: u64 = 30_000;
+
+/// The maximum timeout of a command in milliseconds.
+const MAX_TIMEOUT: u64 = 600_000;
+
+/// The default maximum size of captured output in bytes, for each of standard
+/// output and standard error.
+const DEFAULT_MAX_OUTPUT_SIZE: usize = 1024 * 1024;
+
+/// The maximum size of captured output in bytes that can be requested.
+const MAX_OUTPUT_SIZE: usize = 16 * 1024 * 1024;
+
+pub struct Run;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInputPayload {
+    #[serde(flatten)]
+    options: CommandOptions,
+    /// Timeout in milliseconds, after which the process is killed.
+    timeout: Option<u64>,
+    /// Maximum size of captured output in bytes, for each of standard output
+    /// and standard error.
+    max_output_size: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunOutputPayload {
+    /// The exit code, or `None` if the process is terminated by a signal,
+    /// including when it is killed because it timed out.
+    code: Option<i32>,
+    timed_out: bool,
+    /// Standard output, decoded as UTF-8 with invalid sequences replaced.
+    stdout: String,
+    /// Standard error, decoded as UTF-8 with invalid sequences replaced.
+    stderr: String,
+    /// Whether standard output exceeds the maximum size and is cut off.
+    stdout_truncated: bool,
+    /// Whether standard error exceeds the maximum size and is cut off.
+    stderr_truncated: bool,
+}
+
+/// Output of a process captured up to a maximum size.
+#[derive(Default)]
+struct Capture {
+    data: Vec<u8>,
+    truncated: bool,
+}
+
+impl Capture {
+    /// Append a chunk of output, cutting it off at the maximum size.
+    fn push(&mut self, chunk: &[u8], max_size: usize) {
+        let available = max_size.saturating_sub(self.data.len());
+        if chunk.len() > available {
+            self.truncated = true;
+        }
+        self.data
+            .extend_from_slice(&chunk[..chunk.len().min(available)]);
+    }
+
+    /// Take the captured output as a string.
+    fn take(&mut self) -> (String, bool) {
+        let data = std::mem::take(&mut self.data);
+        (String::from_utf8_lossy(&data).into_owned(), self.truncated)
+    }
+}
+
+/// Run a command to completion and capture its output.
+fn run(mut command: std::process::Command, input: &RunInputPayload) -> Result<RunOutputPayload> {
+    let timeout = input.timeout.unwrap_or(DEFAULT_TIMEOUT);
+    if timeout > MAX_TIMEOUT {
+        bail!("Timeout must be at most {MAX_TIMEOUT}ms, got {timeout}ms");
+    }
+    let max_output_size = input.max_output_size.unwrap_or(DEFAULT_MAX_OUTPUT_SIZE);
+    if max_output_size > MAX_OUTPUT_SIZE {
+        bail!("Maximum output size must be at most {MAX_OUTPUT_SIZE} bytes");
+    }
+
+    let mut child = command
+        .spawn()
+        .with_context(|| format!("Failed to run command: {}", input.options.command))?;
+
+    // Output is read concurrently so that the process does not block on full
+    // pipes; output beyond the maximum size is read but discarded
+    let (done_tx, done_rx) = mpsc::channel();
+    let stdout = Arc::new(Mutex::new(Capture::default()));
+    let stderr = Arc::new(Mutex::new(Capture::default()));
+    if let Some(output) = child.stdout.take() {
+        let capture = stdout.clone();
+        read_output(output, done_tx.clone(), move |chunk| {
+            capture.lock().unwrap().push(chunk, max_output_size);
+        });
+    }
+    if let Some(output) = child.stderr.take() {
+        let capture = stderr.clone();
+        read_output(output, done_tx.clone(), move |chunk| {
+            capture.lock().unwrap().push(chunk, max_output_size);
+        });
+    }
+
+    let (status, timed_out) = wait_with_timeout(&mut child, Duration::from_millis(timeout))
+        .context("Failed to wait for command")?;
+    wait_output(&done_rx, 2);
+
+    let (stdout, stdout_truncated) = stdout.lock().unwrap().take();
+    let (stderr, stderr_truncated) = stderr.lock().unwrap().take();
+    Ok(RunOutputPayload {
+        code: status.code(),
+        timed_out,
+        stdout,
+        stderr,
+        stdout_truncated,
+        stderr_truncated,
+    })
+}
+
+impl PluginCommand for Run {
+    type Plugin = ShellPlugin;
+
+    fn name(&self) -> &str {
+        "run"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: RunInputPayload,
+    ) -> Result<RunOutputPayload> {
+        let command = sandbox::command(engine, &id, &input.options)?;
+        run(command, &input)
+    }
+}
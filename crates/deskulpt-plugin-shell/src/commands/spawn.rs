@@ -0,0 +1,161 @@
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use deskulpt_plugin::{dispatch, serde_json, EngineInterface, EventEmitter, PluginCommand};
+use serde::{Deserialize, Serialize};
+
+use crate::process::{read_output, wait_output, Processes, MAX_PROCESSES, POLL_INTERVAL};
+use crate::sandbox::{self, CommandOptions};
+use crate::ShellPlugin;
+
+pub struct Spawn;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpawnInputPayload {
+    #[serde(flatten)]
+    options: CommandOptions,
+    /// Timeout in milliseconds, after which the process is killed. The process
+    /// may run indefinitely if not specified.
+    timeout: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpawnOutputPayload {
+    /// The handle of the process, used to kill it and to pick up its events.
+    handle: u32,
+}
+
+/// Events of a background process, emitted to the widget that spawned it.
+#[derive(Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+enum ProcessEvent {
+    /// A chunk of standard output, usually a line.
+    Stdout { handle: u32, data: String },
+    /// A chunk of standard error, usually a line.
+    Stderr { handle: u32, data: String },
+    /// The process has exited. This is the last event of the process.
+    Exit {
+        handle: u32,
+        /// The exit code, or `None` if the process is terminated by a signal,
+        /// including when it is killed.
+        code: Option<i32>,
+        killed: bool,
+        timed_out: bool,
+    },
+}
+
+impl ProcessEvent {
+    /// Emit the event to a widget.
+    fn emit(&self, emitter: &EventEmitter, id: &str) {
+        // Events can only fail to be delivered when the app is shutting down,
+        // in which case there is no one to receive them anyway
+        if let Ok(payload) = serde_json::to_value(self) {
+            let _ = emitter.emit(id, payload);
+        }
+    }
+}
+
+/// Watch a background process until it exits, then emit its exit event.
+fn watch(
+    processes: Arc<Mutex<Processes>>,
+    handle: u32,
+    timeout: Option<Duration>,
+    done: mpsc::Receiver<()>,
+    emitter: EventEmitter,
+    id: String,
+) {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    thread::spawn(move || {
+        let exit = loop {
+            {
+                let mut processes = processes.lock().unwrap();
+                if let Some(exit) = processes.try_wait(handle) {
+                    break exit;
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    processes.time_out(handle);
+                }
+            }
+            thread::sleep(POLL_INTERVAL);
+        };
+
+        // The exit event comes after all output of the process
+        wait_output(&done, 2);
+        let event = ProcessEvent::Exit {
+            handle,
+            code: exit.status.and_then(|status| status.code()),
+            killed: exit.killed,
+            timed_out: exit.timed_out,
+        };
+        event.emit(&emitter, &id);
+    });
+}
+
+impl PluginCommand for Spawn {
+    type Plugin = ShellPlugin;
+
+    fn name(&self) -> &str {
+        "spawn"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: SpawnInputPayload,
+    ) -> Result<SpawnOutputPayload> {
+        let mut command = sandbox::command(engine, &id, &input.options)?;
+
+        // The lock is held until the process is added so that concurrent
+        // calls cannot exceed the limit
+        let mut processes = plugin.processes.lock().unwrap();
+        if processes.count(&id) >= MAX_PROCESSES {
+            bail!("Widget cannot have more than {MAX_PROCESSES} background processes");
+        }
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("Failed to spawn command: {}", input.options.command))?;
+        let stdout = child.stdout.take();
+        let stderr = child.stderr.take();
+        let handle = processes.insert(id.clone(), child);
+        drop(processes);
+
+        let emitter = engine.event_emitter();
+        let (done_tx, done_rx) = mpsc::channel();
+        if let Some(output) = stdout {
+            let (emitter, id) = (emitter.clone(), id.clone());
+            read_output(output, done_tx.clone(), move |chunk| {
+                let data = String::from_utf8_lossy(chunk).into_owned();
+                ProcessEvent::Stdout { handle, data }.emit(&emitter, &id);
+            });
+        }
+        if let Some(output) = stderr {
+            let (emitter, id) = (emitter.clone(), id.clone());
+            read_output(output, done_tx.clone(), move |chunk| {
+                let data = String::from_utf8_lossy(chunk).into_owned();
+                ProcessEvent::Stderr { handle, data }.emit(&emitter, &id);
+            });
+        }
+
+        let timeout = input.timeout.map(Duration::from_millis);
+        watch(
+            plugin.processes.clone(),
+            handle,
+            timeout,
+            done_rx,
+            emitter,
+            id,
+        );
+        Ok(SpawnOutputPayload { handle })
+    }
+}
@@ -0,0 +1,359 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg",
+    html_favicon_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg"
+)]
+
+mod commands;
+mod process;
+mod sandbox;
+
+use std::sync::{Arc, Mutex};
+
+use deskulpt_plugin::{register_commands, Plugin};
+use process::Processes;
+
+/// The shell command execution plugin.
+///
+/// A widget may only run the commands declared in the `permissions.shell`
+/// section of its configuration, in its own directory or the directories
+/// declared there. Commands either run to completion with their output
+/// captured, or are spawned in the background with their output streamed to
+/// the widget through events until they exit or are killed.
+#[derive(Default)]
+pub struct ShellPlugin {
+    /// The processes spawned in the background.
+    processes: Arc<Mutex<Processes>>,
+}
+
+impl ShellPlugin {
+    /// Kill the background processes of widgets not specified by the
+    /// predicate.
+    ///
+    /// This is for the Deskulpt core to stop the processes of widgets that are
+    /// re-rendered or removed, which can no longer receive their output, and
+    /// of all widgets when Deskulpt exits.
+    pub fn retain_processes(&self, f: impl FnMut(&str) -> bool) {
+        self.processes.lock().unwrap().retain(f);
+    }
+}
+
+impl Plugin for ShellPlugin {
+    register_commands![commands::Kill, commands::Run, commands::Spawn];
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::mpsc::{self, Receiver, Sender};
+    use std::time::Duration;
+
+    use anyhow::Result;
+    use deskulpt_plugin::serde_json::{json, Value};
+
+    use super::*;
+
+    /// How long to wait for an event of a background process.
+    const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Call a command of the plugin as a widget in `widget_dir` with the given
+    /// shell permissions, sending the emitted events to `events`.
+    fn call(
+        plugin: &ShellPlugin,
+        widget_dir: &Path,
+        permissions: Value,
+        events: Sender<(String, Value)>,
+        command: &str,
+        id: &str,
+        payload: Value,
+    ) -> Result<Value> {
+        let widget_dir = widget_dir.to_path_buf();
+        deskulpt_plugin::call_plugin(
+            move |_| Ok(widget_dir.clone()),
+            |_| unreachable!(),
+            move |_| Ok(permissions.clone()),
+            move |id, payload| {
+                let _ = events.send((id.to_string(), payload));
+                Ok(())
+            },
+            plugin,
+            command,
+            id.to_string(),
+            Some(payload),
+        )
+    }
+
+    /// Run a command as a widget in `widget_dir` with the given shell
+    /// permissions.
+    fn run(widget_dir: &Path, permissions: Value, payload: Value) -> Result<Value> {
+        let plugin = ShellPlugin::default();
+        let (events, _) = mpsc::channel();
+        call(
+            &plugin,
+            widget_dir,
+            permissions,
+            events,
+            "run",
+            "widget",
+            payload,
+        )
+    }
+
+    /// Receive the next event of a background process, checking that it is
+    /// emitted to the widget that spawned the process.
+    fn recv(events: &Receiver<(String, Value)>) -> Value {
+        let (id, event) = events.recv_timeout(EVENT_TIMEOUT).unwrap();
+        assert_eq!(id, "widget");
+        event
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_allowlist() {
+        let widget_dir = tempfile::tempdir().unwrap();
+        let widget_dir = widget_dir.path();
+        let permissions = json!({ "commands": ["echo", "./script.sh"] });
+
+        let output = run(
+            widget_dir,
+            permissions.clone(),
+            json!({ "command": "echo", "args": ["hello"] }),
+        )
+        .unwrap();
+        assert_eq!(output["stdout"], "hello\n");
+
+        // Commands must be declared exactly, and paths are relative to the
+        // widget directory instead of looked up in PATH
+        for command in ["sh", "/bin/echo", "echo hello", "script.sh", "./other.sh"] {
+            let payload = json!({ "command": command });
+            assert!(run(widget_dir, permissions.clone(), payload).is_err());
+        }
+        for permissions in [Value::Null, json!({}), json!({ "commands": ["ECHO"] })] {
+            let payload = json!({ "command": "echo" });
+            assert!(run(widget_dir, permissions, payload).is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_env() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let widget_dir = tempfile::tempdir().unwrap();
+        let widget_dir = widget_dir.path();
+        let permissions = json!({ "commands": ["sh"], "env": ["GREETING", "PATH"] });
+
+        let payload = json!({
+            "command": "sh",
+            "args": ["-c", "echo $GREETING"],
+            "env": { "GREETING": "hello" },
+        });
+        let output = run(widget_dir, permissions.clone(), payload).unwrap();
+        assert_eq!(output["stdout"], "hello\n");
+
+        // Undeclared variables are rejected, including those that would make
+        // `sh` run other code
+        for name in ["BASH_ENV", "ENV", "LD_PRELOAD", "Path", "greeting"] {
+            let payload = json!({ "command": "sh", "env": { name: "value" } });
+            assert!(
+                run(widget_dir, permissions.clone(), payload).is_err(),
+                "{name}"
+            );
+        }
+
+        // The program is looked up in the PATH of Deskulpt, even if the widget
+        // overrides the PATH of the command
+        let bin_dir = widget_dir.join("bin");
+        std::fs::create_dir(&bin_dir).unwrap();
+        std::fs::write(bin_dir.join("sh"), "#!/bin/sh\necho fake\n").unwrap();
+        std::fs::set_permissions(bin_dir.join("sh"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        let payload = json!({
+            "command": "sh",
+            "args": ["-c", "echo real"],
+            "env": { "PATH": bin_dir },
+        });
+        let output = run(widget_dir, permissions, payload).unwrap();
+        assert_eq!(output["stdout"], "real\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run() {
+        let widget_dir = tempfile::tempdir().unwrap();
+        let widget_dir = widget_dir.path();
+        let permissions = json!({ "commands": ["sh", "sleep"] });
+
+        let payload = json!({
+            "command": "sh",
+            "args": ["-c", "printf 0123456789; printf abc >&2; exit 3"],
+            "maxOutputSize": 4,
+        });
+        let output = run(widget_dir, permissions.clone(), payload).unwrap();
+        assert_eq!(
+            output,
+            json!({
+                "code": 3,
+                "timedOut": false,
+                "stdout": "0123",
+                "stderr": "abc",
+                "stdoutTruncated": true,
+                "stderrTruncated": false,
+            })
+        );
+
+        let payload = json!({ "command": "sleep", "args": ["5"], "timeout": 100 });
+        let output = run(widget_dir, permissions.clone(), payload).unwrap();
+        assert_eq!(output["code"], Value::Null);
+        assert_eq!(output["timedOut"], true);
+
+        // Limits cannot be raised beyond the maximum
+        for payload in [
+            json!({ "command": "sleep", "args": ["0"], "timeout": 600_001 }),
+            json!({ "command": "sleep", "args": ["0"], "maxOutputSize": 16 * 1024 * 1024 + 1 }),
+        ] {
+            assert!(run(widget_dir, permissions.clone(), payload).is_err());
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_kill() {
+        let widget_dir = tempfile::tempdir().unwrap();
+        let plugin = ShellPlugin::default();
+        let (events_tx, events) = mpsc::channel();
+        let call = |command, id, payload| {
+            let permissions = json!({ "commands": ["sh"] });
+            let events = events_tx.clone();
+            call(
+                &plugin,
+                widget_dir.path(),
+                permissions,
+                events,
+                command,
+                id,
+                payload,
+            )
+        };
+
+        let payload = json!({ "command": "sh", "args": ["-c", "echo a; sleep 5"] });
+        let handle = call("spawn", "widget", payload).unwrap()["handle"].clone();
+        assert_eq!(
+            recv(&events),
+            json!({ "type": "stdout", "handle": handle, "data": "a\n" })
+        );
+
+        // Processes of other widgets cannot be killed
+        let payload = json!({ "handle": handle });
+        assert_eq!(call("kill", "other", payload.clone()).unwrap(), false);
+        assert_eq!(call("kill", "widget", payload.clone()).unwrap(), true);
+
+        // The exit event comes without waiting for `sleep` because the whole
+        // process group is killed
+        assert_eq!(
+            recv(&events),
+            json!({
+                "type": "exit",
+                "handle": handle,
+                "code": null,
+                "killed": true,
+                "timedOut": false,
+            })
+        );
+        assert_eq!(call("kill", "widget", payload).unwrap(), false);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_events() {
+        let widget_dir = tempfile::tempdir().unwrap();
+        let plugin = ShellPlugin::default();
+        let (events_tx, events) = mpsc::channel();
+        let call = |payload| {
+            let permissions = json!({ "commands": ["sh", "sleep"] });
+            let events = events_tx.clone();
+            call(
+                &plugin,
+                widget_dir.path(),
+                permissions,
+                events,
+                "spawn",
+                "widget",
+                payload,
+            )
+        };
+
+        let payload = json!({ "command": "sh", "args": ["-c", "echo out; echo err >&2; exit 3"] });
+        let handle = call(payload).unwrap()["handle"].clone();
+        let mut output = vec![recv(&events), recv(&events)];
+        output.sort_by_key(|event| event["type"].to_string());
+        assert_eq!(
+            output,
+            [
+                json!({ "type": "stderr", "handle": handle, "data": "err\n" }),
+                json!({ "type": "stdout", "handle": handle, "data": "out\n" }),
+            ]
+        );
+        assert_eq!(
+            recv(&events),
+            json!({
+                "type": "exit",
+                "handle": handle,
+                "code": 3,
+                "killed": false,
+                "timedOut": false,
+            })
+        );
+
+        let payload = json!({ "command": "sleep", "args": ["5"], "timeout": 100 });
+        let handle = call(payload).unwrap()["handle"].clone();
+        assert_eq!(
+            recv(&events),
+            json!({
+                "type": "exit",
+                "handle": handle,
+                "code": null,
+                "killed": false,
+                "timedOut": true,
+            })
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_spawn_limit() {
+        let widget_dir = tempfile::tempdir().unwrap();
+        let plugin = ShellPlugin::default();
+        let (events_tx, events) = mpsc::channel();
+        let call = |id| {
+            let permissions = json!({ "commands": ["sleep"] });
+            let events = events_tx.clone();
+            let payload = json!({ "command": "sleep", "args": ["5"] });
+            call(
+                &plugin,
+                widget_dir.path(),
+                permissions,
+                events,
+                "spawn",
+                id,
+                payload,
+            )
+        };
+
+        for _ in 0..process::MAX_PROCESSES {
+            call("widget").unwrap();
+        }
+        assert!(call("widget").is_err());
+        call("other").unwrap();
+
+        // Processes of the widget are killed when it is no longer retained
+        plugin.retain_processes(|id| id != "widget");
+        for _ in 0..process::MAX_PROCESSES {
+            let event = recv(&events);
+            assert_eq!(event["type"], "exit");
+            assert_eq!(event["killed"], true);
+        }
+        call("widget").unwrap();
+        plugin.retain_processes(|_| false);
+    }
+}
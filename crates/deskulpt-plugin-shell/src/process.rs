@@ -0,0 +1,212 @@
+//! Utilities for managing processes and reading their output.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, ExitStatus};
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The interval for polling whether a process has exited.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// The maximum number of background processes of each widget.
+pub const MAX_PROCESSES: usize = 16;
+
+/// The maximum size of a chunk of output in bytes.
+///
+/// Output is read line by line, and lines longer than this are split into
+/// multiple chunks.
+const MAX_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// How long to wait for the rest of the output after a process exits.
+///
+/// Output pipes may be kept open by descendants of the process even after it
+/// exits, in which case the rest of the output is abandoned.
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+
+/// A process spawned in the background.
+struct Process {
+    /// The ID of the widget that spawned the process.
+    id: String,
+    /// The child process.
+    child: Child,
+    /// Whether the process has been killed on request.
+    killed: bool,
+    /// Whether the process has been killed because it timed out.
+    timed_out: bool,
+}
+
+/// How a background process has exited.
+pub struct Exit {
+    /// The exit status, or `None` if it could not be obtained.
+    pub status: Option<ExitStatus>,
+    /// Whether the process has been killed on request.
+    pub killed: bool,
+    /// Whether the process has been killed because it timed out.
+    pub timed_out: bool,
+}
+
+/// The processes spawned in the background.
+#[derive(Default)]
+pub struct Processes {
+    /// The handle to assign to the next process.
+    next_handle: u32,
+    /// The mapping from handles to processes.
+    processes: HashMap<u32, Process>,
+}
+
+impl Processes {
+    /// Count the background processes of a widget.
+    pub fn count(&self, id: &str) -> usize {
+        self.processes
+            .values()
+            .filter(|process| process.id == id)
+            .count()
+    }
+
+    /// Add a background process of a widget and return its handle.
+    pub fn insert(&mut self, id: String, child: Child) -> u32 {
+        let handle = self.next_handle;
+        self.next_handle = self.next_handle.wrapping_add(1);
+        let process = Process {
+            id,
+            child,
+            killed: false,
+            timed_out: false,
+        };
+        self.processes.insert(handle, process);
+        handle
+    }
+
+    /// Kill a background process of a widget.
+    ///
+    /// This returns whether the process was found. Processes of other widgets
+    /// are treated as not found.
+    pub fn kill(&mut self, id: &str, handle: u32) -> bool {
+        match self.processes.get_mut(&handle) {
+            Some(process) if process.id == id => {
+                // The process may have exited already, which is fine
+                let _ = kill(&mut process.child);
+                process.killed = true;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Kill a background process because it timed out.
+    pub fn time_out(&mut self, handle: u32) {
+        if let Some(process) = self.processes.get_mut(&handle) {
+            let _ = kill(&mut process.child);
+            process.timed_out = true;
+        }
+    }
+
+    /// Kill the background processes of widgets not specified by the
+    /// predicate.
+    pub fn retain(&mut self, mut f: impl FnMut(&str) -> bool) {
+        for process in self.processes.values_mut() {
+            if !f(&process.id) {
+                let _ = kill(&mut process.child);
+                process.killed = true;
+            }
+        }
+    }
+
+    /// Check whether a background process has exited.
+    ///
+    /// The process is removed once it has exited, or if its status cannot be
+    /// obtained. Unknown handles are treated as exited.
+    pub fn try_wait(&mut self, handle: u32) -> Option<Exit> {
+        let status = match self.processes.get_mut(&handle).map(|p| p.child.try_wait()) {
+            Some(Ok(None)) => return None,
+            Some(Ok(Some(status))) => Some(status),
+            Some(Err(_)) | None => None,
+        };
+        let process = self.processes.remove(&handle);
+        Some(Exit {
+            status,
+            killed: process.as_ref().is_some_and(|process| process.killed),
+            timed_out: process.as_ref().is_some_and(|process| process.timed_out),
+        })
+    }
+}
+
+/// Kill a process together with its descendants where possible.
+///
+/// On Unix, processes are spawned as leaders of their own process groups, so
+/// the whole process group is killed. Elsewhere only the process itself is
+/// killed.
+fn kill(child: &mut Child) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        // SAFETY: This only sends a signal. The child has not been waited for,
+        // so its ID still identifies the process group it leads
+        let pgid = child.id() as libc::pid_t;
+        if unsafe { libc::kill(-pgid, libc::SIGKILL) } == 0 {
+            return Ok(());
+        }
+    }
+    child.kill()
+}
+
+/// Wait for a process to exit, killing it if it exceeds the timeout.
+///
+/// This returns the exit status and whether the process timed out.
+pub fn wait_with_timeout(
+    child: &mut Child,
+    timeout: Duration,
+) -> std::io::Result<(ExitStatus, bool)> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok((status, false));
+        }
+        if Instant::now() >= deadline {
+            kill(child)?;
+            return Ok((child.wait()?, true));
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Read output of a process in chunks on a separate thread.
+///
+/// Each chunk is a line including the trailing newline, or part of a line if
+/// it is too long. The thread sends to `done` once the output is closed.
+pub fn read_output(
+    output: impl Read + Send + 'static,
+    done: Sender<()>,
+    mut f: impl FnMut(&[u8]) + Send + 'static,
+) {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(output);
+        let mut chunk = vec![];
+        loop {
+            chunk.clear();
+            match reader
+                .by_ref()
+                .take(MAX_CHUNK_SIZE)
+                .read_until(b'\n', &mut chunk)
+            {
+                Ok(0) | Err(_) => break,
+                Ok(_) => f(&chunk),
+            }
+        }
+        let _ = done.send(());
+    });
+}
+
+/// Wait for the output readers of an exited process to finish.
+///
+/// See [`OUTPUT_GRACE`] for how long this waits at most.
+pub fn wait_output(done: &Receiver<()>, readers: usize) {
+    let deadline = Instant::now() + OUTPUT_GRACE;
+    for _ in 0..readers {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        if done.recv_timeout(timeout).is_err() {
+            break;
+        }
+    }
+}
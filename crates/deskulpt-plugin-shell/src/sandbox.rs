@@ -0,0 +1,307 @@
+//! Sandbox of the commands that widgets may run.
+
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{bail, Context, Result};
+use deskulpt_plugin::{serde_json, EngineInterface};
+use serde::Deserialize;
+
+/// The `permissions.shell` section of the widget configuration.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ShellPermissions {
+    commands: Vec<String>,
+    dirs: Vec<String>,
+    env: Vec<String>,
+}
+
+/// Options of a command, shared by the plugin commands that run processes.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOptions {
+    /// The command to run, exactly as declared in `permissions.shell.commands`.
+    pub command: String,
+    #[serde(default)]
+    args: Vec<String>,
+    /// The working directory, relative to the widget directory if relative.
+    /// Defaults to the widget directory.
+    cwd: Option<String>,
+    /// Environment variables in addition to those inherited from Deskulpt,
+    /// each declared in `permissions.shell.env`.
+    #[serde(default)]
+    env: BTreeMap<String, String>,
+}
+
+/// Build a command that a widget is allowed to run.
+///
+/// Standard input of the command is closed, and standard output and error are
+/// piped.
+///
+/// ### Errors
+///
+/// - Invalid shell permissions in the widget configuration.
+/// - The command is not allowed.
+/// - The environment variables are not allowed.
+/// - The program is not found in `PATH`.
+/// - The working directory does not exist or is not allowed.
+pub fn command(engine: &EngineInterface, id: &str, options: &CommandOptions) -> Result<Command> {
+    let permissions =
+        serde_json::from_value::<Option<ShellPermissions>>(engine.widget_permissions(id)?)
+            .context("Invalid permissions.shell in deskulpt.conf.json")?
+            .unwrap_or_default();
+    if !permissions.commands.contains(&options.command) {
+        bail!(
+            "Command not allowed: {}; add it to permissions.shell.commands in deskulpt.conf.json",
+            options.command,
+        );
+    }
+
+    check_env(&options.env, &permissions.env)?;
+
+    // Program names are looked up in PATH, while paths, e.g., `./script.sh`,
+    // are relative to the widget directory. Programs are resolved here because
+    // the lookup would otherwise use the PATH of the command
+    let widget_dir = engine.widget_dir(id)?;
+    let program = if Path::new(&options.command).components().count() > 1 {
+        widget_dir.join(&options.command)
+    } else {
+        find_program(&options.command, std::env::var_os("PATH").as_deref())?
+    };
+    let cwd = working_dir(&widget_dir, options.cwd.as_deref(), &permissions.dirs)?;
+
+    let mut command = Command::new(program);
+    command
+        .args(&options.args)
+        .current_dir(cwd)
+        .envs(&options.env)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+
+        // Run in a new process group so that descendants of the process, e.g.,
+        // of `sh -c`, can be killed together with it
+        command.process_group(0);
+    }
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        // CREATE_NO_WINDOW, so that console programs do not pop up a window
+        command.creation_flags(0x08000000);
+    }
+
+    Ok(command)
+}
+
+/// Check that the environment variables of a command are declared.
+///
+/// Many variables make programs run other code, e.g. `LD_PRELOAD` or
+/// `GIT_SSH_COMMAND`, so only the declared variables can be set. Names are
+/// compared exactly, so a variable declared as `PATH` cannot be set as `Path`.
+fn check_env(env: &BTreeMap<String, String>, allowed: &[String]) -> Result<()> {
+    for name in env.keys() {
+        if !allowed.contains(name) {
+            bail!(
+                "Environment variable not allowed: {name}; add it to permissions.shell.env in \
+                 deskulpt.conf.json"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Find a program by name in the directories of a `PATH` value.
+///
+/// Relative directories are skipped since they would be relative to the
+/// working directory of Deskulpt. On Windows, the extensions in `PATHEXT` are
+/// tried if the name has none.
+fn find_program(name: &str, path: Option<&OsStr>) -> Result<PathBuf> {
+    #[cfg(windows)]
+    let extensions = if Path::new(name).extension().is_some() {
+        vec![String::new()]
+    } else {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string())
+            .split(';')
+            .filter(|extension| !extension.is_empty())
+            .map(ToString::to_string)
+            .collect()
+    };
+    #[cfg(not(windows))]
+    let extensions = vec![String::new()];
+
+    for dir in std::env::split_paths(path.unwrap_or_default()) {
+        if !dir.is_absolute() {
+            continue;
+        }
+        for extension in &extensions {
+            let program = dir.join(format!("{name}{extension}"));
+            if is_executable(&program) {
+                return Ok(program);
+            }
+        }
+    }
+    bail!("Command not found in PATH: {name}");
+}
+
+/// Check if a path is an executable file.
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        path.metadata()
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    }
+
+    #[cfg(not(unix))]
+    path.is_file()
+}
+
+/// Resolve the working directory of a command.
+///
+/// The working directory must be the widget directory or any of the declared
+/// directories, or be inside one of them. Paths are canonicalized before the
+/// check so that `..` and symlinks cannot escape the allowed directories.
+/// Declared directories that do not exist are ignored.
+fn working_dir(widget_dir: &Path, cwd: Option<&str>, dirs: &[String]) -> Result<PathBuf> {
+    let cwd = widget_dir.join(cwd.unwrap_or_default());
+    let cwd = dunce::canonicalize(&cwd)
+        .with_context(|| format!("Invalid working directory: {}", cwd.display()))?;
+    if !cwd.is_dir() {
+        bail!("Working directory is not a directory: {}", cwd.display());
+    }
+
+    let allowed = std::iter::once(widget_dir.to_path_buf())
+        .chain(dirs.iter().map(|dir| widget_dir.join(dir)))
+        .filter_map(|dir| dunce::canonicalize(dir).ok())
+        .any(|dir| cwd.starts_with(dir));
+    if !allowed {
+        bail!(
+            "Working directory not allowed: {}; add it to permissions.shell.dirs in \
+             deskulpt.conf.json",
+            cwd.display(),
+        );
+    }
+    Ok(cwd)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Create a temporary directory with a widget directory and a directory
+    /// outside of it, returning the canonical paths of both.
+    fn setup() -> (TempDir, PathBuf, PathBuf) {
+        let root = TempDir::new().unwrap();
+        std::fs::create_dir_all(root.path().join("widget/sub")).unwrap();
+        std::fs::create_dir_all(root.path().join("outside/sub")).unwrap();
+        let path = dunce::canonicalize(root.path()).unwrap();
+        (root, path.join("widget"), path.join("outside"))
+    }
+
+    #[test]
+    fn test_working_dir_relative() {
+        let (_root, widget_dir, outside) = setup();
+        assert_eq!(working_dir(&widget_dir, None, &[]).unwrap(), widget_dir);
+        assert_eq!(
+            working_dir(&widget_dir, Some("sub"), &[]).unwrap(),
+            widget_dir.join("sub")
+        );
+        assert_eq!(
+            working_dir(&widget_dir, Some("sub/.."), &[]).unwrap(),
+            widget_dir
+        );
+
+        assert!(working_dir(&widget_dir, Some(".."), &[]).is_err());
+        assert!(working_dir(&widget_dir, Some("../outside"), &[]).is_err());
+        assert!(working_dir(&widget_dir, Some("missing"), &[]).is_err());
+
+        let dirs = ["../outside".to_string()];
+        assert_eq!(
+            working_dir(&widget_dir, Some("../outside/sub"), &dirs).unwrap(),
+            outside.join("sub")
+        );
+        assert!(working_dir(&widget_dir, Some(".."), &dirs).is_err());
+    }
+
+    #[test]
+    fn test_working_dir_absolute() {
+        let (_root, widget_dir, outside) = setup();
+        let cwd = widget_dir.join("sub");
+        assert_eq!(working_dir(&widget_dir, cwd.to_str(), &[]).unwrap(), cwd);
+        assert!(working_dir(&widget_dir, outside.to_str(), &[]).is_err());
+        assert!(working_dir(&widget_dir, widget_dir.parent().unwrap().to_str(), &[]).is_err());
+
+        let dirs = [outside.to_string_lossy().into_owned()];
+        assert_eq!(
+            working_dir(&widget_dir, outside.to_str(), &dirs).unwrap(),
+            outside
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_working_dir_symlink() {
+        let (_root, widget_dir, outside) = setup();
+        std::os::unix::fs::symlink(&outside, widget_dir.join("link")).unwrap();
+        assert!(working_dir(&widget_dir, Some("link"), &[]).is_err());
+        assert!(working_dir(&widget_dir, Some("link/sub"), &[]).is_err());
+
+        // Declared directories are canonicalized as well
+        let dirs = ["link".to_string()];
+        assert_eq!(
+            working_dir(&widget_dir, Some("link/sub"), &dirs).unwrap(),
+            outside.join("sub")
+        );
+    }
+
+    #[test]
+    fn test_check_env() {
+        let env = |name: &str| BTreeMap::from([(name.to_string(), "value".to_string())]);
+        let allowed = ["LANG".to_string(), "PATH".to_string()];
+        assert!(check_env(&BTreeMap::new(), &[]).is_ok());
+        assert!(check_env(&env("LANG"), &allowed).is_ok());
+        assert!(check_env(&env("PATH"), &allowed).is_ok());
+        for name in [
+            "Path",
+            "LANGUAGE",
+            "LD_PRELOAD",
+            "BASH_ENV",
+            "GIT_SSH_COMMAND",
+        ] {
+            assert!(check_env(&env(name), &allowed).is_err(), "{name}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_find_program() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_root, first, second) = setup();
+        std::fs::write(first.join("tool"), "").unwrap();
+        std::fs::write(second.join("tool"), "").unwrap();
+        std::fs::set_permissions(second.join("tool"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+        let path = std::env::join_paths(["relative".as_ref(), first.as_path(), &second]).unwrap();
+
+        // Files that are not executable are skipped
+        assert_eq!(
+            find_program("tool", Some(&path)).unwrap(),
+            second.join("tool")
+        );
+        assert!(find_program("missing", Some(&path)).is_err());
+        assert!(find_program("tool", None).is_err());
+    }
+}
@@ -0,0 +1,27 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+
+use crate::database::{self, ExecuteOutput, StatementPayload};
+use crate::SqlitePlugin;
+
+pub struct Execute;
+
+impl PluginCommand for Execute {
+    type Plugin = SqlitePlugin;
+
+    fn name(&self) -> &str {
+        "execute"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: StatementPayload,
+    ) -> Result<ExecuteOutput> {
+        let conn = database::open(engine, &id)?;
+        database::execute(&conn, &input)
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::{bail, Context, Result};
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+
+use crate::{database, SqlitePlugin};
+
+pub struct Migrate;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateInputPayload {
+    /// SQL scripts that migrate the schema, each possibly consisting of
+    /// multiple statements.
+    ///
+    /// The version of the schema is the number of applied migrations, so
+    /// migrations must only be appended and never modified or removed.
+    migrations: Vec<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrateOutputPayload {
+    /// The schema version before the migration.
+    from: usize,
+    /// The schema version after the migration.
+    to: usize,
+}
+
+impl PluginCommand for Migrate {
+    type Plugin = SqlitePlugin;
+
+    fn name(&self) -> &str {
+        "migrate"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: MigrateInputPayload,
+    ) -> Result<MigrateOutputPayload> {
+        let mut conn = database::open(engine, &id)?;
+
+        // The schema version is tracked with the user version of the database
+        let from = conn.pragma_query_value(None, "user_version", |row| row.get::<_, usize>(0))?;
+        if from > input.migrations.len() {
+            bail!(
+                "Database schema version {from} is newer than the {} migrations",
+                input.migrations.len()
+            );
+        }
+
+        // Each migration is applied in its own transaction, so that a failed
+        // migration leaves the database at the version before it
+        for (index, migration) in input.migrations.iter().enumerate().skip(from) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration)
+                .with_context(|| format!("Failed to apply migration {index}"))?;
+            tx.pragma_update(None, "user_version", index + 1)?;
+            tx.commit()?;
+        }
+
+        Ok(MigrateOutputPayload {
+            from,
+            to: input.migrations.len(),
+        })
+    }
+}
@@ -0,0 +1,15 @@
+//! SQLite plugin commands.
+
+mod execute;
+mod migrate;
+mod query;
+mod transaction;
+
+#[doc(hidden)]
+pub use execute::Execute;
+#[doc(hidden)]
+pub use migrate::Migrate;
+#[doc(hidden)]
+pub use query::Query;
+#[doc(hidden)]
+pub use transaction::Transaction;
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use deskulpt_plugin::serde_json::{Map, Value};
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+
+use crate::database::{self, StatementPayload};
+use crate::SqlitePlugin;
+
+pub struct Query;
+
+impl PluginCommand for Query {
+    type Plugin = SqlitePlugin;
+
+    fn name(&self) -> &str {
+        "query"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: StatementPayload,
+    ) -> Result<Vec<Map<String, Value>>> {
+        let conn = database::open(engine, &id)?;
+        let mut stmt = database::prepare(&conn, &input)?;
+        let columns = stmt
+            .column_names()
+            .into_iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        // Each row is an object mapping column names to values, so columns
+        // with duplicate names should be aliased in the query
+        let mut rows = stmt.raw_query();
+        let mut output = vec![];
+        while let Some(row) = rows.next()? {
+            let mut object = Map::new();
+            for (index, column) in columns.iter().enumerate() {
+                object.insert(column.clone(), database::to_json_value(row.get_ref(index)?));
+            }
+            output.push(object);
+        }
+        Ok(output)
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::{Context, Result};
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Deserialize;
+
+use crate::database::{self, ExecuteOutput, StatementPayload};
+use crate::SqlitePlugin;
+
+pub struct Transaction;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInputPayload {
+    statements: Vec<StatementPayload>,
+}
+
+impl PluginCommand for Transaction {
+    type Plugin = SqlitePlugin;
+
+    fn name(&self) -> &str {
+        "transaction"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        _plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: TransactionInputPayload,
+    ) -> Result<Vec<ExecuteOutput>> {
+        let mut conn = database::open(engine, &id)?;
+
+        // The transaction is rolled back when dropped without being committed,
+        // i.e., if any of the statements fails
+        let tx = conn.transaction()?;
+        let output = input
+            .statements
+            .iter()
+            .enumerate()
+            .map(|(index, statement)| {
+                database::execute(&tx, statement)
+                    .with_context(|| format!("Failed to execute statement {index}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        tx.commit()?;
+        Ok(output)
+    }
+}
@@ -0,0 +1,251 @@
+//! Access to the databases of the widgets.
+
+use std::fs::create_dir_all;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use deskulpt_plugin::serde_json::{Map, Number, Value};
+use deskulpt_plugin::EngineInterface;
+use rusqlite::hooks::{AuthAction, AuthContext, Authorization};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{ffi, Connection, Statement};
+use serde::{Deserialize, Serialize};
+
+/// How long to wait for a locked database before failing.
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Open a connection to the database of a widget.
+///
+/// The database file is created if it does not exist.
+pub fn open(engine: &EngineInterface, id: &str) -> Result<Connection> {
+    let dir = engine.widget_storage_dir(id)?;
+    create_dir_all(&dir)?;
+    let conn = Connection::open(dir.join("database.sqlite3"))?;
+    // Commands of the same widget may run concurrently on separate connections,
+    // so writers wait for each other instead of failing immediately
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    conn.authorizer(Some(authorize));
+    conn.pragma_update(None, "foreign_keys", true)?;
+    Ok(conn)
+}
+
+/// The authorizer of the database connections.
+///
+/// This denies attaching any database other than temporary and in-memory
+/// ones. Internally, `VACUUM` attaches a temporary database while `VACUUM
+/// INTO` attaches the output file, so only the former is allowed.
+fn authorize(ctx: AuthContext<'_>) -> Authorization {
+    match ctx.action {
+        AuthAction::Attach { filename } if filename.is_empty() || filename == ":memory:" => {
+            Authorization::Allow
+        },
+        AuthAction::Attach { .. } => Authorization::Deny,
+        // The filename is not a literal, e.g., bound as a parameter
+        AuthAction::Unknown {
+            code: ffi::SQLITE_ATTACH,
+            ..
+        } => Authorization::Deny,
+        _ => Authorization::Allow,
+    }
+}
+
+/// Parameters of an SQL statement.
+///
+/// Positional parameters are bound to `?` and `?NNN` in order. Named
+/// parameters are bound to `:name`, `@name` or `$name`, where the prefix can
+/// be omitted from the keys. Booleans are bound as integers, and arrays and
+/// objects are bound as JSON text.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Params {
+    Positional(Vec<Value>),
+    Named(Map<String, Value>),
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Self::Positional(vec![])
+    }
+}
+
+/// An SQL statement with its parameters.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatementPayload {
+    pub sql: String,
+    #[serde(default)]
+    pub params: Params,
+}
+
+/// The result of executing an SQL statement.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecuteOutput {
+    /// Number of rows inserted, updated or deleted.
+    rows_affected: usize,
+    /// Row ID of the most recent successful insert into a rowid table.
+    last_insert_rowid: i64,
+}
+
+/// Prepare an SQL statement and bind its parameters.
+pub fn prepare<'conn>(
+    conn: &'conn Connection,
+    payload: &StatementPayload,
+) -> Result<Statement<'conn>> {
+    let mut stmt = conn.prepare(&payload.sql)?;
+    let expected = stmt.parameter_count();
+    match &payload.params {
+        Params::Positional(values) => {
+            if values.len() != expected {
+                bail!("Expected {expected} parameters, got {}", values.len());
+            }
+            for (index, value) in values.iter().enumerate() {
+                stmt.raw_bind_parameter(index + 1, to_sql_value(value))?;
+            }
+        },
+        Params::Named(values) => {
+            if values.len() != expected {
+                bail!("Expected {expected} parameters, got {}", values.len());
+            }
+            for (name, value) in values {
+                let index = if name.starts_with([':', '@', '$']) {
+                    stmt.parameter_index(name)?
+                } else {
+                    [':', '@', '$']
+                        .into_iter()
+                        .map(|prefix| stmt.parameter_index(&format!("{prefix}{name}")))
+                        .find_map(|index| index.transpose())
+                        .transpose()?
+                };
+                let Some(index) = index else {
+                    bail!("Unknown parameter: {name}");
+                };
+                stmt.raw_bind_parameter(index, to_sql_value(value))?;
+            }
+        },
+    }
+    Ok(stmt)
+}
+
+/// Execute an SQL statement that does not return rows.
+pub fn execute(conn: &Connection, payload: &StatementPayload) -> Result<ExecuteOutput> {
+    let rows_affected = prepare(conn, payload)?.raw_execute()?;
+    Ok(ExecuteOutput {
+        rows_affected,
+        last_insert_rowid: conn.last_insert_rowid(),
+    })
+}
+
+/// Convert a JSON value to an SQLite value.
+fn to_sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(value) => SqlValue::Integer(i64::from(*value)),
+        Value::Number(value) => match value.as_i64() {
+            Some(value) => SqlValue::Integer(value),
+            None => SqlValue::Real(value.as_f64().unwrap_or(f64::NAN)),
+        },
+        Value::String(value) => SqlValue::Text(value.clone()),
+        Value::Array(_) | Value::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+/// Convert an SQLite value to a JSON value.
+///
+/// Non-finite real numbers become `null`, and blobs become arrays of bytes.
+pub fn to_json_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(value) => Value::Number(value.into()),
+        ValueRef::Real(value) => Number::from_f64(value).map_or(Value::Null, Value::Number),
+        ValueRef::Text(value) => Value::String(String::from_utf8_lossy(value).into_owned()),
+        ValueRef::Blob(value) => Value::Array(value.iter().map(|&byte| byte.into()).collect()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deskulpt_plugin::serde_json::json;
+
+    use super::*;
+
+    fn connect() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.authorizer(Some(authorize));
+        conn.execute_batch("CREATE TABLE t (a, b)").unwrap();
+        conn
+    }
+
+    fn statement(sql: &str, params: Value) -> StatementPayload {
+        deskulpt_plugin::serde_json::from_value(json!({ "sql": sql, "params": params })).unwrap()
+    }
+
+    fn select_all(conn: &Connection) -> Vec<(SqlValue, SqlValue)> {
+        let mut stmt = conn.prepare("SELECT a, b FROM t ORDER BY rowid").unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap()
+            .collect::<rusqlite::Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_bind_positional_params() {
+        let conn = connect();
+        let sql = "INSERT INTO t VALUES (?, ?)";
+        execute(&conn, &statement(sql, json!([1, "x"]))).unwrap();
+        execute(&conn, &statement(sql, json!([true, [1, 2]]))).unwrap();
+        execute(&conn, &statement(sql, json!([1.5, null]))).unwrap();
+        assert_eq!(
+            select_all(&conn),
+            [
+                (SqlValue::Integer(1), SqlValue::Text("x".to_string())),
+                (SqlValue::Integer(1), SqlValue::Text("[1,2]".to_string())),
+                (SqlValue::Real(1.5), SqlValue::Null),
+            ]
+        );
+
+        assert!(execute(&conn, &statement(sql, json!([1]))).is_err());
+        assert!(execute(&conn, &statement(sql, json!([1, 2, 3]))).is_err());
+    }
+
+    #[test]
+    fn test_bind_named_params() {
+        let conn = connect();
+        let sql = "INSERT INTO t VALUES (:a, @b)";
+        execute(&conn, &statement(sql, json!({ "a": 1, "b": 2 }))).unwrap();
+        execute(&conn, &statement(sql, json!({ ":a": 3, "@b": 4 }))).unwrap();
+        assert_eq!(
+            select_all(&conn),
+            [
+                (SqlValue::Integer(1), SqlValue::Integer(2)),
+                (SqlValue::Integer(3), SqlValue::Integer(4)),
+            ]
+        );
+
+        assert!(execute(&conn, &statement(sql, json!({ "a": 1 }))).is_err());
+        assert!(execute(&conn, &statement(sql, json!({ "a": 1, "c": 2 }))).is_err());
+        assert!(execute(&conn, &statement(sql, json!({ "a": 1, "$b": 2 }))).is_err());
+    }
+
+    #[test]
+    fn test_deny_attach() {
+        let conn = connect();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("other.sqlite3");
+        let path = path.to_string_lossy();
+
+        assert!(conn
+            .execute_batch(&format!("ATTACH '{path}' AS other"))
+            .is_err());
+        assert!(conn.execute("ATTACH ? AS other", [path.as_ref()]).is_err());
+        assert!(conn
+            .execute_batch(&format!("VACUUM INTO '{path}'"))
+            .is_err());
+        assert!(!std::path::Path::new(path.as_ref()).exists());
+
+        conn.execute_batch("ATTACH ':memory:' AS other").unwrap();
+        conn.execute_batch("ATTACH '' AS temporary").unwrap();
+        conn.execute_batch("DETACH other; DETACH temporary; VACUUM")
+            .unwrap();
+    }
+}
@@ -0,0 +1,116 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg",
+    html_favicon_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg"
+)]
+
+mod commands;
+mod database;
+
+use deskulpt_plugin::{register_commands, Plugin};
+
+/// The SQLite plugin.
+///
+/// Each widget has its own SQLite database, stored as a file in the
+/// [storage directory](deskulpt_plugin::EngineInterface::widget_storage_dir)
+/// of the widget. A new connection is opened for each command, so settings
+/// that are specific to a connection, e.g., most pragmas, do not persist
+/// across commands. Foreign key constraints are always enforced.
+///
+/// Widgets cannot access databases other than their own, so attaching
+/// database files, including via `VACUUM INTO`, is denied.
+pub struct SqlitePlugin;
+
+impl Plugin for SqlitePlugin {
+    register_commands![
+        commands::Execute,
+        commands::Migrate,
+        commands::Query,
+        commands::Transaction,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use anyhow::Result;
+    use deskulpt_plugin::serde_json::{json, Value};
+
+    use super::*;
+
+    /// Call a command with the storage directory of the widget under `dir`.
+    fn call(dir: &Path, command: &str, payload: Value) -> Result<Value> {
+        let storage_dir = dir.to_path_buf();
+        deskulpt_plugin::call_plugin(
+            |_| unreachable!(),
+            move |id| Ok(storage_dir.join(id)),
+            |_| Ok(Value::Null),
+            |_, _| Ok(()),
+            &SqlitePlugin,
+            command,
+            "widget".to_string(),
+            Some(payload),
+        )
+    }
+
+    #[test]
+    fn test_transaction_rollback() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        call(
+            dir,
+            "execute",
+            json!({ "sql": "CREATE TABLE t (a UNIQUE)" }),
+        )
+        .unwrap();
+
+        let insert = |a: i64| json!({ "sql": "INSERT INTO t VALUES (?)", "params": [a] });
+        let output = call(
+            dir,
+            "transaction",
+            json!({ "statements": [insert(1), insert(2)] }),
+        );
+        assert_eq!(output.unwrap().as_array().unwrap().len(), 2);
+
+        // The second statement violates the constraint, so the first one is
+        // rolled back as well
+        let output = call(
+            dir,
+            "transaction",
+            json!({ "statements": [insert(3), insert(1)] }),
+        );
+        assert!(output.is_err());
+
+        let rows = call(dir, "query", json!({ "sql": "SELECT a FROM t ORDER BY a" })).unwrap();
+        assert_eq!(rows, json!([{ "a": 1 }, { "a": 2 }]));
+    }
+
+    #[test]
+    fn test_migrate_versions() {
+        let dir = tempfile::tempdir().unwrap();
+        let dir = dir.path();
+        let migrations = [
+            "CREATE TABLE t (a)",
+            "ALTER TABLE t ADD COLUMN b",
+            "INSERT INTO t VALUES (1, 2); INSERT INTO missing VALUES (3)",
+        ];
+
+        let output = call(dir, "migrate", json!({ "migrations": migrations[..1] })).unwrap();
+        assert_eq!(output, json!({ "from": 0, "to": 1 }));
+        let output = call(dir, "migrate", json!({ "migrations": migrations[..2] })).unwrap();
+        assert_eq!(output, json!({ "from": 1, "to": 2 }));
+        let output = call(dir, "migrate", json!({ "migrations": migrations[..2] })).unwrap();
+        assert_eq!(output, json!({ "from": 2, "to": 2 }));
+
+        // A failed migration is rolled back and leaves the previous version
+        assert!(call(dir, "migrate", json!({ "migrations": migrations })).is_err());
+        let rows = call(dir, "query", json!({ "sql": "SELECT * FROM t" })).unwrap();
+        assert_eq!(rows, json!([]));
+        let rows = call(dir, "query", json!({ "sql": "PRAGMA user_version" })).unwrap();
+        assert_eq!(rows, json!([{ "user_version": 2 }]));
+
+        // Migrations cannot be removed
+        assert!(call(dir, "migrate", json!({ "migrations": migrations[..1] })).is_err());
+    }
+}
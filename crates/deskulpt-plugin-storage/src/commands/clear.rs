@@ -0,0 +1,25 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+
+use crate::StoragePlugin;
+
+pub struct Clear;
+
+impl PluginCommand for Clear {
+    type Plugin = StoragePlugin;
+
+    fn name(&self) -> &str {
+        "clear"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: (),
+    ) -> Result<()> {
+        plugin.modify(engine, &id, |store| store.clear())
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::Result;
+use deskulpt_plugin::serde_json::Value;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Deserialize;
+
+use crate::StoragePlugin;
+
+pub struct CompareAndSet;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareAndSetInputPayload {
+    key: String,
+    /// The value that the key is expected to have, where `null` expects the
+    /// key to not exist.
+    #[serde(default)]
+    expected: Value,
+    /// The value to set if the expectation holds, where `null` deletes the
+    /// key.
+    #[serde(default)]
+    value: Value,
+}
+
+impl PluginCommand for CompareAndSet {
+    type Plugin = StoragePlugin;
+
+    fn name(&self) -> &str {
+        "compare_and_set"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: CompareAndSetInputPayload,
+    ) -> Result<bool> {
+        plugin.modify(engine, &id, |store| {
+            let current = store.get(&input.key).unwrap_or(&Value::Null);
+            if *current != input.expected {
+                return false;
+            }
+            if input.value.is_null() {
+                store.remove(&input.key);
+            } else {
+                store.insert(input.key, input.value);
+            }
+            true
+        })
+    }
+}
@@ -0,0 +1,32 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Deserialize;
+
+use crate::StoragePlugin;
+
+pub struct Delete;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteInputPayload {
+    key: String,
+}
+
+impl PluginCommand for Delete {
+    type Plugin = StoragePlugin;
+
+    fn name(&self) -> &str {
+        "delete"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: DeleteInputPayload,
+    ) -> Result<bool> {
+        plugin.modify(engine, &id, |store| store.remove(&input.key).is_some())
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use deskulpt_plugin::serde_json::Value;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Deserialize;
+
+use crate::StoragePlugin;
+
+pub struct Get;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetInputPayload {
+    key: String,
+}
+
+impl PluginCommand for Get {
+    type Plugin = StoragePlugin;
+
+    fn name(&self) -> &str {
+        "get"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: GetInputPayload,
+    ) -> Result<Value> {
+        plugin.read(engine, &id, |store| {
+            store.get(&input.key).cloned().unwrap_or_default()
+        })
+    }
+}
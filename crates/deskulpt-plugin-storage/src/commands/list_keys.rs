@@ -0,0 +1,25 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+
+use crate::StoragePlugin;
+
+pub struct ListKeys;
+
+impl PluginCommand for ListKeys {
+    type Plugin = StoragePlugin;
+
+    fn name(&self) -> &str {
+        "list_keys"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: (),
+    ) -> Result<Vec<String>> {
+        plugin.read(engine, &id, |store| store.keys().cloned().collect())
+    }
+}
@@ -0,0 +1,21 @@
+//! Key-value storage plugin commands.
+
+mod clear;
+mod compare_and_set;
+mod delete;
+mod get;
+mod list_keys;
+mod set;
+
+#[doc(hidden)]
+pub use clear::Clear;
+#[doc(hidden)]
+pub use compare_and_set::CompareAndSet;
+#[doc(hidden)]
+pub use delete::Delete;
+#[doc(hidden)]
+pub use get::Get;
+#[doc(hidden)]
+pub use list_keys::ListKeys;
+#[doc(hidden)]
+pub use set::Set;
@@ -0,0 +1,42 @@
+use anyhow::Result;
+use deskulpt_plugin::serde_json::Value;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Deserialize;
+
+use crate::StoragePlugin;
+
+pub struct Set;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetInputPayload {
+    key: String,
+    /// Setting `null` deletes the key.
+    #[serde(default)]
+    value: Value,
+}
+
+impl PluginCommand for Set {
+    type Plugin = StoragePlugin;
+
+    fn name(&self) -> &str {
+        "set"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: SetInputPayload,
+    ) -> Result<()> {
+        plugin.modify(engine, &id, |store| {
+            if input.value.is_null() {
+                store.remove(&input.key);
+            } else {
+                store.insert(input.key, input.value);
+            }
+        })
+    }
+}
@@ -0,0 +1,242 @@
+#![doc = include_str!("../README.md")]
+#![doc(
+    html_logo_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg",
+    html_favicon_url = "https://github.com/deskulpt-apps/Deskulpt/raw/main/packages/deskulpt/public/deskulpt.svg"
+)]
+
+mod commands;
+mod store;
+
+use std::sync::Mutex;
+
+use anyhow::Result;
+use deskulpt_plugin::{register_commands, EngineInterface, Plugin};
+use store::Store;
+
+/// The key-value storage plugin.
+///
+/// Each widget has its own key-value store, persisted as a JSON file in the
+/// [storage directory](deskulpt_plugin::EngineInterface::widget_storage_dir)
+/// of the widget. Keys are strings and values are arbitrary JSON values, where
+/// `null` is equivalent to the absence of a key. Each store is limited to 1 MiB
+/// when serialized.
+#[derive(Default)]
+pub struct StoragePlugin {
+    /// The lock held while accessing any store.
+    ///
+    /// Each command loads the store, modifies it, and writes it back while
+    /// holding this lock, so that commands, e.g., compare-and-set, are atomic.
+    lock: Mutex<()>,
+}
+
+impl StoragePlugin {
+    /// Read the key-value store of a widget.
+    fn read<T>(
+        &self,
+        engine: &EngineInterface,
+        id: &str,
+        f: impl FnOnce(&Store) -> T,
+    ) -> Result<T> {
+        let _guard = self.lock.lock().unwrap();
+        let store = store::load(&store::path(engine, id)?)?;
+        Ok(f(&store))
+    }
+
+    /// Modify the key-value store of a widget.
+    ///
+    /// The store is written back only if it is changed by the closure.
+    fn modify<T>(
+        &self,
+        engine: &EngineInterface,
+        id: &str,
+        f: impl FnOnce(&mut Store) -> T,
+    ) -> Result<T> {
+        let _guard = self.lock.lock().unwrap();
+        let path = store::path(engine, id)?;
+        let mut store = store::load(&path)?;
+        let original = store.clone();
+        let output = f(&mut store);
+        if store != original {
+            store::save(&path, &store)?;
+        }
+        Ok(output)
+    }
+}
+
+impl Plugin for StoragePlugin {
+    register_commands![
+        commands::Clear,
+        commands::CompareAndSet,
+        commands::Delete,
+        commands::Get,
+        commands::ListKeys,
+        commands::Set,
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use deskulpt_plugin::serde_json::{self, json, Value};
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::store::STORAGE_QUOTA;
+
+    /// A widget with its storage directory in a temporary directory.
+    struct Widget {
+        dir: TempDir,
+        plugin: StoragePlugin,
+    }
+
+    impl Widget {
+        fn new() -> Self {
+            Self {
+                dir: TempDir::new().unwrap(),
+                plugin: StoragePlugin::default(),
+            }
+        }
+
+        fn call(&self, command: &str, payload: Value) -> Result<Value> {
+            let storage_dir = self.dir.path().to_path_buf();
+            deskulpt_plugin::call_plugin(
+                |_| unreachable!(),
+                move |_| Ok(storage_dir.clone()),
+                |_| Ok(Value::Null),
+                |_, _| Ok(()),
+                &self.plugin,
+                command,
+                "widget".to_string(),
+                Some(payload),
+            )
+        }
+
+        fn path(&self) -> PathBuf {
+            self.dir.path().join("storage.json")
+        }
+
+        fn stored(&self) -> Value {
+            serde_json::from_slice(&std::fs::read(self.path()).unwrap()).unwrap()
+        }
+    }
+
+    fn has_tmp_files(dir: &Path) -> bool {
+        std::fs::read_dir(dir).unwrap().any(|entry| {
+            entry
+                .unwrap()
+                .path()
+                .extension()
+                .is_some_and(|ext| ext == "tmp")
+        })
+    }
+
+    #[test]
+    fn test_set_and_delete() {
+        let widget = Widget::new();
+        widget
+            .call("set", json!({ "key": "a", "value": 1 }))
+            .unwrap();
+        widget
+            .call("set", json!({ "key": "b", "value": [true] }))
+            .unwrap();
+        assert_eq!(
+            widget.call("get", json!({ "key": "b" })).unwrap(),
+            json!([true])
+        );
+        assert_eq!(
+            widget.call("get", json!({ "key": "c" })).unwrap(),
+            Value::Null
+        );
+        assert_eq!(
+            widget.call("list_keys", json!(null)).unwrap(),
+            json!(["a", "b"])
+        );
+
+        // The file is replaced through a temporary file that is renamed
+        assert_eq!(widget.stored(), json!({ "a": 1, "b": [true] }));
+        assert!(!has_tmp_files(widget.dir.path()));
+
+        // Setting null deletes the key
+        widget
+            .call("set", json!({ "key": "b", "value": null }))
+            .unwrap();
+        assert_eq!(widget.stored(), json!({ "a": 1 }));
+        assert_eq!(widget.call("delete", json!({ "key": "b" })).unwrap(), false);
+
+        // Deleting the last key removes the file
+        assert_eq!(widget.call("delete", json!({ "key": "a" })).unwrap(), true);
+        assert!(!widget.path().exists());
+        assert_eq!(widget.call("list_keys", json!(null)).unwrap(), json!([]));
+    }
+
+    #[test]
+    fn test_clear() {
+        let widget = Widget::new();
+        widget
+            .call("set", json!({ "key": "a", "value": 1 }))
+            .unwrap();
+        widget.call("clear", json!(null)).unwrap();
+        assert!(!widget.path().exists());
+        assert!(!has_tmp_files(widget.dir.path()));
+    }
+
+    #[test]
+    fn test_compare_and_set() {
+        let widget = Widget::new();
+        let cas = |expected: Value, value: Value| {
+            widget
+                .call(
+                    "compare_and_set",
+                    json!({ "key": "a", "expected": expected, "value": value }),
+                )
+                .unwrap()
+        };
+
+        // A missing key is expected with null
+        assert_eq!(cas(json!(1), json!(2)), false);
+        assert!(!widget.path().exists());
+        assert_eq!(cas(Value::Null, json!(1)), true);
+        assert_eq!(widget.stored(), json!({ "a": 1 }));
+
+        assert_eq!(cas(Value::Null, json!(2)), false);
+        assert_eq!(cas(json!(2), json!(3)), false);
+        assert_eq!(widget.stored(), json!({ "a": 1 }));
+        assert_eq!(cas(json!(1), json!({ "b": 2 })), true);
+        assert_eq!(widget.stored(), json!({ "a": { "b": 2 } }));
+
+        // Setting null deletes the key
+        assert_eq!(cas(json!({ "b": 2 }), Value::Null), true);
+        assert!(!widget.path().exists());
+    }
+
+    #[test]
+    fn test_quota() {
+        let widget = Widget::new();
+        widget
+            .call("set", json!({ "key": "a", "value": 1 }))
+            .unwrap();
+
+        // The key and the JSON syntax count towards the quota as well
+        let value = "x".repeat(STORAGE_QUOTA);
+        let error = widget
+            .call("set", json!({ "key": "b", "value": value }))
+            .unwrap_err();
+        assert!(error.to_string().contains("quota exceeded"), "{error:?}");
+        assert_eq!(widget.stored(), json!({ "a": 1 }));
+        assert!(!has_tmp_files(widget.dir.path()));
+
+        // The quota is on the serialized size of the whole store
+        let value = "x".repeat(STORAGE_QUOTA - r#"{"a":1,"b":""}"#.len());
+        widget
+            .call("set", json!({ "key": "b", "value": value }))
+            .unwrap();
+        assert_eq!(
+            std::fs::metadata(widget.path()).unwrap().len(),
+            STORAGE_QUOTA as u64
+        );
+        assert!(widget
+            .call("set", json!({ "key": "c", "value": 1 }))
+            .is_err());
+    }
+}
@@ -0,0 +1,69 @@
+//! Persistence of the key-value stores.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use deskulpt_plugin::serde_json::{self, Value};
+use deskulpt_plugin::EngineInterface;
+
+/// The maximum size of a key-value store in bytes when serialized.
+pub const STORAGE_QUOTA: usize = 1024 * 1024;
+
+/// A key-value store of a widget.
+pub type Store = BTreeMap<String, Value>;
+
+/// Get the path to the file of the key-value store of a widget.
+pub fn path(engine: &EngineInterface, id: &str) -> Result<PathBuf> {
+    Ok(engine.widget_storage_dir(id)?.join("storage.json"))
+}
+
+/// Load a key-value store from its file.
+///
+/// A missing file is treated as an empty store.
+pub fn load(path: &Path) -> Result<Store> {
+    let content = match fs::read(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Store::new()),
+        Err(e) => return Err(e).context("Failed to read the storage file"),
+    };
+    serde_json::from_slice(&content).context("Failed to parse the storage file")
+}
+
+/// Save a key-value store to its file.
+///
+/// The store is written to a temporary file first and then renamed, so that
+/// the file is never left partially written. An empty store removes the file
+/// instead.
+///
+/// ### Errors
+///
+/// - The serialized store exceeds the [`STORAGE_QUOTA`].
+/// - Error writing the storage file.
+pub fn save(path: &Path, store: &Store) -> Result<()> {
+    if store.is_empty() {
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                return Err(e).context("Failed to remove the storage file");
+            },
+            _ => return Ok(()),
+        }
+    }
+
+    let content = serde_json::to_vec(store)?;
+    if content.len() > STORAGE_QUOTA {
+        bail!(
+            "Storage quota exceeded: {} bytes needed but only {STORAGE_QUOTA} bytes are allowed",
+            content.len()
+        );
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, content).context("Failed to write the storage file")?;
+    fs::rename(&tmp_path, path).context("Failed to write the storage file")?;
+    Ok(())
+}
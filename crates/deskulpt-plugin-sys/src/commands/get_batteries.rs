@@ -0,0 +1,83 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Serialize;
+use starship_battery::units::ratio::percent;
+use starship_battery::units::time::second;
+use starship_battery::{Battery, Error, Manager, State};
+
+use crate::SysPlugin;
+
+pub struct GetBatteries;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryInfo {
+    vendor: Option<String>,
+    model: Option<String>,
+    /// Charge in percent.
+    charge: f32,
+    /// One of "charging", "discharging", "full", "empty" or "unknown".
+    state: &'static str,
+    /// Estimated seconds until fully charged, if charging.
+    time_to_full: Option<f32>,
+    /// Estimated seconds until empty, if discharging.
+    time_to_empty: Option<f32>,
+    /// Health in percent, i.e., the current full capacity relative to the
+    /// design capacity.
+    health: f32,
+    cycle_count: Option<u32>,
+}
+
+impl From<Battery> for BatteryInfo {
+    fn from(battery: Battery) -> Self {
+        let state = match battery.state() {
+            State::Charging => "charging",
+            State::Discharging => "discharging",
+            State::Full => "full",
+            State::Empty => "empty",
+            _ => "unknown",
+        };
+        Self {
+            vendor: battery.vendor().map(ToString::to_string),
+            model: battery.model().map(ToString::to_string),
+            charge: battery.state_of_charge().get::<percent>(),
+            state,
+            time_to_full: battery.time_to_full().map(|time| time.get::<second>()),
+            time_to_empty: battery.time_to_empty().map(|time| time.get::<second>()),
+            health: battery.state_of_health().get::<percent>(),
+            cycle_count: battery.cycle_count(),
+        }
+    }
+}
+
+/// List the batteries, skipping those that cannot be read.
+fn list_batteries() -> Result<Vec<BatteryInfo>, Error> {
+    let manager = Manager::new()?;
+    let batteries = manager
+        .batteries()?
+        .filter_map(|battery| battery.ok())
+        .map(BatteryInfo::from)
+        .collect();
+    Ok(batteries)
+}
+
+impl PluginCommand for GetBatteries {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_batteries"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        _plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: (),
+    ) -> Result<Vec<BatteryInfo>> {
+        // Machines without batteries, or where battery information cannot be
+        // accessed, e.g., in containers, report no batteries instead of errors
+        Ok(list_batteries().unwrap_or_default())
+    }
+}
@@ -0,0 +1,157 @@
+use std::thread;
+use std::time::Instant;
+
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+use sysinfo::{CpuRefreshKind, LoadAvg, System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+use crate::fields::Fields;
+use crate::{SysPlugin, SystemState};
+
+pub struct GetCpu;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CpuField {
+    /// CPU usage in percent, overall and per CPU.
+    ///
+    /// Usage is measured since the previous refresh. Refreshes closer than
+    /// the minimum update interval are skipped so that usage is never measured
+    /// over too short a period, and the first call takes a short extra sample
+    /// to measure against.
+    Usage,
+    /// CPU frequency in MHz.
+    Frequency,
+    /// CPU vendor ID and brand.
+    Brand,
+    /// System load average over 1, 5 and 15 minutes.
+    ///
+    /// This is not available on Windows, where it is always zero.
+    LoadAverage,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GetCpuInputPayload {
+    fields: Fields<CpuField>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuInfo {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) usage: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) frequency: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) vendor_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) brand: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadAverage {
+    one: f64,
+    five: f64,
+    fifteen: f64,
+}
+
+impl From<LoadAvg> for LoadAverage {
+    fn from(load_average: LoadAvg) -> Self {
+        Self {
+            one: load_average.one,
+            five: load_average.five,
+            fifteen: load_average.fifteen,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetCpuOutputPayload {
+    pub(super) cpu_count: usize,
+    physical_core_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    global_usage: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    load_average: Option<LoadAverage>,
+    pub(super) cpus: Vec<CpuInfo>,
+}
+
+/// Collect CPU information, refreshing only what the selected fields require.
+pub(super) fn cpu(plugin: &SysPlugin, fields: &Fields<CpuField>) -> GetCpuOutputPayload {
+    let mut state = plugin.system.lock().unwrap();
+    let SystemState {
+        system: sys,
+        cpu_usage_refreshed: refreshed,
+        ..
+    } = &mut *state;
+
+    let mut refresh_kind = CpuRefreshKind::nothing();
+    if fields.has(CpuField::Usage) {
+        let now = Instant::now();
+        match *refreshed {
+            None => {
+                sys.refresh_cpu_list(CpuRefreshKind::nothing().with_cpu_usage());
+                thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+                refresh_kind = refresh_kind.with_cpu_usage();
+                *refreshed = Some(Instant::now());
+            },
+            Some(last) if now.duration_since(last) >= MINIMUM_CPU_UPDATE_INTERVAL => {
+                refresh_kind = refresh_kind.with_cpu_usage();
+                *refreshed = Some(now);
+            },
+            _ => {},
+        }
+    }
+    if fields.has(CpuField::Frequency) {
+        refresh_kind = refresh_kind.with_frequency();
+    }
+    if sys.cpus().is_empty() {
+        sys.refresh_cpu_list(refresh_kind);
+    } else {
+        sys.refresh_cpu_specifics(refresh_kind);
+    }
+
+    let cpus = sys
+        .cpus()
+        .iter()
+        .map(|cpu| CpuInfo {
+            name: cpu.name().to_string(),
+            usage: fields.then(CpuField::Usage, || cpu.cpu_usage()),
+            frequency: fields.then(CpuField::Frequency, || cpu.frequency()),
+            vendor_id: fields.then(CpuField::Brand, || cpu.vendor_id().to_string()),
+            brand: fields.then(CpuField::Brand, || cpu.brand().to_string()),
+        })
+        .collect();
+
+    GetCpuOutputPayload {
+        cpu_count: sys.cpus().len(),
+        physical_core_count: sys.physical_core_count(),
+        global_usage: fields.then(CpuField::Usage, || sys.global_cpu_usage()),
+        load_average: fields.then(CpuField::LoadAverage, || System::load_average().into()),
+        cpus,
+    }
+}
+
+impl PluginCommand for GetCpu {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_cpu"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: Option<GetCpuInputPayload>,
+    ) -> Result<GetCpuOutputPayload> {
+        Ok(cpu(plugin, &input.unwrap_or_default().fields))
+    }
+}
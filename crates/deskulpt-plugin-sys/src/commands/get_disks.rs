@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+use sysinfo::DiskRefreshKind;
+
+use crate::fields::Fields;
+use crate::{DiskState, SysPlugin};
+
+pub struct GetDisks;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiskField {
+    /// Total and available space in bytes.
+    Space,
+    /// Disk kind, i.e., HDD, SSD or unknown.
+    Kind,
+    /// Total read and written bytes, and bytes read and written per second.
+    ///
+    /// Rates are measured over roughly the last second. The first call takes
+    /// a short extra sample to measure against.
+    Io,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GetDisksInputPayload {
+    fields: Fields<DiskField>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskInfo {
+    pub(super) name: String,
+    pub(super) mount_point: String,
+    file_system: String,
+    is_removable: bool,
+    is_read_only: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) total_space: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) available_space: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_read: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_written: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    read_per_second: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    written_per_second: Option<f64>,
+}
+
+/// Collect disk information, refreshing only what the selected fields require.
+pub(super) fn disks(plugin: &SysPlugin, fields: &Fields<DiskField>) -> Vec<DiskInfo> {
+    let mut state = plugin.disks.lock().unwrap();
+    let DiskState {
+        disks,
+        disk_history,
+    } = &mut *state;
+
+    let mut refresh_kind = DiskRefreshKind::nothing();
+    if fields.has(DiskField::Space) {
+        refresh_kind = refresh_kind.with_storage();
+    }
+    if fields.has(DiskField::Kind) {
+        refresh_kind = refresh_kind.with_kind();
+    }
+
+    // Disks are keyed by their mount points, since multiple disks may have
+    // the same name
+    let rates = if fields.has(DiskField::Io) {
+        refresh_kind = refresh_kind.with_io_usage();
+        disk_history.sample(|| {
+            disks.refresh_specifics(true, refresh_kind);
+            disks
+                .list()
+                .iter()
+                .map(|disk| {
+                    let usage = disk.usage();
+                    let totals = [usage.total_read_bytes, usage.total_written_bytes];
+                    (disk.mount_point().to_string_lossy().to_string(), totals)
+                })
+                .collect()
+        })
+    } else {
+        disks.refresh_specifics(true, refresh_kind);
+        HashMap::new()
+    };
+
+    disks
+        .list()
+        .iter()
+        .map(|disk| {
+            let mount_point = disk.mount_point().to_string_lossy().to_string();
+            let rates = rates.get(&mount_point);
+            DiskInfo {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point,
+                file_system: disk.file_system().to_string_lossy().to_string(),
+                is_removable: disk.is_removable(),
+                is_read_only: disk.is_read_only(),
+                total_space: fields.then(DiskField::Space, || disk.total_space()),
+                available_space: fields.then(DiskField::Space, || disk.available_space()),
+                kind: fields.then(DiskField::Kind, || disk.kind().to_string()),
+                total_read: fields.then(DiskField::Io, || disk.usage().total_read_bytes),
+                total_written: fields.then(DiskField::Io, || disk.usage().total_written_bytes),
+                read_per_second: rates.map(|rates| rates[0]),
+                written_per_second: rates.map(|rates| rates[1]),
+            }
+        })
+        .collect()
+}
+
+impl PluginCommand for GetDisks {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_disks"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: Option<GetDisksInputPayload>,
+    ) -> Result<Vec<DiskInfo>> {
+        Ok(disks(plugin, &input.unwrap_or_default().fields))
+    }
+}
@@ -0,0 +1,99 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+use sysinfo::MemoryRefreshKind;
+
+use crate::fields::Fields;
+use crate::SysPlugin;
+
+pub struct GetMemory;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MemoryField {
+    /// RAM usage in bytes.
+    Ram,
+    /// Swap usage in bytes.
+    Swap,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GetMemoryInputPayload {
+    fields: Fields<MemoryField>,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RamInfo {
+    pub(super) total: u64,
+    pub(super) used: u64,
+    free: u64,
+    available: u64,
+}
+
+#[derive(Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapInfo {
+    pub(super) total: u64,
+    pub(super) used: u64,
+    free: u64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetMemoryOutputPayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) ram: Option<RamInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) swap: Option<SwapInfo>,
+}
+
+/// Collect memory information, refreshing only what the selected fields
+/// require.
+pub(super) fn memory(plugin: &SysPlugin, fields: &Fields<MemoryField>) -> GetMemoryOutputPayload {
+    let mut state = plugin.system.lock().unwrap();
+    let sys = &mut state.system;
+
+    let mut refresh_kind = MemoryRefreshKind::nothing();
+    if fields.has(MemoryField::Ram) {
+        refresh_kind = refresh_kind.with_ram();
+    }
+    if fields.has(MemoryField::Swap) {
+        refresh_kind = refresh_kind.with_swap();
+    }
+    sys.refresh_memory_specifics(refresh_kind);
+
+    GetMemoryOutputPayload {
+        ram: fields.then(MemoryField::Ram, || RamInfo {
+            total: sys.total_memory(),
+            used: sys.used_memory(),
+            free: sys.free_memory(),
+            available: sys.available_memory(),
+        }),
+        swap: fields.then(MemoryField::Swap, || SwapInfo {
+            total: sys.total_swap(),
+            used: sys.used_swap(),
+            free: sys.free_swap(),
+        }),
+    }
+}
+
+impl PluginCommand for GetMemory {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_memory"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: Option<GetMemoryInputPayload>,
+    ) -> Result<GetMemoryOutputPayload> {
+        Ok(memory(plugin, &input.unwrap_or_default().fields))
+    }
+}
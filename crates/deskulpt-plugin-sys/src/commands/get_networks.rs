@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+
+use crate::fields::Fields;
+use crate::{NetworkState, SysPlugin};
+
+pub struct GetNetworks;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NetworkField {
+    /// Total received and transmitted bytes.
+    Traffic,
+    /// Received and transmitted bytes per second.
+    ///
+    /// Rates are measured over roughly the last second. The first call takes
+    /// a short extra sample to measure against.
+    Throughput,
+    /// Total received and transmitted packets.
+    Packets,
+    /// Total errors on receiving and transmitting.
+    Errors,
+    /// MAC address and IP networks.
+    Addresses,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GetNetworksInputPayload {
+    fields: Fields<NetworkField>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkInfo {
+    pub(super) interface_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) total_received: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) total_transmitted: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    received_per_second: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transmitted_per_second: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_packets_received: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_packets_transmitted: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_errors_on_received: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_errors_on_transmitted: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mac_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ip_networks: Option<Vec<String>>,
+}
+
+/// Collect network interface information, refreshing only what the selected
+/// fields require.
+pub(super) fn networks(plugin: &SysPlugin, fields: &Fields<NetworkField>) -> Vec<NetworkInfo> {
+    let mut state = plugin.networks.lock().unwrap();
+    let NetworkState {
+        networks,
+        network_history,
+    } = &mut *state;
+
+    let rates = if fields.has(NetworkField::Throughput) {
+        network_history.sample(|| {
+            networks.refresh(true);
+            networks
+                .list()
+                .iter()
+                .map(|(name, data)| {
+                    let totals = [data.total_received(), data.total_transmitted()];
+                    (name.to_string(), totals)
+                })
+                .collect()
+        })
+    } else {
+        networks.refresh(true);
+        HashMap::new()
+    };
+
+    let mut networks = networks
+        .list()
+        .iter()
+        .map(|(interface_name, data)| NetworkInfo {
+            interface_name: interface_name.to_string(),
+            total_received: fields.then(NetworkField::Traffic, || data.total_received()),
+            total_transmitted: fields.then(NetworkField::Traffic, || data.total_transmitted()),
+            received_per_second: rates.get(interface_name).map(|rates| rates[0]),
+            transmitted_per_second: rates.get(interface_name).map(|rates| rates[1]),
+            total_packets_received: fields
+                .then(NetworkField::Packets, || data.total_packets_received()),
+            total_packets_transmitted: fields
+                .then(NetworkField::Packets, || data.total_packets_transmitted()),
+            total_errors_on_received: fields
+                .then(NetworkField::Errors, || data.total_errors_on_received()),
+            total_errors_on_transmitted: fields
+                .then(NetworkField::Errors, || data.total_errors_on_transmitted()),
+            mac_address: fields.then(NetworkField::Addresses, || data.mac_address().to_string()),
+            ip_networks: fields.then(NetworkField::Addresses, || {
+                data.ip_networks()
+                    .iter()
+                    .map(|network| format!("{}/{}", network.addr, network.prefix))
+                    .collect()
+            }),
+        })
+        .collect::<Vec<_>>();
+    networks.sort_by(|a, b| a.interface_name.cmp(&b.interface_name));
+    networks
+}
+
+impl PluginCommand for GetNetworks {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_networks"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: Option<GetNetworksInputPayload>,
+    ) -> Result<Vec<NetworkInfo>> {
+        Ok(networks(plugin, &input.unwrap_or_default().fields))
+    }
+}
@@ -0,0 +1,85 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+use crate::fields::Fields;
+use crate::SysPlugin;
+
+pub struct GetOsInfo;
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OsInfoField {
+    /// Operating system name, versions and distribution ID.
+    Os,
+    /// Kernel version.
+    Kernel,
+    /// Host name.
+    HostName,
+    /// CPU architecture.
+    Arch,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GetOsInfoInputPayload {
+    fields: Fields<OsInfoField>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOsInfoOutputPayload {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) system_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) os_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    long_os_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distribution_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) kernel_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(super) host_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cpu_arch: Option<String>,
+}
+
+/// Collect operating system information with the selected fields.
+pub(super) fn os_info(fields: &Fields<OsInfoField>) -> GetOsInfoOutputPayload {
+    GetOsInfoOutputPayload {
+        system_name: fields.then(OsInfoField::Os, System::name).flatten(),
+        os_version: fields.then(OsInfoField::Os, System::os_version).flatten(),
+        long_os_version: fields
+            .then(OsInfoField::Os, System::long_os_version)
+            .flatten(),
+        distribution_id: fields.then(OsInfoField::Os, System::distribution_id),
+        kernel_version: fields
+            .then(OsInfoField::Kernel, System::kernel_version)
+            .flatten(),
+        host_name: fields
+            .then(OsInfoField::HostName, System::host_name)
+            .flatten(),
+        cpu_arch: fields.then(OsInfoField::Arch, System::cpu_arch),
+    }
+}
+
+impl PluginCommand for GetOsInfo {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_os_info"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        _plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: Option<GetOsInfoInputPayload>,
+    ) -> Result<GetOsInfoOutputPayload> {
+        Ok(os_info(&input.unwrap_or_default().fields))
+    }
+}
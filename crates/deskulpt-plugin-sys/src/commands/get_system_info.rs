@@ -1,10 +1,21 @@
-use anyhow::{Ok, Result};
+use anyhow::Result;
 use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
 use serde::Serialize;
-use sysinfo::{Disks, Networks, System};
 
+use super::get_cpu::{self, CpuField};
+use super::get_disks::{self, DiskField};
+use super::get_memory::{self, MemoryField};
+use super::get_networks::{self, NetworkField};
+use super::get_os_info::{self, OsInfoField};
 use crate::SysPlugin;
 
+/// Deprecated; use the granular commands instead.
+///
+/// This is kept for compatibility with widgets written against the original
+/// all-in-one command. It collects the information in the same way as
+/// [`GetCpu`](super::GetCpu), [`GetMemory`](super::GetMemory),
+/// [`GetDisks`](super::GetDisks), [`GetNetworks`](super::GetNetworks) and
+/// [`GetOsInfo`](super::GetOsInfo), refreshing only what its output requires.
 pub struct GetSystemInfo;
 
 #[derive(Serialize)]
@@ -65,52 +76,61 @@ impl PluginCommand for GetSystemInfo {
         _engine: &EngineInterface,
         input: (),
     ) -> Result<GetSystemInfoOutputPayload> {
-        let mut sys = plugin.0.lock().unwrap();
-        sys.refresh_all();
+        let cpu_fields = [CpuField::Usage, CpuField::Frequency, CpuField::Brand];
+        let cpu = get_cpu::cpu(plugin, &cpu_fields.into_iter().collect());
+        let memory_fields = [MemoryField::Ram, MemoryField::Swap];
+        let memory = get_memory::memory(plugin, &memory_fields.into_iter().collect());
+        let disks = get_disks::disks(plugin, &[DiskField::Space].into_iter().collect());
+        let networks =
+            get_networks::networks(plugin, &[NetworkField::Traffic].into_iter().collect());
+        let os_info_fields = [OsInfoField::Os, OsInfoField::Kernel, OsInfoField::HostName];
+        let os_info = get_os_info::os_info(&os_info_fields.into_iter().collect());
 
-        let disks_info: Vec<DiskInfo> = Disks::new_with_refreshed_list()
-            .iter()
+        // The selected fields are always present, so the defaults are never
+        // actually used
+        let ram = memory.ram.unwrap_or_default();
+        let swap = memory.swap.unwrap_or_default();
+        let cpu_info = cpu
+            .cpus
+            .into_iter()
+            .map(|cpu| CpuInfo {
+                vendor_id: cpu.vendor_id.unwrap_or_default(),
+                brand: cpu.brand.unwrap_or_default(),
+                frequency: cpu.frequency.unwrap_or_default(),
+                total_cpu_usage: cpu.usage.unwrap_or_default(),
+            })
+            .collect();
+        let disks = disks
+            .into_iter()
             .map(|disk| DiskInfo {
-                name: disk.name().to_string_lossy().to_string(),
-                available_space: disk.available_space(),
-                total_space: disk.total_space(),
-                mount_point: disk.mount_point().to_string_lossy().to_string(),
+                name: disk.name,
+                available_space: disk.available_space.unwrap_or_default(),
+                total_space: disk.total_space.unwrap_or_default(),
+                mount_point: disk.mount_point,
             })
             .collect();
-
-        let networks_info: Vec<NetworkInfo> = Networks::new_with_refreshed_list()
-            .iter()
-            .map(|(interface_name, data)| NetworkInfo {
-                interface_name: interface_name.to_string(),
-                total_received: data.total_received(),
-                total_transmitted: data.total_transmitted(),
+        let networks = networks
+            .into_iter()
+            .map(|network| NetworkInfo {
+                interface_name: network.interface_name,
+                total_received: network.total_received.unwrap_or_default(),
+                total_transmitted: network.total_transmitted.unwrap_or_default(),
             })
             .collect();
 
-        let cpu_count = sys.cpus().len();
-        let mut cpu_info = Vec::with_capacity(cpu_count);
-        for cpu in sys.cpus() {
-            cpu_info.push(CpuInfo {
-                vendor_id: cpu.vendor_id().to_string(),
-                brand: cpu.brand().to_string(),
-                frequency: cpu.frequency(),
-                total_cpu_usage: cpu.cpu_usage(),
-            });
-        }
-
         Ok(GetSystemInfoOutputPayload {
-            total_memory: sys.total_memory(),
-            used_memory: sys.used_memory(),
-            total_swap: sys.total_swap(),
-            used_swap: sys.used_swap(),
-            system_name: System::name(),
-            kernel_version: System::kernel_version(),
-            os_version: System::os_version(),
-            host_name: System::host_name(),
-            cpu_count,
+            total_swap: swap.total,
+            used_swap: swap.used,
+            system_name: os_info.system_name,
+            kernel_version: os_info.kernel_version,
+            os_version: os_info.os_version,
+            host_name: os_info.host_name,
+            cpu_count: cpu.cpu_count,
             cpu_info,
-            disks: disks_info,
-            networks: networks_info,
+            disks,
+            networks,
+            total_memory: ram.total,
+            used_memory: ram.used,
         })
     }
 }
@@ -0,0 +1,54 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Serialize;
+
+use crate::SysPlugin;
+
+pub struct GetTemperatures;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ComponentTemperature {
+    label: String,
+    /// Temperature in degrees Celsius.
+    temperature: Option<f32>,
+    /// Highest temperature seen in degrees Celsius.
+    max: Option<f32>,
+    /// Critical temperature in degrees Celsius.
+    critical: Option<f32>,
+}
+
+impl PluginCommand for GetTemperatures {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_temperatures"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: (),
+    ) -> Result<Vec<ComponentTemperature>> {
+        // Machines without temperature sensors, e.g., virtual machines and
+        // containers, simply have no components
+        let mut components = plugin.components.lock().unwrap();
+        components.refresh(true);
+
+        let mut temperatures = components
+            .list()
+            .iter()
+            .map(|component| ComponentTemperature {
+                label: component.label().to_string(),
+                temperature: component.temperature(),
+                max: component.max(),
+                critical: component.critical(),
+            })
+            .collect::<Vec<_>>();
+        temperatures.sort_by(|a, b| a.label.cmp(&b.label));
+        Ok(temperatures)
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use deskulpt_plugin::{dispatch, EngineInterface, PluginCommand};
+use serde::Serialize;
+use sysinfo::System;
+
+use super::get_cpu::LoadAverage;
+use crate::SysPlugin;
+
+pub struct GetUptime;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetUptimeOutputPayload {
+    /// System uptime in seconds.
+    uptime: u64,
+    /// Boot time in seconds since the Unix epoch.
+    boot_time: u64,
+    /// System load average, which is always zero on Windows.
+    load_average: LoadAverage,
+}
+
+impl PluginCommand for GetUptime {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "get_uptime"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        _id: String,
+        _plugin: &Self::Plugin,
+        _engine: &EngineInterface,
+        input: (),
+    ) -> Result<GetUptimeOutputPayload> {
+        Ok(GetUptimeOutputPayload {
+            uptime: System::uptime(),
+            boot_time: System::boot_time(),
+            load_average: System::load_average().into(),
+        })
+    }
+}
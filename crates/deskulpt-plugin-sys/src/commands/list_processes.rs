@@ -0,0 +1,302 @@
+use std::cmp::Ordering;
+use std::thread;
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use deskulpt_plugin::{dispatch, serde_json, EngineInterface, PluginCommand};
+use serde::{Deserialize, Serialize};
+use sysinfo::{
+    Process, ProcessRefreshKind, ProcessesToUpdate, UpdateKind, MINIMUM_CPU_UPDATE_INTERVAL,
+};
+
+use crate::{SysPlugin, SystemState};
+
+pub struct ListProcesses;
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProcessSortKey {
+    /// Sort by CPU usage.
+    #[default]
+    Cpu,
+    /// Sort by memory usage.
+    Memory,
+    /// Sort by process name.
+    Name,
+    /// Sort by process ID.
+    Pid,
+    /// Sort by start time.
+    StartTime,
+}
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// The `permissions.sys` section of the widget configuration.
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct SysPermissions {
+    process_command_lines: bool,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListProcessesInputPayload {
+    /// The key to sort the processes by, defaulting to CPU usage.
+    sort_by: ProcessSortKey,
+    /// The sort order, defaulting to descending.
+    order: SortOrder,
+    /// The maximum number of processes to return, after sorting.
+    limit: Option<usize>,
+    /// Only return processes whose names contain this string, ignoring case.
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessInfo {
+    pid: u32,
+    name: String,
+    /// The command line, only if the widget declares
+    /// `permissions.sys.processCommandLines`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cmd: Option<Vec<String>>,
+    /// CPU usage in percent, which can exceed 100 on multi-core systems.
+    cpu_usage: f32,
+    /// Memory usage in bytes.
+    memory: u64,
+    /// Start time in seconds since the Unix epoch.
+    start_time: u64,
+    /// The name of the user that owns the process, if known.
+    user: Option<String>,
+}
+
+impl ProcessInfo {
+    fn compare(&self, other: &Self, key: ProcessSortKey) -> Ordering {
+        match key {
+            ProcessSortKey::Cpu => self.cpu_usage.total_cmp(&other.cpu_usage),
+            ProcessSortKey::Memory => self.memory.cmp(&other.memory),
+            ProcessSortKey::Name => self.name.to_lowercase().cmp(&other.name.to_lowercase()),
+            ProcessSortKey::Pid => self.pid.cmp(&other.pid),
+            ProcessSortKey::StartTime => self.start_time.cmp(&other.start_time),
+        }
+    }
+}
+
+impl PluginCommand for ListProcesses {
+    type Plugin = SysPlugin;
+
+    fn name(&self) -> &str {
+        "list_processes"
+    }
+
+    #[dispatch]
+    fn run(
+        &self,
+        id: String,
+        plugin: &Self::Plugin,
+        engine: &EngineInterface,
+        input: Option<ListProcessesInputPayload>,
+    ) -> Result<Vec<ProcessInfo>> {
+        let input = input.unwrap_or_default();
+        let permissions =
+            serde_json::from_value::<Option<SysPermissions>>(engine.widget_permissions(&id)?)
+                .context("Invalid permissions.sys in deskulpt.conf.json")?
+                .unwrap_or_default();
+        let mut state = plugin.system.lock().unwrap();
+        let SystemState {
+            system: sys,
+            processes_refreshed: refreshed,
+            users,
+            ..
+        } = &mut *state;
+
+        // CPU usage of processes is measured since the previous refresh, so
+        // refreshes are skipped if too close, and the first call takes a short
+        // extra sample to measure against
+        let refresh_kind = ProcessRefreshKind::nothing()
+            .with_cpu()
+            .with_memory()
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_user(UpdateKind::OnlyIfNotSet);
+        let now = Instant::now();
+        match *refreshed {
+            None => {
+                sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+                thread::sleep(MINIMUM_CPU_UPDATE_INTERVAL);
+                sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+                *refreshed = Some(Instant::now());
+            },
+            Some(last) if now.duration_since(last) >= MINIMUM_CPU_UPDATE_INTERVAL => {
+                sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+                *refreshed = Some(now);
+            },
+            _ => {},
+        }
+
+        let name_filter = input.name.map(|name| name.to_lowercase());
+        let matched = sys
+            .processes()
+            .values()
+            .filter(|process| match &name_filter {
+                Some(filter) => process
+                    .name()
+                    .to_string_lossy()
+                    .to_lowercase()
+                    .contains(filter),
+                None => true,
+            })
+            .collect::<Vec<_>>();
+
+        // Refresh the user list only if there are unknown users, e.g., on the
+        // first call or after a new user is created
+        if matched.iter().any(|process| {
+            process
+                .user_id()
+                .is_some_and(|uid| users.get_user_by_id(uid).is_none())
+        }) {
+            users.refresh();
+        }
+
+        let to_info = |process: &Process| ProcessInfo {
+            pid: process.pid().as_u32(),
+            name: process.name().to_string_lossy().to_string(),
+            cmd: permissions.process_command_lines.then(|| {
+                process
+                    .cmd()
+                    .iter()
+                    .map(|arg| arg.to_string_lossy().to_string())
+                    .collect()
+            }),
+            cpu_usage: process.cpu_usage(),
+            memory: process.memory(),
+            start_time: process.start_time(),
+            user: process
+                .user_id()
+                .and_then(|uid| users.get_user_by_id(uid))
+                .map(|user| user.name().to_string()),
+        };
+        let mut processes = matched.into_iter().map(to_info).collect::<Vec<_>>();
+
+        processes.sort_by(|a, b| match input.order {
+            SortOrder::Asc => a.compare(b, input.sort_by),
+            SortOrder::Desc => b.compare(a, input.sort_by),
+        });
+        if let Some(limit) = input.limit {
+            processes.truncate(limit);
+        }
+        Ok(processes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use deskulpt_plugin::serde_json::{json, Value};
+
+    use super::*;
+
+    /// List processes as a widget with the given sys permissions.
+    fn list(plugin: &SysPlugin, permissions: Value, payload: Value) -> Vec<Value> {
+        let output = deskulpt_plugin::call_plugin(
+            |_| unreachable!(),
+            |_| unreachable!(),
+            move |_| Ok(permissions.clone()),
+            |_, _| Ok(()),
+            plugin,
+            "list_processes",
+            "widget".to_string(),
+            Some(payload),
+        )
+        .unwrap();
+        serde_json::from_value(output).unwrap()
+    }
+
+    fn pids(processes: &[Value]) -> Vec<u64> {
+        processes
+            .iter()
+            .map(|process| process["pid"].as_u64().unwrap())
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_filter() {
+        use std::process::Command;
+
+        // Processes are given a unique name by copying `sleep`, so that they
+        // can be told apart from other processes on the system
+        let dir = tempfile::tempdir().unwrap();
+        let program = dir.path().join("DeskulptSleep");
+        let sleep = std::env::split_paths(&std::env::var_os("PATH").unwrap())
+            .map(|dir| dir.join("sleep"))
+            .find(|path| path.is_file())
+            .unwrap();
+        std::fs::copy(sleep, &program).unwrap();
+        let mut children = (0..3)
+            .map(|_| Command::new(&program).arg("30").spawn().unwrap())
+            .collect::<Vec<_>>();
+        let mut expected = children
+            .iter()
+            .map(|child| child.id() as u64)
+            .collect::<Vec<_>>();
+        expected.sort();
+
+        let plugin = SysPlugin::default();
+
+        // Names are matched ignoring case, and command lines are omitted
+        // unless declared
+        let payload = json!({ "name": "deskulptSLEEP", "sortBy": "pid", "order": "asc" });
+        let processes = list(&plugin, Value::Null, payload);
+        assert_eq!(pids(&processes), expected);
+        assert!(processes.iter().all(|process| process.get("cmd").is_none()));
+
+        // The order defaults to descending, and the limit applies after sorting
+        let payload = json!({ "name": "deskulptsleep", "sortBy": "pid", "limit": 2 });
+        let permissions = json!({ "processCommandLines": true });
+        let processes = list(&plugin, permissions, payload);
+        assert_eq!(pids(&processes), [expected[2], expected[1]]);
+        for process in processes {
+            assert_eq!(process["cmd"], json!([program, "30"]));
+        }
+
+        children.iter_mut().for_each(|child| {
+            let _ = child.kill();
+            let _ = child.wait();
+        });
+    }
+
+    #[test]
+    fn test_sort() {
+        let plugin = SysPlugin::default();
+        for (key, field) in [
+            ("cpu", "cpuUsage"),
+            ("memory", "memory"),
+            ("name", "name"),
+            ("pid", "pid"),
+            ("startTime", "startTime"),
+        ] {
+            for order in ["asc", "desc"] {
+                let payload = json!({ "sortBy": key, "order": order });
+                let processes = list(&plugin, Value::Null, payload);
+                assert!(!processes.is_empty());
+                let sorted = processes.windows(2).all(|pair| {
+                    let (a, b) = (&pair[0][field], &pair[1][field]);
+                    let ordering = match a.as_str().zip(b.as_str()) {
+                        Some((a, b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+                        None => a.as_f64().unwrap().total_cmp(&b.as_f64().unwrap()),
+                    };
+                    match order {
+                        "asc" => ordering.is_le(),
+                        _ => ordering.is_ge(),
+                    }
+                });
+                assert!(sorted, "{key} {order}");
+            }
+        }
+    }
+}
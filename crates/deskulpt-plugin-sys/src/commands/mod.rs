@@ -1,6 +1,33 @@
 //! System information plugin commands.
 
+mod get_batteries;
+mod get_cpu;
+mod get_disks;
+mod get_memory;
+mod get_networks;
+mod get_os_info;
 mod get_system_info;
+mod get_temperatures;
+mod get_uptime;
+mod list_processes;
 
+#[doc(hidden)]
+pub use get_batteries::GetBatteries;
+#[doc(hidden)]
+pub use get_cpu::GetCpu;
+#[doc(hidden)]
+pub use get_disks::GetDisks;
+#[doc(hidden)]
+pub use get_memory::GetMemory;
+#[doc(hidden)]
+pub use get_networks::GetNetworks;
+#[doc(hidden)]
+pub use get_os_info::GetOsInfo;
 #[doc(hidden)]
 pub use get_system_info::GetSystemInfo;
+#[doc(hidden)]
+pub use get_temperatures::GetTemperatures;
+#[doc(hidden)]
+pub use get_uptime::GetUptime;
+#[doc(hidden)]
+pub use list_processes::ListProcesses;
@@ -0,0 +1,41 @@
+//! Field selection for the command input payloads.
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+
+/// A selection of fields to compute.
+///
+/// This is deserialized from an optional array of field names. If omitted or
+/// `null`, all fields are selected. Fields that are not selected are neither
+/// refreshed nor included in the output.
+#[derive(Deserialize)]
+#[serde(transparent)]
+pub struct Fields<F: Ord>(Option<BTreeSet<F>>);
+
+impl<F: Ord> Default for Fields<F> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<F: Ord> Fields<F> {
+    /// Whether a field is selected.
+    pub fn has(&self, field: F) -> bool {
+        match &self.0 {
+            Some(fields) => fields.contains(&field),
+            None => true,
+        }
+    }
+
+    /// Return a value only if a field is selected.
+    pub fn then<T>(&self, field: F, f: impl FnOnce() -> T) -> Option<T> {
+        self.has(field).then(f)
+    }
+}
+
+impl<F: Ord> FromIterator<F> for Fields<F> {
+    fn from_iter<I: IntoIterator<Item = F>>(iter: I) -> Self {
+        Self(Some(iter.into_iter().collect()))
+    }
+}
@@ -5,23 +5,77 @@
 )]
 
 mod commands;
+mod fields;
+mod sampling;
 
 use std::sync::Mutex;
+use std::time::Instant;
 
 use deskulpt_plugin::{register_commands, Plugin};
-use sysinfo::System;
+use sampling::RateHistory;
+use sysinfo::{Components, Disks, Networks, System, Users};
 
-/// The system information plugin (🚧 TODO 🚧).
+/// The system information plugin.
 ///
-/// ### 🚧 TODO 🚧
-///
-/// Redesign the exposed APIs, splitting into different groups of information to
-/// avoid having to retrieve all information even when only a subset is needed.
-///
-/// Also note that the `#[derive(Default)]` may be removed if unneeded.
+/// System information is split into groups, each retrieved by a separate
+/// command that only refreshes the relevant subsystem. The plugin keeps the
+/// states of the subsystems across calls, along with sampling histories, which
+/// are required for computing usages and rates that are measured between two
+/// refreshes, e.g., CPU usage and network throughput.
+#[derive(Default)]
+pub struct SysPlugin {
+    /// The system state for CPU, memory and process information.
+    system: Mutex<SystemState>,
+    /// The state for disk information.
+    disks: Mutex<DiskState>,
+    /// The list of hardware components with temperature sensors.
+    components: Mutex<Components>,
+    /// The state for network information.
+    networks: Mutex<NetworkState>,
+}
+
+/// The system state for CPU, memory and process information.
+#[derive(Default)]
+struct SystemState {
+    /// The system state.
+    system: System,
+    /// The last time CPU usage was refreshed.
+    cpu_usage_refreshed: Option<Instant>,
+    /// The last time processes were refreshed.
+    processes_refreshed: Option<Instant>,
+    /// The list of users for resolving process owners.
+    users: Users,
+}
+
+/// The state for disk information.
+#[derive(Default)]
+struct DiskState {
+    /// The list of disks.
+    disks: Disks,
+    /// The history of total read and written bytes of disks.
+    disk_history: RateHistory<2>,
+}
+
+/// The state for network information.
 #[derive(Default)]
-pub struct SysPlugin(pub Mutex<System>);
+struct NetworkState {
+    /// The list of network interfaces.
+    networks: Networks,
+    /// The history of total received and transmitted bytes of interfaces.
+    network_history: RateHistory<2>,
+}
 
 impl Plugin for SysPlugin {
-    register_commands![commands::GetSystemInfo];
+    register_commands![
+        commands::GetBatteries,
+        commands::GetCpu,
+        commands::GetDisks,
+        commands::GetMemory,
+        commands::GetNetworks,
+        commands::GetOsInfo,
+        commands::GetSystemInfo,
+        commands::GetTemperatures,
+        commands::GetUptime,
+        commands::ListProcesses,
+    ];
 }
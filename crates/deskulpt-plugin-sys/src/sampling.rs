@@ -0,0 +1,146 @@
+//! Sampling history for rate-based metrics.
+
+use std::collections::{HashMap, VecDeque};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The window over which rates are measured.
+///
+/// Rates are computed against the newest sample that is at least this old, so
+/// that frequent calls, e.g., from multiple widgets, do not produce rates over
+/// tiny intervals that are dominated by noise.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// The interval between the two samples taken on the first call.
+const INITIAL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A history of samples of cumulative counters.
+///
+/// Each sample maps a key, e.g., a network interface name, to `N` cumulative
+/// counters, e.g., total received and transmitted bytes. Only the samples
+/// needed for the current rate window are kept.
+pub struct RateHistory<const N: usize> {
+    samples: VecDeque<(Instant, HashMap<String, [u64; N]>)>,
+}
+
+impl<const N: usize> Default for RateHistory<N> {
+    fn default() -> Self {
+        Self {
+            samples: VecDeque::new(),
+        }
+    }
+}
+
+impl<const N: usize> RateHistory<N> {
+    /// Take a sample and compute per-second rates of the counters.
+    ///
+    /// `sample` is called to refresh and collect the counters. If there is no
+    /// previous sample, an extra sample is taken after a short interval so
+    /// that the first call also reports meaningful rates. Keys that are absent
+    /// from the baseline sample, e.g., newly attached devices, have no rates.
+    /// Counters that decrease, e.g., after a device reset, are treated as zero
+    /// change.
+    pub fn sample(
+        &mut self,
+        mut sample: impl FnMut() -> HashMap<String, [u64; N]>,
+    ) -> HashMap<String, [f64; N]> {
+        if self.samples.is_empty() {
+            self.sample_at(Instant::now(), sample());
+            thread::sleep(INITIAL_INTERVAL);
+        }
+        self.sample_at(Instant::now(), sample())
+            .expect("history is not empty")
+    }
+
+    /// Add a sample taken at `now` and compute per-second rates of the
+    /// counters against the baseline sample.
+    ///
+    /// This returns `None` if there is no previous sample to measure against.
+    fn sample_at(
+        &mut self,
+        now: Instant,
+        current: HashMap<String, [u64; N]>,
+    ) -> Option<HashMap<String, [f64; N]>> {
+        // Drop samples that are superseded by a newer sample that is still at
+        // least as old as the rate window; the front is then the baseline
+        while self
+            .samples
+            .get(1)
+            .is_some_and(|(time, _)| now.duration_since(*time) >= RATE_WINDOW)
+        {
+            self.samples.pop_front();
+        }
+
+        let rates = self.samples.front().map(|(time, baseline)| {
+            let elapsed = now.duration_since(*time).as_secs_f64();
+            current
+                .iter()
+                .filter_map(|(key, counters)| {
+                    let previous = baseline.get(key)?;
+                    let rates = std::array::from_fn(|i| {
+                        counters[i].saturating_sub(previous[i]) as f64 / elapsed
+                    });
+                    Some((key.clone(), rates))
+                })
+                .collect()
+        });
+
+        self.samples.push_back((now, current));
+        rates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a map from keys to values.
+    fn map<T: Copy, const N: usize>(entries: &[(&str, [T; N])]) -> HashMap<String, [T; N]> {
+        entries
+            .iter()
+            .map(|(key, values)| (key.to_string(), *values))
+            .collect()
+    }
+
+    #[test]
+    fn test_sample_at() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut history = RateHistory::default();
+
+        // There is nothing to measure against on the first sample
+        assert_eq!(history.sample_at(at(0), map(&[("a", [100, 100])])), None);
+
+        let rates = history.sample_at(at(500), map(&[("a", [200, 150])]));
+        assert_eq!(rates, Some(map(&[("a", [200.0, 100.0])])));
+
+        // The previous sample is still within the window, so the first one
+        // remains the baseline; keys absent from it have no rates, and
+        // decreased counters are treated as zero change
+        let rates = history.sample_at(at(1000), map(&[("a", [50, 250]), ("b", [100, 100])]));
+        assert_eq!(rates, Some(map(&[("a", [0.0, 150.0])])));
+    }
+
+    #[test]
+    fn test_sample_at_window() {
+        let start = Instant::now();
+        let at = |millis| start + Duration::from_millis(millis);
+        let mut history = RateHistory::default();
+
+        history.sample_at(at(0), map(&[("a", [0])]));
+        history.sample_at(at(500), map(&[("a", [0])]));
+        history.sample_at(at(1000), map(&[("a", [0])]));
+
+        // The baseline is the newest sample that is at least as old as the
+        // window, and older samples are dropped
+        let rates = history.sample_at(at(1500), map(&[("a", [100])]));
+        assert_eq!(rates, Some(map(&[("a", [100.0])])));
+        let times = history.samples.iter().map(|(time, _)| *time);
+        assert_eq!(times.collect::<Vec<_>>(), [at(500), at(1000), at(1500)]);
+
+        // Samples within the window are measured against the oldest sample
+        let rates = history.sample_at(at(1600), map(&[("a", [100])]));
+        assert_eq!(rates, Some(map(&[("a", [100.0 / 1.1])])));
+        assert_eq!(history.samples.len(), 4);
+    }
+}
@@ -1,6 +1,7 @@
 //! Interaction interface.
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::Result;
 
@@ -16,13 +17,28 @@ use anyhow::Result;
 pub struct EngineInterface {
     #[allow(clippy::type_complexity)]
     widget_dir_fn: Box<dyn Fn(&str) -> Result<PathBuf>>,
+    #[allow(clippy::type_complexity)]
+    widget_storage_dir_fn: Box<dyn Fn(&str) -> Result<PathBuf>>,
+    #[allow(clippy::type_complexity)]
+    widget_permissions_fn: Box<dyn Fn(&str) -> Result<serde_json::Value>>,
+    event_emitter: EventEmitter,
 }
 
 impl EngineInterface {
     /// Create a new engine interface instance.
-    pub(crate) fn new(widget_dir_fn: impl Fn(&str) -> Result<PathBuf> + 'static) -> Self {
+    pub(crate) fn new(
+        widget_dir_fn: impl Fn(&str) -> Result<PathBuf> + 'static,
+        widget_storage_dir_fn: impl Fn(&str) -> Result<PathBuf> + 'static,
+        widget_permissions_fn: impl Fn(&str) -> Result<serde_json::Value> + 'static,
+        emit_event_fn: impl Fn(&str, serde_json::Value) -> Result<()> + Send + Sync + 'static,
+    ) -> Self {
         Self {
             widget_dir_fn: Box::new(widget_dir_fn),
+            widget_storage_dir_fn: Box::new(widget_storage_dir_fn),
+            widget_permissions_fn: Box::new(widget_permissions_fn),
+            event_emitter: EventEmitter {
+                emit_event_fn: Arc::new(emit_event_fn),
+            },
         }
     }
 
@@ -36,4 +52,77 @@ impl EngineInterface {
     pub fn widget_dir(&self, id: &str) -> Result<PathBuf> {
         (self.widget_dir_fn)(id)
     }
+
+    /// Get the storage directory of a widget (🚧 TODO 🚧).
+    ///
+    /// This is a directory in the persistence directory of Deskulpt instead of
+    /// the widget directory, dedicated to the widget for persisting its data.
+    /// Plugins should store data of the widget under this directory, using
+    /// file names that do not collide with other plugins. The directory may
+    /// not exist yet. The Deskulpt core takes care of moving the directory
+    /// when the widget is renamed and removing it when the widget is removed.
+    ///
+    /// # 🚧 TODO 🚧
+    ///
+    /// This method is a temporary implementation. The final implementation
+    /// should use IPC to communicate with the Deskulpt core to get the widget
+    /// storage directory.
+    pub fn widget_storage_dir(&self, id: &str) -> Result<PathBuf> {
+        (self.widget_storage_dir_fn)(id)
+    }
+
+    /// Get the permissions that a widget requests from the plugin (🚧 TODO 🚧).
+    ///
+    /// This is the field of the `permissions` section in the configuration of
+    /// the widget named after the plugin, or `null` if not specified. It is up
+    /// to the plugin to interpret the permissions and enforce them.
+    ///
+    /// # 🚧 TODO 🚧
+    ///
+    /// This method is a temporary implementation. The final implementation
+    /// should use IPC to communicate with the Deskulpt core to get the widget
+    /// permissions.
+    pub fn widget_permissions(&self, id: &str) -> Result<serde_json::Value> {
+        (self.widget_permissions_fn)(id)
+    }
+
+    /// Get an emitter for sending events to the widgets (🚧 TODO 🚧).
+    ///
+    /// Unlike the engine interface itself, the emitter can be sent to and
+    /// kept by other threads, so that the plugin can keep sending events
+    /// after the command returns, e.g., output of a long-running task.
+    ///
+    /// # 🚧 TODO 🚧
+    ///
+    /// This method is a temporary implementation. The final implementation
+    /// should use IPC to communicate with the Deskulpt core to emit events.
+    pub fn event_emitter(&self) -> EventEmitter {
+        self.event_emitter.clone()
+    }
+}
+
+/// An emitter for sending events to the widgets (🚧 TODO 🚧).
+///
+/// This is obtained from [`EngineInterface::event_emitter`].
+///
+/// ### 🚧 TODO 🚧
+///
+/// This is a temporary implementation that directly takes the function for
+/// emitting events from the Deskulpt core. The final implementation should use
+/// IPC for communication instead.
+#[derive(Clone)]
+pub struct EventEmitter {
+    #[allow(clippy::type_complexity)]
+    emit_event_fn: Arc<dyn Fn(&str, serde_json::Value) -> Result<()> + Send + Sync>,
+}
+
+impl EventEmitter {
+    /// Emit an event to a widget.
+    ///
+    /// The event is delivered to the frontend together with the name of the
+    /// plugin and the ID of the widget. It is up to the widget to pick up the
+    /// events of its own and interpret the payload.
+    pub fn emit(&self, id: &str, payload: serde_json::Value) -> Result<()> {
+        (self.emit_event_fn)(id, payload)
+    }
 }
@@ -9,10 +9,11 @@ mod interface;
 
 use std::path::PathBuf;
 
+pub use anyhow;
 use anyhow::{bail, Result};
 pub use command::PluginCommand;
-pub use interface::EngineInterface;
-pub use {anyhow, serde_json};
+pub use interface::{EngineInterface, EventEmitter};
+pub use serde_json;
 
 /// The API for a Deskulpt plugin.
 pub trait Plugin {
@@ -40,14 +41,23 @@ pub trait Plugin {
 /// standalone process that can interact with the Deskulpt core through IPC. See
 /// [nushell](https://docs.rs/nu-plugin/0.101.0/nu_plugin/fn.serve_plugin.html)
 /// for reference.
+#[allow(clippy::too_many_arguments)]
 pub fn call_plugin<P: Plugin>(
     widget_dir_fn: impl Fn(&str) -> Result<PathBuf> + 'static,
+    widget_storage_dir_fn: impl Fn(&str) -> Result<PathBuf> + 'static,
+    widget_permissions_fn: impl Fn(&str) -> Result<serde_json::Value> + 'static,
+    emit_event_fn: impl Fn(&str, serde_json::Value) -> Result<()> + Send + Sync + 'static,
     plugin: &P,
     command: &str,
     id: String,
     payload: Option<serde_json::Value>,
 ) -> Result<serde_json::Value> {
-    let engine = EngineInterface::new(widget_dir_fn);
+    let engine = EngineInterface::new(
+        widget_dir_fn,
+        widget_storage_dir_fn,
+        widget_permissions_fn,
+        emit_event_fn,
+    );
 
     for plugin_command in plugin.commands() {
         if plugin_command.name() == command {
@@ -6,7 +6,8 @@
 
 use deskulpt_core::path::PathExt;
 use deskulpt_core::states::{
-    CanvasImodeStateExt, InitialRenderStateExt, SettingsStateExt, WidgetCatalogStateExt,
+    BundleReportsStateExt, CanvasImodeStateExt, InitialRenderStateExt, MetricSubscriptionsStateExt,
+    SettingsStateExt, WidgetCatalogStateExt,
 };
 use deskulpt_core::tray::TrayExt;
 use deskulpt_core::window::WindowExt;
@@ -26,7 +27,9 @@ pub fn run() {
             app.manage_settings();
             app.manage_initial_render();
             app.manage_widget_catalog();
+            app.manage_bundle_reports();
             app.manage_canvas_imode();
+            app.manage_metric_subscriptions();
 
             // Hide the application from the dock on macOS because skipping
             // taskbar is not applicable for macOS
@@ -1,14 +1,28 @@
 use anyhow::Result;
-use schemars::schema_for;
+use schemars::{schema_for, Schema};
 
-/// Entry point for the `cargo gen schema` command.
-pub fn run() -> Result<()> {
-    let schema = schema_for!(deskulpt_core::schema::Settings);
+/// Write a JSON schema to the public directory of the documentation.
+fn write_schema(schema: Schema, file_name: &str) -> Result<()> {
     let output = serde_json::to_string_pretty(&schema)?;
 
-    let path = deskulpt_workspace::docs_dir().join("src/public/settings-schema.json");
+    let path = deskulpt_workspace::docs_dir()
+        .join("src/public")
+        .join(file_name);
     std::fs::write(&path, output)?;
     println!("✅ Generated: {}", path.display());
 
     Ok(())
 }
+
+/// Entry point for the `cargo gen schema` command.
+pub fn run() -> Result<()> {
+    write_schema(
+        schema_for!(deskulpt_core::schema::Settings),
+        "settings-schema.json",
+    )?;
+    write_schema(
+        schema_for!(deskulpt_core::schema::DeskulptConf),
+        "deskulpt-conf-schema.json",
+    )?;
+    Ok(())
+}
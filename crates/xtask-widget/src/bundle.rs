@@ -0,0 +1,121 @@
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use deskulpt_common::outcome::Outcome;
+use deskulpt_core::headless::{WidgetBundlerBuilder, WidgetCatalog};
+
+/// Arguments of the `cargo widget bundle` command.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The widgets directory to discover widgets from.
+    dir: PathBuf,
+    /// Only bundle the widgets with these IDs [default: all widgets].
+    #[arg(long = "widget", value_name = "ID")]
+    ids: Vec<String>,
+    /// Shared package cache to resolve dependencies from.
+    #[arg(long, value_name = "DIR")]
+    packages_dir: Option<PathBuf>,
+    /// Write each bundle to `<OUT_DIR>/<ID>.js`.
+    #[arg(long, value_name = "DIR")]
+    out_dir: Option<PathBuf>,
+}
+
+/// Print an error message indented under a widget.
+fn print_error(message: &str) {
+    for line in message.lines() {
+        println!("    {line}");
+    }
+}
+
+/// Entry point for the `cargo widget bundle` command.
+pub async fn run(args: Args) -> Result<()> {
+    let dir = dunce::canonicalize(&args.dir)
+        .with_context(|| format!("Widgets directory not found: {}", args.dir.display()))?;
+    let catalog = WidgetCatalog::load(&dir)
+        .with_context(|| format!("Failed to load widget catalog from {}", dir.display()))?;
+
+    let WidgetCatalog {
+        mut widgets, dirs, ..
+    } = catalog;
+    if !args.ids.is_empty() {
+        let unknown = args
+            .ids
+            .iter()
+            .filter(|id| !widgets.contains_key(*id))
+            .collect::<Vec<_>>();
+        if !unknown.is_empty() {
+            bail!("Widgets not found in {}: {unknown:?}", dir.display());
+        }
+        widgets.retain(|id, _| args.ids.contains(id));
+    }
+
+    if widgets.is_empty() {
+        println!("⚠️ No widgets found in {}", dir.display());
+        return Ok(());
+    }
+
+    let total = widgets.len();
+    let mut failed = vec![];
+
+    for (id, config) in widgets {
+        let config = match config {
+            Outcome::Ok(config) => config,
+            Outcome::Err(e) => {
+                println!("❌ {id}: invalid configuration");
+                print_error(&e);
+                failed.push(id);
+                continue;
+            },
+        };
+
+        let start = Instant::now();
+        let mut builder = WidgetBundlerBuilder::new(dirs[&id].clone(), config.entry)
+            .dependencies(config.dependencies)
+            .config(config.build);
+        if let Some(packages_dir) = &args.packages_dir {
+            builder = builder.packages_dir(packages_dir.clone());
+        }
+
+        let result = match builder.build().context("Failed to build widget bundler") {
+            Ok(mut bundler) => bundler.bundle().await.context("Failed to bundle widget"),
+            Err(e) => Err(e),
+        };
+        let (code, report) = match result {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                println!("❌ {id}");
+                print_error(&format!("{e:?}"));
+                failed.push(id);
+                continue;
+            },
+        };
+
+        println!(
+            "✅ {id} ({} bytes, {} modules, {:.2?})",
+            report.size,
+            report.modules.len(),
+            start.elapsed(),
+        );
+
+        if let Some(out_dir) = &args.out_dir {
+            let path = out_dir.join(format!("{id}.js"));
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&path, code)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            println!("    -> {}", path.display());
+        }
+    }
+
+    if !failed.is_empty() {
+        bail!(
+            "{} of {total} widgets failed to bundle: {}",
+            failed.len(),
+            failed.join(", ")
+        );
+    }
+    println!("✅ Bundled {total} widgets");
+    Ok(())
+}
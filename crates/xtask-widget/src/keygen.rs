@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use ed25519_dalek::SigningKey;
+use rand_core::OsRng;
+
+/// Arguments of the `cargo widget keygen` command.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The name of the key.
+    ///
+    /// The secret key is written to `<NAME>.key` and the public key to
+    /// `<NAME>.pub`. The public key can be copied to the trust store of
+    /// Deskulpt to trust the packages signed with the secret key.
+    name: String,
+    /// The directory to write the keys to.
+    #[arg(long, value_name = "DIR", default_value = ".")]
+    out_dir: PathBuf,
+}
+
+/// Entry point for the `cargo widget keygen` command.
+pub fn run(args: Args) -> Result<()> {
+    let secret_path = args.out_dir.join(format!("{}.key", args.name));
+    let public_path = args.out_dir.join(format!("{}.pub", args.name));
+    if secret_path.exists() || public_path.exists() {
+        bail!(
+            "Key {:?} already exists in {}",
+            args.name,
+            args.out_dir.display()
+        );
+    }
+
+    let signing_key = SigningKey::generate(&mut OsRng);
+    std::fs::create_dir_all(&args.out_dir)?;
+    std::fs::write(&secret_path, hex::encode(signing_key.to_bytes()))
+        .with_context(|| format!("Failed to write {}", secret_path.display()))?;
+    std::fs::write(
+        &public_path,
+        hex::encode(signing_key.verifying_key().as_bytes()),
+    )
+    .with_context(|| format!("Failed to write {}", public_path.display()))?;
+
+    println!("✅ Generated key {:?}", args.name);
+    println!("    secret -> {}", secret_path.display());
+    println!("    public -> {}", public_path.display());
+    Ok(())
+}
+
+/// Read a secret key written by `cargo widget keygen`.
+pub fn read_signing_key(path: &Path) -> Result<SigningKey> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let mut bytes = [0; 32];
+    hex::decode_to_slice(content.trim(), &mut bytes)
+        .with_context(|| format!("Invalid secret key in {}", path.display()))?;
+    Ok(SigningKey::from_bytes(&bytes))
+}
@@ -0,0 +1,39 @@
+mod bundle;
+mod keygen;
+mod new;
+mod pack;
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Bundle widgets without starting Deskulpt.
+    Bundle(bundle::Args),
+    /// Pack a widget into a package that can be installed in Deskulpt.
+    Pack(pack::Args),
+    /// Generate a key pair for signing widget packages.
+    Keygen(keygen::Args),
+    /// Create a new widget from a built-in template.
+    New(new::Args),
+}
+
+/// [XTASK] Widget tooling for Deskulpt.
+#[derive(Debug, Parser)]
+#[command(version, about, author, bin_name = "cargo widget")]
+struct Args {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    match args.command {
+        Commands::Bundle(args) => bundle::run(args).await?,
+        Commands::Pack(args) => pack::run(args)?,
+        Commands::Keygen(args) => keygen::run(args)?,
+        Commands::New(args) => new::run(args)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use deskulpt_core::headless::{create_widget, WidgetCatalog, WidgetTemplate};
+
+/// Arguments of the `cargo widget new` command.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The widgets directory to create the widget in.
+    dir: PathBuf,
+    /// The display name of the widget.
+    ///
+    /// The widget directory is named after it, with a numeric suffix if the
+    /// name is already taken.
+    name: String,
+    /// The template to generate the widget from [possible values: minimal,
+    /// tsx, plugin].
+    #[arg(long, default_value = "minimal")]
+    template: WidgetTemplate,
+}
+
+/// Entry point for the `cargo widget new` command.
+pub fn run(args: Args) -> Result<()> {
+    let dir = dunce::canonicalize(&args.dir)
+        .with_context(|| format!("Widgets directory not found: {}", args.dir.display()))?;
+    let catalog = WidgetCatalog::load(&dir)
+        .with_context(|| format!("Failed to load widget catalog from {}", dir.display()))?;
+
+    let id = create_widget(&dir, &args.name, args.template, |dir_name| {
+        catalog.widgets.contains_key(dir_name)
+    })?;
+    println!("✅ {id} ({:?} template)", args.template);
+    println!("    -> {}", dir.join(&id).display());
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use deskulpt_core::headless::pack;
+
+use crate::keygen::read_signing_key;
+
+/// Arguments of the `cargo widget pack` command.
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The widget directory to pack.
+    dir: PathBuf,
+    /// The path of the package [default: `<ID>.zip` in the current directory].
+    #[arg(long, short, value_name = "FILE")]
+    out: Option<PathBuf>,
+    /// Sign the package with the secret key generated by `cargo widget keygen`.
+    #[arg(long, value_name = "FILE")]
+    key: Option<PathBuf>,
+}
+
+/// Entry point for the `cargo widget pack` command.
+pub fn run(args: Args) -> Result<()> {
+    let dir = dunce::canonicalize(&args.dir)
+        .with_context(|| format!("Widget directory not found: {}", args.dir.display()))?;
+
+    let signing_key = args.key.as_deref().map(read_signing_key).transpose()?;
+
+    let mut package = Cursor::new(vec![]);
+    let manifest = pack(&dir, &mut package, signing_key.as_ref())
+        .with_context(|| format!("Failed to pack widget at {}", dir.display()))?;
+    let out = args
+        .out
+        .unwrap_or_else(|| PathBuf::from(format!("{}.zip", manifest.id)));
+    std::fs::write(&out, package.into_inner())
+        .with_context(|| format!("Failed to write {}", out.display()))?;
+
+    println!(
+        "✅ {} ({} files, checksum {}, {})",
+        manifest.id,
+        manifest.files.len(),
+        manifest.checksum,
+        if manifest.signature.is_some() {
+            "signed"
+        } else {
+            "unsigned"
+        },
+    );
+    println!("    -> {}", out.display());
+    Ok(())
+}
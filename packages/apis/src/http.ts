@@ -0,0 +1,54 @@
+import { invoke } from "@tauri-apps/api/core";
+
+interface RequestInputPayload {
+  url: string;
+  /** Defaults to `GET`. */
+  method?: string;
+  /**
+   * The `Host` header cannot be set, since it must match the allowed URL.
+   */
+  headers?: Record<string, string>;
+  body?: string;
+  /**
+   * Timeout of the whole request in milliseconds, including reading the
+   * response body. Defaults to 30 seconds and can be at most 5 minutes.
+   */
+  timeout?: number;
+  /**
+   * Maximum size of the response body in bytes. Defaults to 10 MiB and can be
+   * at most 100 MiB.
+   */
+  maxResponseSize?: number;
+}
+
+interface RequestOutputPayload {
+  status: number;
+  statusText: string;
+  /**
+   * Header names are lowercase, and multiple values of the same header are
+   * joined with `, `.
+   */
+  headers: Record<string, string>;
+  /**
+   * The response body, decoded as UTF-8 with invalid sequences replaced.
+   */
+  body: string;
+}
+
+/**
+ * Send an HTTP request from the backend, not subject to CORS.
+ *
+ * The host of the URL must be declared in the `permissions.http` section of
+ * `deskulpt.conf.json`. Responses with error statuses resolve as well. Redirects
+ * are not followed; the redirect response is resolved instead.
+ */
+function request(id: string, payload: RequestInputPayload) {
+  return invoke<RequestOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "http",
+    command: "request",
+    id,
+    payload,
+  });
+}
+
+export { request };
@@ -1,4 +1,8 @@
 import * as fs from "./fs";
+import * as http from "./http";
+import * as shell from "./shell";
+import * as sqlite from "./sqlite";
+import * as storage from "./storage";
 import * as sys from "./sys";
 
-export default { fs, sys };
+export default { fs, http, shell, sqlite, storage, sys };
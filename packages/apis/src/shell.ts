@@ -0,0 +1,199 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+interface CommandOptions {
+  /**
+   * The command to run, exactly as declared in the `permissions.shell.commands`
+   * section of `deskulpt.conf.json`.
+   */
+  command: string;
+  args?: string[];
+  /**
+   * The working directory, relative to the widget directory if relative.
+   * Defaults to the widget directory. It must be inside the widget directory
+   * or any of the directories declared in `permissions.shell.dirs`.
+   */
+  cwd?: string;
+  /**
+   * Environment variables in addition to those inherited from Deskulpt. Each
+   * of them must be declared in the `permissions.shell.env` section of
+   * `deskulpt.conf.json`.
+   */
+  env?: Record<string, string>;
+}
+
+interface RunInputPayload extends CommandOptions {
+  /**
+   * Timeout in milliseconds, after which the process is killed. Defaults to
+   * 30 seconds and can be at most 10 minutes.
+   */
+  timeout?: number;
+  /**
+   * Maximum size of captured output in bytes, for each of standard output and
+   * standard error. Defaults to 1 MiB and can be at most 16 MiB.
+   */
+  maxOutputSize?: number;
+}
+
+interface RunOutputPayload {
+  /**
+   * The exit code, or `null` if the process is terminated by a signal,
+   * including when it is killed because it timed out.
+   */
+  code: number | null;
+  timedOut: boolean;
+  /**
+   * Standard output, decoded as UTF-8 with invalid sequences replaced.
+   */
+  stdout: string;
+  /**
+   * Standard error, decoded as UTF-8 with invalid sequences replaced.
+   */
+  stderr: string;
+  /**
+   * Whether standard output exceeds the maximum size and is cut off.
+   */
+  stdoutTruncated: boolean;
+  /**
+   * Whether standard error exceeds the maximum size and is cut off.
+   */
+  stderrTruncated: boolean;
+}
+
+interface SpawnInputPayload extends CommandOptions {
+  /**
+   * Timeout in milliseconds, after which the process is killed. The process
+   * may run indefinitely if not specified.
+   */
+  timeout?: number;
+}
+
+interface ExitInfo {
+  /**
+   * The exit code, or `null` if the process is terminated by a signal,
+   * including when it is killed.
+   */
+  code: number | null;
+  killed: boolean;
+  timedOut: boolean;
+}
+
+interface SpawnCallbacks {
+  /**
+   * Callback for each chunk of standard output, usually a line.
+   */
+  onStdout?: (data: string) => void;
+  /**
+   * Callback for each chunk of standard error, usually a line.
+   */
+  onStderr?: (data: string) => void;
+  /**
+   * Callback for when the process exits, after all of its output.
+   */
+  onExit?: (exit: ExitInfo) => void;
+}
+
+type ProcessEvent =
+  | { type: "stdout"; handle: number; data: string }
+  | { type: "stderr"; handle: number; data: string }
+  | ({ type: "exit"; handle: number } & ExitInfo);
+
+/**
+ * Run a command to completion and capture its output.
+ *
+ * The command must be declared in the `permissions.shell` section of
+ * `deskulpt.conf.json`. Non-zero exit codes and timeouts resolve as well.
+ */
+function run(id: string, payload: RunInputPayload) {
+  return invoke<RunOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "shell",
+    command: "run",
+    id,
+    payload,
+  });
+}
+
+/**
+ * Kill a process spawned in the background.
+ *
+ * Resolves to whether the process was found, i.e., it has not exited yet.
+ */
+function kill(id: string, payload: { handle: number }) {
+  return invoke<boolean>("plugin:deskulpt-core|call_plugin", {
+    plugin: "shell",
+    command: "kill",
+    id,
+    payload,
+  });
+}
+
+/**
+ * Spawn a command in the background and stream its output.
+ *
+ * The command must be declared in the `permissions.shell` section of
+ * `deskulpt.conf.json`. The returned promise resolves to the handle of the
+ * process and a function that kills it. Processes are also killed when the
+ * widget is re-rendered or removed. Each widget can have at most 16 processes
+ * in the background.
+ */
+async function spawn(
+  id: string,
+  payload: SpawnInputPayload,
+  callbacks: SpawnCallbacks = {},
+) {
+  const { onStdout, onStderr, onExit } = callbacks;
+  const deliver = (event: ProcessEvent) => {
+    switch (event.type) {
+      case "stdout":
+        onStdout?.(event.data);
+        break;
+      case "stderr":
+        onStderr?.(event.data);
+        break;
+      case "exit":
+        unlisten();
+        onExit?.({
+          code: event.code,
+          killed: event.killed,
+          timedOut: event.timedOut,
+        });
+        break;
+    }
+  };
+
+  // Listen before spawning so that no output is missed in between; events
+  // before the handle is known are buffered
+  let handle: number | undefined;
+  const pending: ProcessEvent[] = [];
+  const unlisten = await listen<{
+    plugin: string;
+    id: string;
+    payload: ProcessEvent;
+  }>("plugin", (event) => {
+    if (event.payload.plugin !== "shell" || event.payload.id !== id) {
+      return;
+    }
+    if (handle === undefined) {
+      pending.push(event.payload.payload);
+    } else if (event.payload.payload.handle === handle) {
+      deliver(event.payload.payload);
+    }
+  });
+
+  const output = await invoke<{ handle: number }>(
+    "plugin:deskulpt-core|call_plugin",
+    { plugin: "shell", command: "spawn", id, payload },
+  ).catch((error) => {
+    unlisten();
+    throw error;
+  });
+  handle = output.handle;
+  pending.filter((event) => event.handle === handle).forEach(deliver);
+
+  return {
+    handle,
+    kill: () => kill(id, { handle: output.handle }),
+  };
+}
+
+export { kill, run, spawn };
@@ -0,0 +1,87 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Parameters of an SQL statement.
+ *
+ * Positional parameters are bound to `?` and `?NNN` in order. Named parameters
+ * are bound to `:name`, `@name` or `$name`, where the prefix can be omitted
+ * from the keys. Booleans are bound as integers, and arrays and objects are
+ * bound as JSON text.
+ */
+type Params = unknown[] | Record<string, unknown>;
+
+interface Statement {
+  sql: string;
+  params?: Params;
+}
+
+interface ExecuteOutputPayload {
+  rowsAffected: number;
+  lastInsertRowid: number;
+}
+
+/**
+ * Execute a single SQL statement that does not return rows.
+ */
+function execute(id: string, payload: Statement) {
+  return invoke<ExecuteOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sqlite",
+    command: "execute",
+    id,
+    payload,
+  });
+}
+
+/**
+ * Execute a single SQL query.
+ *
+ * Resolves to the rows, each mapping column names to values. Blobs are
+ * represented as arrays of bytes.
+ */
+function query<T = Record<string, unknown>>(id: string, payload: Statement) {
+  return invoke<T[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sqlite",
+    command: "query",
+    id,
+    payload,
+  });
+}
+
+/**
+ * Execute SQL statements that do not return rows in a single transaction.
+ *
+ * If any statement fails, the transaction is rolled back and none of the
+ * statements take effect.
+ */
+function transaction(id: string, payload: { statements: Statement[] }) {
+  return invoke<ExecuteOutputPayload[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sqlite",
+    command: "transaction",
+    id,
+    payload,
+  });
+}
+
+interface MigrateOutputPayload {
+  from: number;
+  to: number;
+}
+
+/**
+ * Migrate the database schema.
+ *
+ * Each migration is an SQL script that may consist of multiple statements. The
+ * schema version is the number of applied migrations, and only migrations
+ * after the current version are applied, each in its own transaction. Thus,
+ * migrations must only be appended and never modified or removed.
+ */
+function migrate(id: string, payload: { migrations: string[] }) {
+  return invoke<MigrateOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sqlite",
+    command: "migrate",
+    id,
+    payload,
+  });
+}
+
+export { execute, migrate, query, transaction };
@@ -0,0 +1,87 @@
+import { invoke } from "@tauri-apps/api/core";
+
+/**
+ * Get the value of a key in the storage of the widget.
+ *
+ * Resolves to `null` if the key does not exist.
+ */
+function get<T = unknown>(id: string, payload: { key: string }) {
+  return invoke<T | null>("plugin:deskulpt-core|call_plugin", {
+    plugin: "storage",
+    command: "get",
+    id,
+    payload,
+  });
+}
+
+/**
+ * Set the value of a key in the storage of the widget.
+ *
+ * The value can be anything serializable to JSON, where `null` deletes the key.
+ * Rejects if the storage would exceed its quota of 1 MiB.
+ */
+function set(id: string, payload: { key: string; value: unknown }) {
+  return invoke<void>("plugin:deskulpt-core|call_plugin", {
+    plugin: "storage",
+    command: "set",
+    id,
+    payload,
+  });
+}
+
+/**
+ * Delete a key from the storage of the widget.
+ *
+ * Resolves to whether the key existed.
+ */
+function deleteKey(id: string, payload: { key: string }) {
+  return invoke<boolean>("plugin:deskulpt-core|call_plugin", {
+    plugin: "storage",
+    command: "delete",
+    id,
+    payload,
+  });
+}
+
+/**
+ * List the keys in the storage of the widget in lexicographical order.
+ */
+function listKeys(id: string) {
+  return invoke<string[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "storage",
+    command: "list_keys",
+    id,
+  });
+}
+
+/**
+ * Delete all keys from the storage of the widget.
+ */
+function clear(id: string) {
+  return invoke<void>("plugin:deskulpt-core|call_plugin", {
+    plugin: "storage",
+    command: "clear",
+    id,
+  });
+}
+
+/**
+ * Atomically set the value of a key if it currently has the expected value.
+ *
+ * An omitted or `null` expected value expects the key to not exist, and an
+ * omitted or `null` value deletes the key. Resolves to whether the value was
+ * set.
+ */
+function compareAndSet(
+  id: string,
+  payload: { key: string; expected?: unknown; value?: unknown },
+) {
+  return invoke<boolean>("plugin:deskulpt-core|call_plugin", {
+    plugin: "storage",
+    command: "compare_and_set",
+    id,
+    payload,
+  });
+}
+
+export { clear, compareAndSet, deleteKey as delete, get, listKeys, set };
@@ -1,23 +1,134 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+/**
+ * Input payload selecting the fields to compute.
+ *
+ * If omitted, all fields are computed. Fields that are not selected are not
+ * refreshed and are absent from the output.
+ */
+interface FieldsInputPayload<F extends string> {
+  fields?: F[];
+}
+
+type CpuField = "usage" | "frequency" | "brand" | "loadAverage";
 
 interface CpuInfo {
-  vendorId: string;
-  brand: string;
-  frequency: number;
-  totalCpuUsage: number;
+  name: string;
+  usage?: number;
+  frequency?: number;
+  vendorId?: string;
+  brand?: string;
 }
 
+interface GetCpuOutputPayload {
+  cpuCount: number;
+  physicalCoreCount: number | null;
+  globalUsage?: number;
+  loadAverage?: { one: number; five: number; fifteen: number };
+  cpus: CpuInfo[];
+}
+
+function getCpu(id: string, payload?: FieldsInputPayload<CpuField>) {
+  return invoke<GetCpuOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_cpu",
+    id,
+    payload,
+  });
+}
+
+type MemoryField = "ram" | "swap";
+
+interface GetMemoryOutputPayload {
+  ram?: { total: number; used: number; free: number; available: number };
+  swap?: { total: number; used: number; free: number };
+}
+
+function getMemory(id: string, payload?: FieldsInputPayload<MemoryField>) {
+  return invoke<GetMemoryOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_memory",
+    id,
+    payload,
+  });
+}
+
+type DiskField = "space" | "kind" | "io";
+
 interface DiskInfo {
   name: string;
-  availableSpace: number;
-  totalSpace: number;
   mountPoint: string;
+  fileSystem: string;
+  isRemovable: boolean;
+  isReadOnly: boolean;
+  totalSpace?: number;
+  availableSpace?: number;
+  kind?: string;
+  totalRead?: number;
+  totalWritten?: number;
+  readPerSecond?: number;
+  writtenPerSecond?: number;
 }
 
+function getDisks(id: string, payload?: FieldsInputPayload<DiskField>) {
+  return invoke<DiskInfo[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_disks",
+    id,
+    payload,
+  });
+}
+
+type NetworkField =
+  | "traffic"
+  | "throughput"
+  | "packets"
+  | "errors"
+  | "addresses";
+
 interface NetworkInfo {
   interfaceName: string;
-  totalReceived: number;
-  totalTransmitted: number;
+  totalReceived?: number;
+  totalTransmitted?: number;
+  receivedPerSecond?: number;
+  transmittedPerSecond?: number;
+  totalPacketsReceived?: number;
+  totalPacketsTransmitted?: number;
+  totalErrorsOnReceived?: number;
+  totalErrorsOnTransmitted?: number;
+  macAddress?: string;
+  ipNetworks?: string[];
+}
+
+function getNetworks(id: string, payload?: FieldsInputPayload<NetworkField>) {
+  return invoke<NetworkInfo[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_networks",
+    id,
+    payload,
+  });
+}
+
+type OsInfoField = "os" | "kernel" | "hostName" | "arch";
+
+interface GetOsInfoOutputPayload {
+  systemName?: string;
+  osVersion?: string;
+  longOsVersion?: string;
+  distributionId?: string;
+  kernelVersion?: string;
+  hostName?: string;
+  cpuArch?: string;
+}
+
+function getOsInfo(id: string, payload?: FieldsInputPayload<OsInfoField>) {
+  return invoke<GetOsInfoOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_os_info",
+    id,
+    payload,
+  });
 }
 
 interface GetSystemInfoOutputPayload {
@@ -28,13 +139,31 @@ interface GetSystemInfoOutputPayload {
   osVersion?: string;
   hostName?: string;
   cpuCount: number;
-  cpuInfo: CpuInfo[];
-  disks: DiskInfo[];
-  networks: NetworkInfo[];
+  cpuInfo: {
+    vendorId: string;
+    brand: string;
+    frequency: number;
+    totalCpuUsage: number;
+  }[];
+  disks: {
+    name: string;
+    availableSpace: number;
+    totalSpace: number;
+    mountPoint: string;
+  }[];
+  networks: {
+    interfaceName: string;
+    totalReceived: number;
+    totalTransmitted: number;
+  }[];
   totalMemory: number;
   usedMemory: number;
 }
 
+/**
+ * @deprecated Use the granular functions, e.g., `getCpu` and `getMemory`,
+ * which only refresh the requested information.
+ */
 function getSystemInfo(id: string) {
   return invoke<GetSystemInfoOutputPayload>(
     "plugin:deskulpt-core|call_plugin",
@@ -46,4 +175,209 @@ function getSystemInfo(id: string) {
   );
 }
 
-export { getSystemInfo };
+interface ListProcessesInputPayload {
+  sortBy?: "cpu" | "memory" | "name" | "pid" | "startTime";
+  order?: "asc" | "desc";
+  limit?: number;
+  name?: string;
+}
+
+interface ProcessInfo {
+  pid: number;
+  name: string;
+  /**
+   * Only present if the widget declares `permissions.sys.processCommandLines`.
+   */
+  cmd?: string[];
+  cpuUsage: number;
+  memory: number;
+  startTime: number;
+  user: string | null;
+}
+
+function listProcesses(id: string, payload?: ListProcessesInputPayload) {
+  return invoke<ProcessInfo[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "list_processes",
+    id,
+    payload,
+  });
+}
+
+interface ComponentTemperature {
+  label: string;
+  temperature: number | null;
+  max: number | null;
+  critical: number | null;
+}
+
+function getTemperatures(id: string) {
+  return invoke<ComponentTemperature[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_temperatures",
+    id,
+  });
+}
+
+interface BatteryInfo {
+  vendor: string | null;
+  model: string | null;
+  charge: number;
+  state: "charging" | "discharging" | "full" | "empty" | "unknown";
+  timeToFull: number | null;
+  timeToEmpty: number | null;
+  health: number;
+  cycleCount: number | null;
+}
+
+function getBatteries(id: string) {
+  return invoke<BatteryInfo[]>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_batteries",
+    id,
+  });
+}
+
+interface GetUptimeOutputPayload {
+  uptime: number;
+  bootTime: number;
+  loadAverage: { one: number; five: number; fifteen: number };
+}
+
+function getUptime(id: string) {
+  return invoke<GetUptimeOutputPayload>("plugin:deskulpt-core|call_plugin", {
+    plugin: "sys",
+    command: "get_uptime",
+    id,
+  });
+}
+
+interface Metrics {
+  batteries: { payload: undefined; sample: BatteryInfo[] };
+  cpu: {
+    payload: FieldsInputPayload<CpuField>;
+    sample: GetCpuOutputPayload;
+  };
+  disks: { payload: FieldsInputPayload<DiskField>; sample: DiskInfo[] };
+  memory: {
+    payload: FieldsInputPayload<MemoryField>;
+    sample: GetMemoryOutputPayload;
+  };
+  networks: {
+    payload: FieldsInputPayload<NetworkField>;
+    sample: NetworkInfo[];
+  };
+  osInfo: {
+    payload: FieldsInputPayload<OsInfoField>;
+    sample: GetOsInfoOutputPayload;
+  };
+  processes: { payload: ListProcessesInputPayload; sample: ProcessInfo[] };
+  temperatures: { payload: undefined; sample: ComponentTemperature[] };
+  uptime: { payload: undefined; sample: GetUptimeOutputPayload };
+}
+
+const METRIC_COMMANDS: Record<keyof Metrics, string> = {
+  batteries: "get_batteries",
+  cpu: "get_cpu",
+  disks: "get_disks",
+  memory: "get_memory",
+  networks: "get_networks",
+  osInfo: "get_os_info",
+  processes: "list_processes",
+  temperatures: "get_temperatures",
+  uptime: "get_uptime",
+};
+
+type Outcome<T> =
+  | { type: "ok"; content: T }
+  | { type: "err"; content: string };
+
+interface SubscribeOptions<P> {
+  /**
+   * The sampling interval in milliseconds, at least 250. Defaults to 1000.
+   */
+  interval?: number;
+  /**
+   * The payload of the metric, as for the corresponding command.
+   */
+  payload?: P;
+  /**
+   * Callback for errors sampling the metric.
+   */
+  onError?: (error: string) => void;
+}
+
+/**
+ * Subscribe to a metric sampled periodically by the backend.
+ *
+ * Each distinct combination of metric, payload and interval is sampled once
+ * per interval no matter how many widgets subscribe to it. The latest sample
+ * is delivered immediately on subscription. The returned promise resolves to
+ * a function that cancels the subscription. Subscriptions are also cancelled
+ * when the widget is re-rendered or removed.
+ */
+async function subscribe<M extends keyof Metrics>(
+  id: string,
+  metric: M,
+  onSample: (sample: Metrics[M]["sample"]) => void,
+  options: SubscribeOptions<Metrics[M]["payload"]> = {},
+) {
+  const { interval = 1000, payload, onError = console.error } = options;
+  const deliver = (sample: Outcome<Metrics[M]["sample"]>) => {
+    if (sample.type === "ok") {
+      onSample(sample.content);
+    } else {
+      onError(sample.content);
+    }
+  };
+
+  // Listen before subscribing so that no sample is missed in between; samples
+  // of other topics, including those before the topic is known, are ignored
+  let topic: string | undefined;
+  const unlisten = await listen<{
+    topic: string;
+    sample: Outcome<Metrics[M]["sample"]>;
+  }>("metric-update", (event) => {
+    if (event.payload.topic === topic) {
+      deliver(event.payload.sample);
+    }
+  });
+
+  const subscription = await invoke<{
+    handle: number;
+    topic: string;
+    latest: Outcome<Metrics[M]["sample"]>;
+  }>("plugin:deskulpt-core|subscribe_metric", {
+    id,
+    command: METRIC_COMMANDS[metric],
+    payload: payload ?? null,
+    interval,
+  }).catch((error) => {
+    unlisten();
+    throw error;
+  });
+  topic = subscription.topic;
+  deliver(subscription.latest);
+
+  return () => {
+    unlisten();
+    invoke("plugin:deskulpt-core|unsubscribe_metric", {
+      id,
+      handle: subscription.handle,
+    }).catch(console.error);
+  };
+}
+
+export {
+  getBatteries,
+  getCpu,
+  getDisks,
+  getMemory,
+  getNetworks,
+  getOsInfo,
+  getSystemInfo,
+  getTemperatures,
+  getUptime,
+  listProcesses,
+  subscribe,
+};
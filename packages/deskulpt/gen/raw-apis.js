@@ -1,2 +1,2 @@
 /*! Auto-generated from packages/apis. DO NOT EDIT! */
-import{invoke as l}from"@tauri-apps/api/core";var i={fs:Object.freeze({__proto__:null,appendFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"append_file",id:i,payload:n})},createDir:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"create_dir",id:i,payload:n})},exists:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"exists",id:i,payload:n})},isDir:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"is_dir",id:i,payload:n})},isFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"is_file",id:i,payload:n})},readFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"read_file",id:i,payload:n})},removeDir:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"remove_dir",id:i,payload:n})},removeFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"remove_file",id:i,payload:n})},writeFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"write_file",id:i,payload:n})}}),sys:Object.freeze({__proto__:null,getSystemInfo:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_system_info",id:i})}})};export{i as default};
+import{invoke as l}from"@tauri-apps/api/core";import{listen as m}from"@tauri-apps/api/event";const d={batteries:"get_batteries",cpu:"get_cpu",disks:"get_disks",memory:"get_memory",networks:"get_networks",osInfo:"get_os_info",processes:"list_processes",temperatures:"get_temperatures",uptime:"get_uptime"};var i={fs:Object.freeze({__proto__:null,appendFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"append_file",id:i,payload:n})},createDir:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"create_dir",id:i,payload:n})},exists:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"exists",id:i,payload:n})},isDir:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"is_dir",id:i,payload:n})},isFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"is_file",id:i,payload:n})},readFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"read_file",id:i,payload:n})},removeDir:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"remove_dir",id:i,payload:n})},removeFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"remove_file",id:i,payload:n})},writeFile:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"fs",command:"write_file",id:i,payload:n})}}),http:Object.freeze({__proto__:null,request:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"http",command:"request",id:i,payload:n})}}),shell:Object.freeze({__proto__:null,kill:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"shell",command:"kill",id:i,payload:n})},run:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"shell",command:"run",id:i,payload:n})},spawn:async function(i,n,e={}){const{onStdout:t,onStderr:o,onExit:r}=e,s=i=>{switch(i.type){case"stdout":null==t||t(i.data);break;case"stderr":null==o||o(i.data);break;case"exit":u(),null==r||r({code:i.code,killed:i.killed,timedOut:i.timedOut})}};let a;const c=[],u=await m("plugin",(n=>{"shell"===n.payload.plugin&&n.payload.id===i&&(void 0===a?c.push(n.payload.payload):n.payload.payload.handle===a&&s(n.payload.payload))})),p=await l("plugin:deskulpt-core|call_plugin",{plugin:"shell",command:"spawn",id:i,payload:n}).catch((i=>{throw u(),i}));return a=p.handle,c.filter((i=>i.handle===a)).forEach(s),{handle:a,kill:()=>l("plugin:deskulpt-core|call_plugin",{plugin:"shell",command:"kill",id:i,payload:{handle:p.handle}})}}}),sqlite:Object.freeze({__proto__:null,execute:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sqlite",command:"execute",id:i,payload:n})},migrate:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sqlite",command:"migrate",id:i,payload:n})},query:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sqlite",command:"query",id:i,payload:n})},transaction:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sqlite",command:"transaction",id:i,payload:n})}}),storage:Object.freeze({__proto__:null,clear:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"storage",command:"clear",id:i})},compareAndSet:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"storage",command:"compare_and_set",id:i,payload:n})},delete:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"storage",command:"delete",id:i,payload:n})},get:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"storage",command:"get",id:i,payload:n})},listKeys:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"storage",command:"list_keys",id:i})},set:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"storage",command:"set",id:i,payload:n})}}),sys:Object.freeze({__proto__:null,getBatteries:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_batteries",id:i})},getCpu:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_cpu",id:i,payload:n})},getDisks:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_disks",id:i,payload:n})},getMemory:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_memory",id:i,payload:n})},getNetworks:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_networks",id:i,payload:n})},getOsInfo:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_os_info",id:i,payload:n})},getSystemInfo:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_system_info",id:i})},getTemperatures:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_temperatures",id:i})},getUptime:function(i){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"get_uptime",id:i})},listProcesses:function(i,n){return l("plugin:deskulpt-core|call_plugin",{plugin:"sys",command:"list_processes",id:i,payload:n})},subscribe:async function(i,n,e,t={}){const{interval:o=1e3,payload:r,onError:s=console.error}=t,a=i=>{"ok"===i.type?e(i.content):s(i.content)};let c;const u=await m("metric-update",(i=>{i.payload.topic===c&&a(i.payload.sample)})),p=await l("plugin:deskulpt-core|subscribe_metric",{id:i,command:d[n],payload:null!=r?r:null,interval:o}).catch((i=>{throw u(),i}));return c=p.topic,a(p.latest),()=>{u(),l("plugin:deskulpt-core|unsubscribe_metric",{id:i,handle:p.handle}).catch(console.error)}}})};export{i as default};
@@ -7,6 +7,125 @@ import * as tauriEvent from "@tauri-apps/api/event";
 // Types
 // =============================================================================
 
+/**
+ * Preferred anchor of a widget on the canvas.
+ */
+export type Anchor = "topLeft" | "topRight" | "bottomLeft" | "bottomRight" | "center"
+
+/**
+ * Widget-specific bundler configuration.
+ * 
+ * This is the `build` section of `deskulpt.conf.json`. Each field is optional
+ * and, if specified, is merged over the defaults of the widget bundler.
+ */
+export type BuildConfig = { 
+/**
+ * Import aliases.
+ * 
+ * This maps import specifiers (or specifier prefixes, e.g. `@/`) to paths
+ * relative to the widget directory (e.g. `src/`). Aliases must stay within
+ * the widget directory and cannot override the packages provided by
+ * Deskulpt at runtime.
+ */
+alias: { [key in string]: string }; 
+/**
+ * Global constants to replace at build time.
+ * 
+ * This maps identifiers or property accesses to JavaScript expressions,
+ * e.g. `"__DEBUG__": "true"` or `"process.env.API": "\"/api\""`.
+ */
+define: { [key in string]: string }; 
+/**
+ * The import source for the automatic JSX runtime.
+ * 
+ * Defaults to `@deskulpt-test/emotion` if not specified.
+ */
+jsxImportSource: string | null; 
+/**
+ * Whether to minify the bundled code.
+ * 
+ * Defaults to `true` if not specified. Turning this off can be useful for
+ * debugging.
+ */
+minify: boolean | null }
+
+/**
+ * Event for reporting the progress of bundling widgets.
+ * 
+ * This event is emitted from the backend to all frontend windows when each
+ * widget starts and finishes bundling.
+ */
+export type BundleProgressEvent = 
+/**
+ * A widget has started bundling.
+ */
+{ type: "started"; content: { 
+/**
+ * The ID of the widget.
+ */
+id: string } } | 
+/**
+ * A widget has been bundled successfully.
+ */
+{ type: "finished"; content: { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The time taken to bundle the widget in milliseconds.
+ */
+elapsed: number } } | 
+/**
+ * A widget has failed to bundle.
+ */
+{ type: "failed"; content: { 
+/**
+ * The ID of the widget.
+ */
+id: string; 
+/**
+ * The time taken before the failure in milliseconds.
+ */
+elapsed: number; 
+/**
+ * The error message.
+ */
+error: string } }
+
+/**
+ * Report of a widget bundle.
+ * 
+ * All module paths are relative to the widget directory. Modules outside the
+ * widget directory, e.g. dependencies resolved from the shared package cache,
+ * are relative paths starting with `..`. Externalized imports are kept as is.
+ */
+export type BundleReport = { 
+/**
+ * The size of the bundled code in bytes.
+ */
+size: number; 
+/**
+ * The size in bytes contributed by each module to the bundled code.
+ * 
+ * Modules that are completely tree-shaken are not included.
+ */
+modules: { [key in string]: number }; 
+/**
+ * The import graph.
+ * 
+ * This maps each module to the modules it imports, including externalized
+ * imports, in the order they are imported.
+ */
+imports: { [key in string]: string[] }; 
+/**
+ * The imports externalized through the alias map.
+ * 
+ * This maps each externalized import specifier to the URL it is aliased
+ * to, which will be resolved at runtime.
+ */
+externals: { [key in string]: string } }
+
 /**
  * Deskulpt window enum.
  */
@@ -20,8 +139,104 @@ export type DeskulptWindow =
  */
 "canvas"
 
+/**
+ * A widget installed from a package.
+ */
+export type InstalledWidget = { 
+/**
+ * The version of the installed package, if any.
+ */
+version: string | null; 
+/**
+ * The checksum of the installed package.
+ */
+checksum: string }
+
 export type JsonValue = null | boolean | number | string | JsonValue[] | { [key in string]: JsonValue }
 
+/**
+ * Default geometry and placement hints of a widget.
+ * 
+ * This is the `layout` section of `deskulpt.conf.json`. The default size and
+ * anchor only take effect when the widget is discovered for the first time,
+ * while the size constraints are enforced whenever the widget is resized.
+ */
+export type LayoutConfig = { 
+/**
+ * The default width in pixels.
+ */
+width: number | null; 
+/**
+ * The default height in pixels.
+ */
+height: number | null; 
+/**
+ * The minimum width in pixels.
+ */
+minWidth: number | null; 
+/**
+ * The minimum height in pixels.
+ */
+minHeight: number | null; 
+/**
+ * The maximum width in pixels.
+ */
+maxWidth: number | null; 
+/**
+ * The maximum height in pixels.
+ */
+maxHeight: number | null; 
+/**
+ * The aspect ratio (width divided by height) to maintain.
+ */
+aspectRatio: number | null; 
+/**
+ * The preferred anchor on the canvas.
+ * 
+ * New widgets are placed in the free space closest to this anchor.
+ */
+anchor: Anchor }
+
+/**
+ * A subscription to a metric topic.
+ */
+export type MetricSubscription = { 
+/**
+ * The handle of the subscription.
+ * 
+ * This is used to unsubscribe with
+ * [`unsubscribe_metric`](crate::commands::unsubscribe_metric).
+ */
+handle: number; 
+/**
+ * The key of the metric topic.
+ * 
+ * Samples of the metric are sent with [`MetricUpdateEvent`] carrying
+ * this key.
+ */
+topic: string; 
+/**
+ * The latest sample of the metric.
+ */
+latest: Outcome<JsonValue> }
+
+/**
+ * Event for sending a sample of a metric.
+ * 
+ * This event is emitted from the backend to the canvas window whenever a
+ * metric topic with subscriptions is sampled. Widgets subscribed to the topic
+ * are responsible for picking up the samples with the matching topic key.
+ */
+export type MetricUpdateEvent = { 
+/**
+ * The key of the metric topic.
+ */
+topic: string; 
+/**
+ * The sample of the metric.
+ */
+sample: Outcome<JsonValue> }
+
 /**
  * A result-like binary outcome.
  * 
@@ -30,6 +245,53 @@ export type JsonValue = null | boolean | number | string | JsonValue[] | { [key
  */
 export type Outcome<T> = { type: "ok"; content: T } | { type: "err"; content: string }
 
+/**
+ * Permissions requested by a widget.
+ * 
+ * This is the `permissions` section of `deskulpt.conf.json`. Each field
+ * declares what the widget may access through the plugin of the same name,
+ * and is passed to that plugin as is.
+ */
+export type PermissionsConfig = { 
+/**
+ * The hosts that the widget may send HTTP requests to.
+ * 
+ * Each host may be followed by a port to only allow that port, e.g.
+ * `localhost:8080`, and may start with `*.` to allow all of its
+ * subdomains, e.g. `*.example.com`.
+ */
+http: string[]; 
+/**
+ * The shell commands that the widget may run.
+ */
+shell: ShellPermissionsConfig; 
+/**
+ * The sensitive system information that the widget may read.
+ */
+sys: SysPermissionsConfig }
+
+/**
+ * Event for sending data from a plugin to a widget.
+ * 
+ * This event is emitted from the backend to the canvas window whenever a
+ * plugin emits an event to a widget, e.g., output of a process spawned by the
+ * shell plugin. Widgets are responsible for picking up the events with their
+ * own IDs and the name of the plugin.
+ */
+export type PluginEvent = { 
+/**
+ * The name of the plugin that emitted the event.
+ */
+plugin: string; 
+/**
+ * The ID of the widget that the event is for.
+ */
+id: string; 
+/**
+ * The payload of the event, interpreted by the plugin and the widget.
+ */
+payload: JsonValue }
+
 /**
  * Event for rendering widgets.
  * 
@@ -54,7 +316,42 @@ shortcuts: Partial<{ [key in ShortcutKey]: string }>;
 /**
  * The mapping from widget IDs to their respective settings.
  */
-widgets: { [key in string]: WidgetSettings } }
+widgets: { [key in string]: WidgetSettings }; 
+/**
+ * The maximum number of widgets to bundle at the same time.
+ * 
+ * If set to 0, the available parallelism of the system is used.
+ */
+bundleConcurrency: number; 
+/**
+ * Extra widget directories, mapped from their namespaces.
+ * 
+ * Widgets are discovered in these directories in addition to the default
+ * widgets directory, with their IDs prefixed by the namespace, e.g.
+ * `shared:clock`. Namespaces may only contain ASCII letters, digits, `-`
+ * and `_`.
+ */
+widgetRoots: { [key in string]: string }; 
+/**
+ * Whether to refuse privileged plugin access to unverified widgets.
+ * 
+ * If enabled, only widgets installed from packages signed by a trusted
+ * key can call privileged plugins, such as the file system plugin, and
+ * permissions declared by other widgets are ignored.
+ * 
+ * This is advisory only and not a security boundary. All widgets run in
+ * the same canvas and the widget ID of a plugin call is supplied by the
+ * caller, so a malicious widget can call plugins on behalf of a verified
+ * widget.
+ */
+restrictUnverifiedWidgets: boolean; 
+/**
+ * The location of the widget registry index, if any.
+ * 
+ * This can be a `file://` URL, an `http://` URL, or an absolute path to a
+ * local file. Installed widgets are checked for updates against it.
+ */
+registryIndex: string | null }
 
 /**
  * A patch for partial updates to [`Settings`].
@@ -81,7 +378,86 @@ shortcuts?: Partial<{ [key in ShortcutKey]: string | null }>;
  * does not exist, a new widget settings will be created with default
  * values, and then the patch will be applied to it.
  */
-widgets?: { [key in string]: WidgetSettingsPatch | null } }
+widgets?: { [key in string]: WidgetSettingsPatch | null }; 
+/**
+ * If not `None`, update [`Settings::bundle_concurrency`].
+ */
+bundleConcurrency?: number; 
+/**
+ * If not `None`, update [`Settings::widget_roots`].
+ * 
+ * Non-specified widget roots will remain unchanged. If a widget root value
+ * is `None`, it means removing that widget root. Otherwise, it means
+ * updating or adding that widget root. Changes take effect on the next
+ * rescan.
+ */
+widgetRoots?: { [key in string]: string | null }; 
+/**
+ * If not `None`, update [`Settings::restrict_unverified_widgets`].
+ */
+restrictUnverifiedWidgets?: boolean; 
+/**
+ * If not `None`, update [`Settings::registry_index`].
+ * 
+ * If the value is `None`, it means removing the registry index.
+ */
+registryIndex?: string | null }
+
+/**
+ * Shell commands that a widget may run.
+ * 
+ * This is the `permissions.shell` section of `deskulpt.conf.json`.
+ */
+export type ShellPermissionsConfig = { 
+/**
+ * The commands that the widget may run, with any arguments.
+ * 
+ * Each command is either the name of a program looked up in `PATH`, e.g.
+ * `git`, or a path relative to the widget directory, e.g.
+ * `./scripts/status.sh`. Note that allowing an interpreter such as `sh`
+ * or `python` effectively allows any command.
+ */
+commands: string[]; 
+/**
+ * Extra directories that the commands may run in.
+ * 
+ * Commands run in the widget directory by default, and may run in its
+ * subdirectories or in these directories and their subdirectories.
+ * Relative paths are relative to the widget directory.
+ */
+dirs: string[]; 
+/**
+ * Environment variables that the widget may set for the commands.
+ * 
+ * Commands inherit the environment of Deskulpt, and only these variables
+ * may be added or overridden. Note that many variables make programs run
+ * other code, e.g. `LD_PRELOAD`, `BASH_ENV`, `NODE_OPTIONS` or
+ * `GIT_SSH_COMMAND`, so allowing them is like allowing an interpreter.
+ */
+env: string[] }
+
+/**
+ * The signature status of a widget.
+ */
+export type SignatureStatus = 
+/**
+ * The widget is installed from a package signed by a trusted key.
+ * 
+ * The content is the name of the trusted key in the trust store.
+ */
+{ type: "verified"; content: string } | 
+/**
+ * The widget is not installed from a signed package, or the package is
+ * signed by a key that is not in the trust store.
+ */
+{ type: "unverified" } | 
+/**
+ * The signature of the package is invalid, or the installed files have
+ * been modified since installation.
+ * 
+ * The content is the reason why the signature is invalid.
+ */
+{ type: "invalid"; content: string }
 
 /**
  * Types of keyboard shortcuts in the application.
@@ -112,6 +488,20 @@ export type ShowToastEvent =
  */
 { type: "error"; content: string }
 
+/**
+ * Sensitive system information that a widget may read.
+ * 
+ * This is the `permissions.sys` section of `deskulpt.conf.json`.
+ */
+export type SysPermissionsConfig = { 
+/**
+ * Whether the widget may read the command lines of processes.
+ * 
+ * Command lines may contain secrets such as tokens passed as arguments,
+ * so they are omitted from process lists unless this is enabled.
+ */
+processCommandLines: boolean }
+
 /**
  * Light/dark theme of the application.
  */
@@ -127,25 +517,59 @@ export type Theme = "light" | "dark"
 export type UpdateSettingsEvent = Settings
 
 /**
- * Event for updating the widget catalog.
+ * The difference between two widget catalogs.
+ */
+export type WidgetCatalogDiff = { 
+/**
+ * Newly discovered widgets, mapped from their IDs to their configurations.
+ */
+added: { [key in string]: Outcome<WidgetConfig> }; 
+/**
+ * The IDs of widgets that are no longer discovered.
+ */
+removed: string[]; 
+/**
+ * Widgets that have changed, mapped from their IDs to their new
+ * configurations.
  * 
- * This event is emitted from the backend to all frontend windows whenever
- * there is a change in the widget catalog.
+ * A widget is considered changed if its configuration, its directory, or
+ * the fingerprint of its contents has changed.
+ */
+changed: { [key in string]: Outcome<WidgetConfig> }; 
+/**
+ * The IDs of widgets that have not changed.
+ */
+unchanged: string[]; 
+/**
+ * The full mapping from groups to the IDs of the widgets directly in them.
+ * 
+ * See [`WidgetCatalog::groups`] for more details.
+ */
+groups: { [key in string]: string[] }; 
+/**
+ * The full mapping from widget IDs to their signature statuses.
+ * 
+ * See [`WidgetCatalog::signatures`] for more details.
  */
-export type UpdateWidgetCatalogEvent = WidgetCatalog
+signatures: { [key in string]: SignatureStatus } }
 
 /**
- * The widget catalog.
+ * Event for notifying changes to the widget catalog.
  * 
- * This is a collection of all widgets discovered locally, mapped from their
- * widget IDs to their configurations.
+ * This event is emitted from the backend to all frontend windows whenever the
+ * widget catalog is rescanned. Only the difference from the previous catalog
+ * is included, so that windows can leave unchanged widgets untouched.
  */
-export type WidgetCatalog = { [key in string]: Outcome<WidgetConfig> }
+export type WidgetCatalogDiffEvent = WidgetCatalogDiff
 
 /**
  * Full configuration of a Deskulpt widget.
  */
 export type WidgetConfig = { 
+/**
+ * The stable identifier of the widget, if specified.
+ */
+id: string | null; 
 /**
  * The name of the widget.
  */
@@ -157,7 +581,68 @@ entry: string;
 /**
  * The dependencies of the widget.
  */
-dependencies: { [key in string]: string } }
+dependencies: { [key in string]: string }; 
+/**
+ * Descriptive metadata of the widget.
+ */
+metadata: WidgetMetadata; 
+/**
+ * Default geometry and placement hints of the widget.
+ */
+layout: LayoutConfig; 
+/**
+ * Widget-specific bundler configuration.
+ */
+build: BuildConfig; 
+/**
+ * Permissions requested by the widget.
+ */
+permissions: PermissionsConfig }
+
+/**
+ * Descriptive metadata of a widget.
+ * 
+ * These fields are all optional in `deskulpt.conf.json`, specified at the top
+ * level next to `name` and `entry`. They are mostly for display purposes.
+ */
+export type WidgetMetadata = { 
+/**
+ * A short description of the widget.
+ */
+description: string | null; 
+/**
+ * The version of the widget, following semantic versioning.
+ */
+version: string | null; 
+/**
+ * The authors of the widget.
+ */
+authors: string[]; 
+/**
+ * The URL to the homepage of the widget.
+ */
+homepage: string | null; 
+/**
+ * The license of the widget, preferably as an SPDX identifier.
+ */
+license: string | null; 
+/**
+ * The icon of the widget.
+ * 
+ * This is the path to an image file relative to the widget directory.
+ */
+icon: string | null; 
+/**
+ * Tags for categorizing the widget.
+ */
+tags: string[]; 
+/**
+ * The minimum version of Deskulpt required by the widget.
+ * 
+ * A widget that requires a newer version of Deskulpt than the running one
+ * fails to load.
+ */
+minDeskulptVersion: string | null }
 
 /**
  * Per-widget settings.
@@ -185,7 +670,15 @@ height: number;
 /**
  * The opacity in percentage.
  */
-opacity: number }
+opacity: number; 
+/**
+ * The fingerprint of the widget contents when it was last discovered.
+ * 
+ * This is used to recognize the widget and migrate its settings if its
+ * directory is renamed or moved. It is managed by the application and
+ * should not be modified manually.
+ */
+fingerprint: string | null }
 
 /**
  * A patch for partial updates to [`WidgetSettings`].
@@ -212,6 +705,37 @@ height?: number;
  */
 opacity?: number }
 
+/**
+ * Built-in templates for new widgets.
+ */
+export type WidgetTemplate = "minimal" | "tsx" | "plugin"
+
+/**
+ * An update available for an installed widget.
+ */
+export type WidgetUpdate = { 
+/**
+ * The display name of the widget in the registry.
+ */
+name: string; 
+/**
+ * The installed version of the widget.
+ */
+current: string; 
+/**
+ * The latest version of the widget in the registry.
+ */
+latest: string }
+
+/**
+ * Event for reporting available widget updates.
+ * 
+ * This event is emitted from the backend to the manager window whenever the
+ * widget registry is checked for updates. The event carries a mapping from
+ * the IDs of the widgets with available updates to the update information.
+ */
+export type WidgetUpdatesEvent = { [key in string]: WidgetUpdate }
+
 // =============================================================================
 // Events
 // =============================================================================
@@ -233,10 +757,14 @@ function makeEvent<T>(name: string) {
 }
 
 export const events = {
+  bundleProgress: makeEvent<BundleProgressEvent>("bundle-progress"),
+  metricUpdate: makeEvent<MetricUpdateEvent>("metric-update"),
+  plugin: makeEvent<PluginEvent>("plugin"),
   renderWidgets: makeEvent<RenderWidgetsEvent>("render-widgets"),
   showToast: makeEvent<ShowToastEvent>("show-toast"),
   updateSettings: makeEvent<UpdateSettingsEvent>("update-settings"),
-  updateWidgetCatalog: makeEvent<UpdateWidgetCatalogEvent>("update-widget-catalog"),
+  widgetCatalogDiff: makeEvent<WidgetCatalogDiffEvent>("widget-catalog-diff"),
+  widgetUpdates: makeEvent<WidgetUpdatesEvent>("widget-updates"),
 };
 
 // =============================================================================
@@ -249,14 +777,22 @@ export const commands = {
      * Bundle widgets.
      * 
      * This command bundles the specified widgets that exist in the catalog. If
-     * `ids` is not provided, all widgets in the catalog are bundled. Failure to
-     * bundle an individual widget does not prevent other widgets from being
-     * bundled. Instead, the outcome of each bundling operation is collected and
-     * sent to the canvas window via the [`RenderWidgetsEvent`].
+     * `ids` is not provided, all widgets in the catalog are bundled. At most
+     * [`Settings::bundle_concurrency`](crate::settings::Settings::bundle_concurrency)
+     * widgets are bundled at the same time. Failure to bundle an individual widget
+     * does not prevent other widgets from being bundled.
+     * 
+     * The progress of each widget is reported to all windows via the
+     * [`BundleProgressEvent`]. As soon as a widget finishes bundling, its outcome
+     * is sent to the canvas window via the [`RenderWidgetsEvent`], so that one
+     * slow widget does not delay the others from being rendered. The report of
+     * each successful bundle is kept in the state, which can be retrieved with
+     * [`get_bundle_report`](crate::commands::get_bundle_report).
      * 
      * ### Errors
      * 
-     * - Error accessing the widgets directory.
+     * - Error accessing the persistence directory.
+     * - Error emitting the [`BundleProgressEvent`].
      * - Error emitting the [`RenderWidgetsEvent`].
      */
     bundleWidgets: (
@@ -278,6 +814,15 @@ export const commands = {
      * Also, in order to simplify the engine API for the plugin (because it is
      * a temporary implementation), `app_handle` is using the default runtime but
      * it should be a generic `R: Runtime` parameter in the final implementation.
+     * 
+     * ### Errors
+     * 
+     * - The plugin is privileged, unverified widgets are restricted, and the
+     *   widget is not installed from a package signed by a trusted key. Note that
+     *   this trusts the widget ID sent by the caller, so it does not stop a
+     *   malicious widget that impersonates a verified one.
+     * - Error calling the plugin command.
+     * - Unknown plugin.
      */
     callPlugin: (
       plugin: string,
@@ -291,6 +836,93 @@ export const commands = {
       payload,
     }),
 
+    /**
+     * Check the installed widgets for updates in the widget registry.
+     * 
+     * This command fetches the index configured in
+     * [`Settings::registry_index`](crate::settings::Settings::registry_index) and
+     * compares it with the versions of the widgets installed from packages. The
+     * available updates are reported to the manager window with the
+     * [`WidgetUpdatesEvent`] and also returned.
+     * 
+     * ### Errors
+     * 
+     * - No registry index is configured.
+     * - Error fetching or parsing the registry index.
+     * - Error accessing the widgets directory.
+     * - Error emitting the [`WidgetUpdatesEvent`].
+     */
+    checkWidgetUpdates: () => invoke<{ [key in string]: WidgetUpdate }>("plugin:deskulpt-core|check_widget_updates"),
+
+    /**
+     * Create a new widget from a built-in template.
+     * 
+     * This command generates a widget with the given display name from the given
+     * template in its own directory in the widgets directory. The directory is
+     * named after the widget name and made unique among the existing directories
+     * and widget IDs. It then rescans the widgets with `rescan_widgets` so that
+     * the new widget is discovered, and returns the ID of the new widget.
+     * 
+     * ### Errors
+     * 
+     * - The widget name is empty.
+     * - Error accessing the widgets directory.
+     * - Error writing the widget files.
+     * - Error rescanning the widgets.
+     */
+    createWidget: (
+      name: string,
+      template: WidgetTemplate,
+    ) => invoke<string>("plugin:deskulpt-core|create_widget", {
+      name,
+      template,
+    }),
+
+    /**
+     * Get the report of the last successful bundle of a widget.
+     * 
+     * The report includes the bundle size, the size contributed by each module,
+     * the import graph, and the imports externalized through the alias map. If
+     * the widget has not been bundled successfully yet, `null` is returned.
+     */
+    getBundleReport: (
+      id: string,
+    ) => invoke<BundleReport | null>("plugin:deskulpt-core|get_bundle_report", {
+      id,
+    }),
+
+    /**
+     * Install a widget from a package.
+     * 
+     * This command verifies the widget package at the given local path and
+     * extracts it into its own directory in the widgets directory. See
+     * [`PackageManifest`](crate::package::PackageManifest) for the package format.
+     * It then rescans the widgets with `rescan_widgets` so that the installed
+     * widget is discovered, and returns the ID of the installed widget.
+     * 
+     * ### Errors
+     * 
+     * - Error accessing the widgets directory.
+     * - The package is invalid, e.g., its files do not match the manifest or it
+     *   contains entries that escape the widget directory.
+     * - A widget directory with the same ID already exists.
+     * - Error rescanning the widgets.
+     */
+    installWidget: (
+      path: string,
+    ) => invoke<string>("plugin:deskulpt-core|install_widget", {
+      path,
+    }),
+
+    /**
+     * List the widgets installed from packages.
+     * 
+     * This returns the mapping from the IDs of the widgets in the catalog that
+     * are installed from packages to their installation information. Widgets
+     * whose package manifests cannot be read are skipped.
+     */
+    listInstalled: () => invoke<{ [key in string]: InstalledWidget }>("plugin:deskulpt-core|list_installed"),
+
     /**
      * Open the widgets directory or a specific widget directory.
      * 
@@ -299,7 +931,7 @@ export const commands = {
      * 
      * ### Errors
      * 
-     * - Widget ID is provided but does not exist in the collection.
+     * - Widget ID is provided but does not exist in the catalog.
      * - Failed to access the widgets directory.
      * - Error opening the directory.
      */
@@ -312,19 +944,46 @@ export const commands = {
     /**
      * Rescan the widgets directory to discover widgets.
      * 
-     * This command scans the widgets directory for available widgets and updates
-     * the widget catalog and settings accordingly. It then emits events to notify
-     * the frontend of these changes. Finally, it triggers the bundling of all
-     * widgets in the updated catalog with `bundle_widgets` to ensure they are
-     * ready for use.
+     * This command scans the widgets directory as well as the extra widget roots
+     * in [`Settings::widget_roots`](crate::settings::Settings::widget_roots) for
+     * available widgets and updates the widget catalog and settings accordingly.
+     * It then emits events to notify the frontend of these changes, where only the
+     * difference from the previous catalog is sent. Finally, it triggers the
+     * bundling of the added and changed widgets with `bundle_widgets`, while the
+     * unchanged widgets are left untouched. Settings are only emitted if they
+     * have changed.
+     * 
+     * The signatures of the widgets installed from packages are checked against
+     * the trust store in the persistence directory. See
+     * [`TrustStore`](crate::package::TrustStore) for more information.
+     * 
+     * Problems with the extra widget roots, such as missing directories or ID
+     * conflicts, do not fail the rescan. They are instead shown as an error toast
+     * on the canvas.
+     * 
+     * Settings of widgets that are no longer discovered are removed, unless the
+     * widget directories have only been renamed or moved, in which case the
+     * settings are migrated to the new widget IDs. See
+     * [`Settings::migrate_widgets`](crate::settings::Settings::migrate_widgets)
+     * for more information. Newly discovered widgets are sized and placed in free
+     * space on the canvas according to the layout hints in their configurations.
+     * See [`LayoutConfig`](crate::config::LayoutConfig) for more information.
+     * 
+     * Storage directories of migrated widgets are moved along with their
+     * settings, and those of widgets that are no longer discovered are removed.
+     * Widgets in extra widget roots that could not be scanned, e.g., on a detached
+     * drive, keep their storage since they may only be unavailable for now. See
+     * [`PathExt::widget_storage_dir`] for more information. Failing to clean up
+     * the storage directories does not fail the rescan.
      * 
      * ### Errors
      * 
      * - Error accessing the widgets directory.
+     * - Error accessing the persistence directory.
      * - Error loading the new widget catalog from the widgets directory.
      * - Error emitting the [`UpdateSettingsEvent`].
-     * - Error emitting the [`UpdateWidgetCatalogEvent`].
-     * - Error bundling all discovered widgets.
+     * - Error emitting the [`WidgetCatalogDiffEvent`].
+     * - Error bundling the added and changed widgets.
      */
     rescanWidgets: () => invoke<null>("plugin:deskulpt-core|rescan_widgets"),
 
@@ -338,6 +997,80 @@ export const commands = {
      */
     setRenderReady: () => invoke<null>("plugin:deskulpt-core|set_render_ready"),
 
+    /**
+     * Subscribe a widget to a metric.
+     * 
+     * A metric is a command of the system information plugin with its payload,
+     * sampled every `interval` milliseconds. Each distinct metric is sampled by a
+     * single task no matter how many widgets subscribe to it, and each sample is
+     * sent to the canvas window via the [`MetricUpdateEvent`] with the key of the
+     * metric topic. The latest sample is returned immediately on subscription so
+     * that the widget does not have to wait for the next one.
+     * 
+     * Subscriptions are removed with
+     * [`unsubscribe_metric`](crate::commands::unsubscribe_metric), or when the
+     * widget is re-rendered or removed. The metric is no longer sampled once it
+     * has no subscriptions left.
+     * 
+     * ### Errors
+     * 
+     * - The interval is shorter than the minimum of 250 milliseconds.
+     * - The widget is not found or has invalid configuration.
+     * - Error sampling the metric for the first time, e.g., unknown command or
+     *   invalid payload.
+     */
+    subscribeMetric: (
+      id: string,
+      command: string,
+      payload: JsonValue | null,
+      interval: number,
+    ) => invoke<MetricSubscription>("plugin:deskulpt-core|subscribe_metric", {
+      id,
+      command,
+      payload,
+      interval,
+    }),
+
+    /**
+     * Uninstall a widget installed from a package.
+     * 
+     * This command removes the directory of the widget and cleans up its
+     * settings and storage. It then rescans the widgets with `rescan_widgets` so
+     * that the widget is removed from the catalog. Widgets that are not installed
+     * from packages cannot be uninstalled. Failing to remove the storage does not
+     * fail the uninstallation.
+     * 
+     * ### Errors
+     * 
+     * - The widget does not exist in the catalog.
+     * - The widget is not installed from a package.
+     * - Error removing the widget directory.
+     * - Error emitting the [`UpdateSettingsEvent`].
+     * - Error rescanning the widgets.
+     */
+    uninstallWidget: (
+      id: string,
+    ) => invoke<null>("plugin:deskulpt-core|uninstall_widget", {
+      id,
+    }),
+
+    /**
+     * Wrapper of
+     * [`remove_metric_subscription`](MetricSubscriptionsStateExt::remove_metric_subscription).
+     * 
+     * The handle is obtained from
+     * [`subscribe_metric`](crate::commands::subscribe_metric). Unknown handles,
+     * e.g., of subscriptions already removed because the widget is re-rendered,
+     * and handles of subscriptions of other widgets are ignored.
+     */
+    unsubscribeMetric: (
+      id: string,
+      handle: number,
+    ) => invoke<null>("plugin:deskulpt-core|unsubscribe_metric", {
+      id,
+      handle,
+    }),
+
     /**
      * Update the settings.
      * 
@@ -354,5 +1087,33 @@ export const commands = {
     ) => invoke<null>("plugin:deskulpt-core|update_settings", {
       patch,
     }),
+
+    /**
+     * Update a widget to its latest version in the widget registry.
+     * 
+     * This command downloads the package of the widget from the registry index
+     * configured in
+     * [`Settings::registry_index`](crate::settings::Settings::registry_index),
+     * verifies it against the checksum in the index, and installs it in place of
+     * the existing installation. The existing installation is restored if the
+     * update fails. It then rescans the widgets with `rescan_widgets` and checks
+     * for updates again with `check_widget_updates`.
+     * 
+     * ### Errors
+     * 
+     * - No registry index is configured.
+     * - Error fetching or parsing the registry index.
+     * - The widget ID is invalid or not in the registry.
+     * - Error downloading the package or checksum mismatch.
+     * - Error installing the package, e.g., the package is invalid or the widget
+     *   is not installed from a package.
+     * - Error rescanning the widgets.
+     * - Error checking for updates.
+     */
+    updateWidget: (
+      id: string,
+    ) => invoke<null>("plugin:deskulpt-core|update_widget", {
+      id,
+    }),
   },
 };
@@ -7,7 +7,7 @@ import {
   useSettingsStore,
   useShowToastListener,
   useUpdateSettingsListener,
-  useUpdateWidgetCatalogListener,
+  useWidgetCatalogDiffListener,
   useWidgetsStore,
 } from "./hooks";
 
@@ -18,7 +18,7 @@ const App = () => {
   useRenderWidgetsListener();
   useShowToastListener();
   useUpdateSettingsListener();
-  useUpdateWidgetCatalogListener();
+  useWidgetCatalogDiffListener();
 
   return (
     <RadixTheme
@@ -2,5 +2,5 @@ export * from "./useRenderWidgetsListener";
 export * from "./useSettingsStore";
 export * from "./useShowToastListener";
 export * from "./useUpdateSettingsListener";
-export * from "./useUpdateWidgetCatalogListener";
+export * from "./useWidgetCatalogDiffListener";
 export * from "./useWidgetsStore";
@@ -2,15 +2,21 @@ import { useEffect } from "react";
 import { events } from "../../bindings";
 import { useWidgetsStore } from "./useWidgetsStore";
 
-export function useUpdateWidgetCatalogListener() {
+export function useWidgetCatalogDiffListener() {
   useEffect(() => {
-    const unlisten = events.updateWidgetCatalog.listen((event) => {
-      const widgets = Object.entries(useWidgetsStore.getState());
+    const unlisten = events.widgetCatalogDiff.listen((event) => {
+      const { removed } = event.payload;
+
+      // Only removed widgets need to be cleaned up; added and changed widgets
+      // will be rendered once bundled, and unchanged widgets are left as is
+      if (removed.length === 0) {
+        return;
+      }
 
-      // Clean up widgets that are no longer in the catalog
+      const widgets = Object.entries(useWidgetsStore.getState());
       const remainingWidgets = widgets.filter(
         ([id, { apisBlobUrl, moduleBlobUrl }]) => {
-          if (id in event.payload) {
+          if (!removed.includes(id)) {
             return true;
           }
           URL.revokeObjectURL(apisBlobUrl);
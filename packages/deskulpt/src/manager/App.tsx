@@ -4,7 +4,8 @@ import {
   useInitialRescan,
   useSettingsStore,
   useUpdateSettingsListener,
-  useUpdateWidgetCatalogListener,
+  useWidgetCatalogDiffListener,
+  useWidgetUpdatesListener,
 } from "./hooks";
 import About from "./components/About";
 import Widgets from "./components/Widgets";
@@ -22,7 +23,8 @@ const App = () => {
 
   useInitialRescan();
   useUpdateSettingsListener();
-  useUpdateWidgetCatalogListener();
+  useWidgetCatalogDiffListener();
+  useWidgetUpdatesListener();
 
   return (
     <RadixTheme appearance={theme} accentColor="indigo" grayColor="slate">
@@ -1,4 +1,12 @@
-import { Box, Code, ScrollArea, Table } from "@radix-ui/themes";
+import {
+  Badge,
+  Box,
+  Code,
+  Flex,
+  Link,
+  ScrollArea,
+  Table,
+} from "@radix-ui/themes";
 import { useWidgetsStore } from "../../hooks";
 import { memo } from "react";
 import Dependencies from "./Dependencies";
@@ -19,6 +27,7 @@ interface ConfigProps {
 
 const Config = memo(({ id }: ConfigProps) => {
   const config = useWidgetsStore((state) => state[id]);
+  const metadata = config?.type === "ok" ? config.content.metadata : undefined;
 
   return (
     <ScrollArea asChild>
@@ -30,6 +39,50 @@ const Config = memo(({ id }: ConfigProps) => {
                 <Table.RowHeaderCell>Name</Table.RowHeaderCell>
                 <Table.Cell>{config.content.name}</Table.Cell>
               </Table.Row>
+              {metadata?.description && (
+                <Table.Row align="center">
+                  <Table.RowHeaderCell>Description</Table.RowHeaderCell>
+                  <Table.Cell>{metadata.description}</Table.Cell>
+                </Table.Row>
+              )}
+              {metadata?.version && (
+                <Table.Row align="center">
+                  <Table.RowHeaderCell>Version</Table.RowHeaderCell>
+                  <Table.Cell>{metadata.version}</Table.Cell>
+                </Table.Row>
+              )}
+              {metadata !== undefined && metadata.authors.length > 0 && (
+                <Table.Row align="center">
+                  <Table.RowHeaderCell>Authors</Table.RowHeaderCell>
+                  <Table.Cell>{metadata.authors.join(", ")}</Table.Cell>
+                </Table.Row>
+              )}
+              {metadata?.license && (
+                <Table.Row align="center">
+                  <Table.RowHeaderCell>License</Table.RowHeaderCell>
+                  <Table.Cell>{metadata.license}</Table.Cell>
+                </Table.Row>
+              )}
+              {metadata?.homepage && (
+                <Table.Row align="center">
+                  <Table.RowHeaderCell>Homepage</Table.RowHeaderCell>
+                  <Table.Cell>
+                    <Link href={metadata.homepage}>{metadata.homepage}</Link>
+                  </Table.Cell>
+                </Table.Row>
+              )}
+              {metadata !== undefined && metadata.tags.length > 0 && (
+                <Table.Row align="center">
+                  <Table.RowHeaderCell>Tags</Table.RowHeaderCell>
+                  <Table.Cell>
+                    <Flex gap="1" wrap="wrap">
+                      {metadata.tags.map((tag) => (
+                        <Badge key={tag}>{tag}</Badge>
+                      ))}
+                    </Flex>
+                  </Table.Cell>
+                </Table.Row>
+              )}
               <Table.Row align="center">
                 <Table.RowHeaderCell>Entry</Table.RowHeaderCell>
                 <Table.Cell>{config.content.entry}</Table.Cell>
@@ -1,7 +1,19 @@
-import { Flex, IconButton } from "@radix-ui/themes";
+import { DropdownMenu, Flex, IconButton } from "@radix-ui/themes";
 import { memo, useCallback } from "react";
-import { LuFileScan, LuFolderOpen, LuRepeat } from "react-icons/lu";
-import { commands } from "../../../bindings";
+import {
+  LuFileScan,
+  LuFolderOpen,
+  LuFolderPlus,
+  LuRepeat,
+} from "react-icons/lu";
+import { toast } from "sonner";
+import { WidgetTemplate, commands } from "../../../bindings";
+
+const templates: { value: WidgetTemplate; label: string }[] = [
+  { value: "minimal", label: "Minimal (JSX)" },
+  { value: "tsx", label: "TypeScript with options" },
+  { value: "plugin", label: "Using plugins" },
+];
 
 interface GlobalActionsProps {
   length: number;
@@ -20,6 +32,17 @@ const GlobalActions = memo(({ length }: GlobalActionsProps) => {
     commands.core.openWidget(null).catch(console.error);
   }, []);
 
+  const createAction = useCallback((template: WidgetTemplate) => {
+    commands.core
+      .createWidget("New Widget", template)
+      .then((id) => {
+        toast.success(`Widget created: ${id}`);
+      })
+      .catch(() => {
+        toast.error("Failed to create widget.");
+      });
+  }, []);
+
   return (
     <Flex gap="6" align="center" justify="center" pb="2" pr="4">
       <IconButton
@@ -47,6 +70,20 @@ const GlobalActions = memo(({ length }: GlobalActionsProps) => {
       >
         <LuFolderOpen size="16" />
       </IconButton>
+      <DropdownMenu.Root>
+        <DropdownMenu.Trigger>
+          <IconButton title="Create a new widget" size="1" variant="ghost">
+            <LuFolderPlus size="16" />
+          </IconButton>
+        </DropdownMenu.Trigger>
+        <DropdownMenu.Content size="1">
+          {templates.map(({ value, label }) => (
+            <DropdownMenu.Item key={value} onSelect={() => createAction(value)}>
+              {label}
+            </DropdownMenu.Item>
+          ))}
+        </DropdownMenu.Content>
+      </DropdownMenu.Root>
     </Flex>
   );
 });
@@ -1,7 +1,8 @@
 import { Badge, Button, Flex } from "@radix-ui/themes";
 import { memo, useCallback } from "react";
-import { LuFolderOpen, LuRepeat } from "react-icons/lu";
-import { useWidgetsStore } from "../../hooks";
+import { LuDownload, LuFolderOpen, LuRepeat } from "react-icons/lu";
+import { toast } from "sonner";
+import { useWidgetUpdatesStore, useWidgetsStore } from "../../hooks";
 import { commands } from "../../../bindings";
 
 interface HeaderProps {
@@ -10,11 +11,23 @@ interface HeaderProps {
 
 const Header = memo(({ id }: HeaderProps) => {
   const type = useWidgetsStore((state) => state[id]?.type);
+  const latest = useWidgetUpdatesStore((state) => state[id]?.latest);
 
   const refreshAction = useCallback(() => {
     commands.core.bundleWidgets([id]).catch(console.error);
   }, [id]);
 
+  const updateAction = useCallback(() => {
+    commands.core
+      .updateWidget(id)
+      .then(() => {
+        toast.success("Widget updated.");
+      })
+      .catch(() => {
+        toast.error("Failed to update widget.");
+      });
+  }, [id]);
+
   const openAction = useCallback(() => {
     commands.core.openWidget(id).catch(console.error);
   }, [id]);
@@ -23,6 +36,16 @@ const Header = memo(({ id }: HeaderProps) => {
     <Flex align="center" justify="between">
       <Badge color={type === "ok" ? "gray" : "red"}>ID: {id}</Badge>
       <Flex align="center" gap="2">
+        {latest !== undefined && (
+          <Button
+            title={`Update this widget to version ${latest}`}
+            size="1"
+            variant="surface"
+            onClick={updateAction}
+          >
+            <LuDownload /> Update
+          </Button>
+        )}
         <Button
           title="Refresh this widget"
           size="1"
@@ -26,15 +26,21 @@ const styles = {
 
 interface TriggerProps {
   id: string;
+  group?: string;
   value: string;
 }
 
-const Trigger = memo(({ id, value }: TriggerProps) => {
+const Trigger = memo(({ id, group, value }: TriggerProps) => {
   const config = useWidgetsStore((state) => state[id]);
+  // Widgets with stable identifiers do not have their group in their IDs
+  const label =
+    group !== undefined && id.startsWith(`${group}/`)
+      ? id.slice(group.length + 1)
+      : id;
 
   return (
     <Tabs.Trigger value={value} css={styles.trigger}>
-      <Flex align="center" gap="3">
+      <Flex align="center" gap="3" pl={group === undefined ? "0" : "3"}>
         <Box
           width="6px"
           height="6px"
@@ -43,7 +49,7 @@ const Trigger = memo(({ id, value }: TriggerProps) => {
             config?.type !== "ok" && styles.indicatorInvalid,
           ]}
         />
-        <Text>{id}</Text>
+        <Text>{label}</Text>
       </Flex>
     </Tabs.Trigger>
   );
@@ -1,6 +1,6 @@
 import { Flex, ScrollArea, Separator, Tabs, Text } from "@radix-ui/themes";
-import { useWidgetsStore } from "../../hooks";
-import { memo } from "react";
+import { useWidgetGroupsStore, useWidgetsStore } from "../../hooks";
+import { Fragment, memo, useMemo } from "react";
 import { useShallow } from "zustand/shallow";
 import Trigger from "./Trigger";
 import GlobalActions from "./GlobalActions";
@@ -15,7 +15,31 @@ const styles = {
 };
 
 const WidgetsTab = memo(() => {
-  const ids = useWidgetsStore(useShallow((state) => Object.keys(state)));
+  const allIds = useWidgetsStore(useShallow((state) => Object.keys(state)));
+  const groups = useWidgetGroupsStore();
+
+  // Map each widget to its group, and list top-level widgets first followed by
+  // widgets in each group, so that widgets in the same group stay together
+  const groupOf = useMemo(
+    () =>
+      Object.fromEntries(
+        Object.entries(groups).flatMap(([group, members]) =>
+          members.map((id) => [id, group]),
+        ),
+      ) as Record<string, string>,
+    [groups],
+  );
+  const ids = useMemo(
+    () =>
+      [...allIds].sort((a, b) => {
+        const groupA = groupOf[a] ?? "";
+        const groupB = groupOf[b] ?? "";
+        return groupA === groupB
+          ? a.localeCompare(b)
+          : groupA.localeCompare(groupB);
+      }),
+    [allIds, groupOf],
+  );
 
   return (
     <Tabs.Root orientation="vertical" defaultValue="tab0" asChild>
@@ -24,9 +48,22 @@ const WidgetsTab = memo(() => {
           <Flex direction="column" width="100%" gap="4">
             <ScrollArea scrollbars="vertical" asChild>
               <Flex direction="column">
-                {ids.map((id, index) => (
-                  <Trigger key={id} id={id} value={`tab${index}`} />
-                ))}
+                {ids.map((id, index) => {
+                  const group = groupOf[id];
+                  const isFirstInGroup =
+                    group !== undefined &&
+                    (index === 0 || groupOf[ids[index - 1]!] !== group);
+                  return (
+                    <Fragment key={id}>
+                      {isFirstInGroup && (
+                        <Text size="1" color="gray" mt="2" ml="2">
+                          {group}/
+                        </Text>
+                      )}
+                      <Trigger id={id} group={group} value={`tab${index}`} />
+                    </Fragment>
+                  );
+                })}
               </Flex>
             </ScrollArea>
             <Separator size="4" />
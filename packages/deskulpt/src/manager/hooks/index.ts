@@ -1,5 +1,7 @@
 export * from "./useSettingsStore";
 export * from "./useInitialRescan";
 export * from "./useUpdateSettingsListener";
-export * from "./useUpdateWidgetCatalogListener";
+export * from "./useWidgetCatalogDiffListener";
 export * from "./useWidgetsStore";
+export * from "./useWidgetUpdatesListener";
+export * from "./useWidgetUpdatesStore";
@@ -1,8 +1,16 @@
 import { useEffect } from "react";
 import { commands } from "../../bindings";
+import { useSettingsStore } from "./useSettingsStore";
 
 export function useInitialRescan() {
   useEffect(() => {
-    commands.core.rescanWidgets().catch(console.error);
+    commands.core
+      .rescanWidgets()
+      .then(async () => {
+        if (useSettingsStore.getState().registryIndex !== null) {
+          await commands.core.checkWidgetUpdates();
+        }
+      })
+      .catch(console.error);
   }, []);
 }
@@ -0,0 +1,22 @@
+import { useEffect } from "react";
+import { events } from "../../bindings";
+import { useWidgetGroupsStore, useWidgetsStore } from "./useWidgetsStore";
+
+export function useWidgetCatalogDiffListener() {
+  useEffect(() => {
+    const unlisten = events.widgetCatalogDiff.listen((event) => {
+      const { added, removed, changed, groups } = event.payload;
+
+      useWidgetsStore.setState((state) => {
+        const widgets = { ...state, ...added, ...changed };
+        removed.forEach((id) => delete widgets[id]);
+        return widgets;
+      }, true);
+      useWidgetGroupsStore.setState(() => groups, true);
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(console.error);
+    };
+  }, []);
+}
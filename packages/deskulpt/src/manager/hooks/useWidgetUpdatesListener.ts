@@ -0,0 +1,15 @@
+import { useEffect } from "react";
+import { events } from "../../bindings";
+import { useWidgetUpdatesStore } from "./useWidgetUpdatesStore";
+
+export function useWidgetUpdatesListener() {
+  useEffect(() => {
+    const unlisten = events.widgetUpdates.listen((event) => {
+      useWidgetUpdatesStore.setState(() => event.payload, true);
+    });
+
+    return () => {
+      unlisten.then((f) => f()).catch(console.error);
+    };
+  }, []);
+}
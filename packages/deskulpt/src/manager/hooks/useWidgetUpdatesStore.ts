@@ -0,0 +1,4 @@
+import { create } from "zustand";
+import { WidgetUpdatesEvent } from "../../bindings";
+
+export const useWidgetUpdatesStore = create<WidgetUpdatesEvent>(() => ({}));
@@ -1,4 +1,8 @@
 import { create } from "zustand";
-import { WidgetCatalog } from "../../bindings";
+import { WidgetCatalogDiff } from "../../bindings";
 
-export const useWidgetsStore = create<WidgetCatalog>(() => ({}));
+export const useWidgetsStore = create<WidgetCatalogDiff["added"]>(() => ({}));
+
+export const useWidgetGroupsStore = create<WidgetCatalogDiff["groups"]>(
+  () => ({}),
+);